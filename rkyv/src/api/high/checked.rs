@@ -11,6 +11,7 @@ use crate::{
         access_pos_unchecked_mut, access_pos_with_context, access_with_context,
         check_pos_with_context, deserialize_using, root_position,
     },
+    checksum::verify_trailer,
     de::pooling::Pool,
     seal::Seal,
     validation::{
@@ -25,7 +26,18 @@ use crate::{
 pub type HighValidator<'a, E> =
     Strategy<Validator<ArchiveValidator<'a>, SharedValidator>, E>;
 
+// Immutable access allows aliased subtrees, like the ones written by
+// `Intern`. Mutable access doesn't, since it would allow mutating the same
+// bytes through multiple pointers.
 fn validator(bytes: &[u8]) -> Validator<ArchiveValidator<'_>, SharedValidator> {
+    let mut archive = ArchiveValidator::new(bytes);
+    archive.allow_aliasing();
+    Validator::new(archive, SharedValidator::new())
+}
+
+fn validator_mut(
+    bytes: &[u8],
+) -> Validator<ArchiveValidator<'_>, SharedValidator> {
     Validator::new(ArchiveValidator::new(bytes), SharedValidator::new())
 }
 
@@ -115,6 +127,58 @@ where
     access_with_context::<_, _, E>(bytes, &mut validator(bytes))
 }
 
+/// Verifies the checksum trailer of a byte slice and accesses the archive
+/// before it.
+///
+/// The trailer is written by a
+/// [`ChecksumWriter`](crate::ser::writer::ChecksumWriter). The checksum is
+/// verified before the archive is validated, so corruption is reported as a
+/// checksum mismatch instead of a validation error or wrong values. This is
+/// part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{access_checksummed, to_bytes_in},
+///     rancor::Error,
+///     ser::writer::ChecksumWriter,
+///     util::AlignedVec,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+///
+/// let writer = ChecksumWriter::new(AlignedVec::<16>::new());
+/// let writer = to_bytes_in::<_, Error>(&value, writer).unwrap();
+/// let mut bytes = writer.finish::<Error>().unwrap();
+///
+/// let archived =
+///     access_checksummed::<ArchivedExample, Error>(&bytes).unwrap();
+/// assert_eq!(archived.value, 31415926);
+///
+/// // Flip a bit in the value.
+/// let len = bytes.len();
+/// bytes[len - 9] ^= 0x10;
+/// assert!(access_checksummed::<ArchivedExample, Error>(&bytes).is_err());
+/// ```
+pub fn access_checksummed<T, E>(bytes: &[u8]) -> Result<&T, E>
+where
+    T: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source,
+{
+    access::<T, E>(verify_trailer(bytes)?)
+}
+
 /// Mutably access a byte slice with a given root position.
 ///
 /// This is a safe alternative to [`access_pos_unchecked_mut`] and is part of
@@ -161,7 +225,7 @@ where
     T: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
     E: Source,
 {
-    let mut context = validator(bytes);
+    let mut context = validator_mut(bytes);
     check_pos_with_context::<T, _, E>(bytes, pos, &mut context)?;
     unsafe { Ok(access_pos_unchecked_mut::<T>(bytes, pos)) }
 }
@@ -210,7 +274,7 @@ where
     T: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
     E: Source,
 {
-    let mut context = validator(bytes);
+    let mut context = validator_mut(bytes);
     let pos = root_position::<T>(bytes.len());
     check_pos_with_context::<T, _, E>(bytes, pos, &mut context)?;
     unsafe { Ok(access_pos_unchecked_mut::<T>(bytes, pos)) }
@@ -5,20 +5,31 @@
 
 #[cfg(feature = "bytecheck")]
 mod checked;
+#[cfg(feature = "rayon-1")]
+mod rayon;
+#[cfg(feature = "tokio-1")]
+mod tokio;
 
 use rancor::Strategy;
 
 #[cfg(feature = "bytecheck")]
 pub use self::checked::*;
+#[cfg(feature = "rayon-1")]
+pub use self::rayon::*;
+#[cfg(feature = "tokio-1")]
+pub use self::tokio::*;
 use crate::{
     access_unchecked,
-    api::{deserialize_using, serialize_using},
+    api::{deserialize_into_using, deserialize_using, serialize_using},
     de::Pool,
     ser::{
-        allocator::ArenaHandle, sharing::Share, Allocator, Serializer, Writer,
+        allocator::{ArenaHandle, LimitedArenaHandle},
+        sharing::Share,
+        writer::{LimitWriter, SizeCounter},
+        Allocator, Serializer, Writer,
     },
     util::{with_arena, AlignedVec},
-    Archive, Deserialize, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Serialize,
 };
 
 /// A high-level serializer.
@@ -73,6 +84,102 @@ where
     to_bytes_in(value, AlignedVec::new())
 }
 
+/// Serialize a value to canonical bytes.
+///
+/// Equal values always serialize to identical bytes, regardless of things like
+/// the iteration order of hash maps. This makes the bytes suitable for hashing
+/// and content addressing. See
+/// [`Serializer::new_canonical`](crate::ser::Serializer::new_canonical) for
+/// details.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use rkyv::{api::high::to_bytes_canonical, rancor::Error};
+///
+/// let a = (0..100).map(|i| (i, i * 2)).collect::<HashMap<u32, u32>>();
+/// let b = (0..100)
+///     .rev()
+///     .map(|i| (i, i * 2))
+///     .collect::<HashMap<_, _>>();
+///
+/// let a = to_bytes_canonical::<Error>(&a).unwrap();
+/// let b = to_bytes_canonical::<Error>(&b).unwrap();
+/// assert_eq!(a.as_slice(), b.as_slice());
+/// ```
+pub fn to_bytes_canonical<E>(
+    // rustfmt insists on inlining this parameter even though it exceeds the
+    // max line length
+    #[rustfmt::skip] value: &impl for<'a> Serialize<
+        HighSerializer<AlignedVec, ArenaHandle<'a>, E>,
+    >,
+) -> Result<AlignedVec, E>
+where
+    E: rancor::Source,
+{
+    with_arena(|arena| {
+        let mut serializer = Serializer::new_canonical(
+            AlignedVec::new(),
+            arena.acquire(),
+            Share::new(),
+        );
+        serialize_using(value, &mut serializer)?;
+        Ok(serializer.into_writer())
+    })
+}
+
+/// Serialize a value to bytes, failing if the archive would be larger than
+/// `limit` bytes.
+///
+/// Serialization stops at the first write which would exceed the limit, and
+/// fails with an [`ArchiveTooLarge`](crate::ser::writer::ArchiveTooLarge)
+/// error. The scratch space used while serializing can use all of the
+/// capacity the thread's arena already has, but can't grow the arena by more
+/// than `limit` bytes. The bytes are returned in an [`AlignedVec`].
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::to_bytes_with_limit, rancor::Error,
+///     ser::writer::ArchiveTooLarge,
+/// };
+///
+/// let value = "hello world".repeat(100);
+///
+/// let bytes = to_bytes_with_limit::<Error>(&value, 4096).unwrap();
+/// assert!(bytes.len() <= 4096);
+///
+/// let error = to_bytes_with_limit::<Error>(&value, 512).unwrap_err();
+/// let too_large = Error::inner(&error).downcast_ref::<ArchiveTooLarge>();
+/// assert_eq!(too_large.unwrap().limit, 512);
+/// ```
+pub fn to_bytes_with_limit<E>(
+    value: &impl for<'a> Serialize<
+        HighSerializer<LimitWriter<AlignedVec>, LimitedArenaHandle<'a>, E>,
+    >,
+    limit: usize,
+) -> Result<AlignedVec, E>
+where
+    E: rancor::Source,
+{
+    with_arena(|arena| {
+        let writer = LimitWriter::new(AlignedVec::new(), limit);
+        let bytes = to_bytes_in_with_alloc(
+            value,
+            writer,
+            arena.acquire_with_limit(limit),
+        )?;
+        Ok(bytes.into_inner())
+    })
+}
+
 /// Serialize a value and write the bytes to the given writer.
 ///
 /// This is part of the [high-level API](crate::api::high).
@@ -211,6 +318,53 @@ where
     Ok(serializer.into_writer())
 }
 
+/// Compute the size of the archive of a value without writing it.
+///
+/// The returned size is exact: serializing the same value again into a buffer
+/// of this length always fits.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use core::mem::MaybeUninit;
+///
+/// use rkyv::{
+///     api::high::to_bytes_in, archived_size, rancor::Error,
+///     ser::writer::Buffer, Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     name: String,
+///     values: Vec<u64>,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     values: vec![3, 1, 4, 1, 5, 9],
+/// };
+///
+/// let size = archived_size::<Error>(&value).unwrap();
+/// let mut bytes = vec![MaybeUninit::<u8>::uninit(); size];
+/// let buffer =
+///     to_bytes_in::<_, Error>(&value, Buffer::from(&mut *bytes)).unwrap();
+/// assert_eq!(buffer.len(), size);
+/// ```
+pub fn archived_size<E>(
+    // rustfmt insists on inlining this parameter even though it exceeds the
+    // max line length
+    #[rustfmt::skip] value: &impl for<'a> Serialize<
+        HighSerializer<SizeCounter, ArenaHandle<'a>, E>,
+    >,
+) -> Result<usize, E>
+where
+    E: rancor::Source,
+{
+    Ok(to_bytes_in(value, SizeCounter::new())?.len())
+}
+
 /// Deserialize a value from the given bytes.
 ///
 /// This function does not check that the data is valid. Use [`from_bytes`] to
@@ -295,19 +449,184 @@ pub fn deserialize<T, E>(
     deserialize_using(value, &mut Pool::new())
 }
 
+/// Deserialize a value into an existing value, reusing its allocations.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     access, api::high::deserialize_into, rancor::Error, to_bytes, Archive,
+///     Deserialize, DeserializeInPlace, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize, DeserializeInPlace)]
+/// struct Example {
+///     name: String,
+///     values: Vec<i32>,
+/// }
+///
+/// let mut target = Example {
+///     name: String::new(),
+///     values: Vec::new(),
+/// };
+///
+/// for i in 0..10 {
+///     let value = Example {
+///         name: format!("example {i}"),
+///         values: vec![i; 4],
+///     };
+///     let bytes = to_bytes::<Error>(&value).unwrap();
+///     let archived = access::<ArchivedExample, Error>(&*bytes).unwrap();
+///     deserialize_into::<Example, Error>(archived, &mut target).unwrap();
+///
+///     assert_eq!(target.name, format!("example {i}"));
+///     assert_eq!(target.values, [i; 4]);
+/// }
+/// ```
+pub fn deserialize_into<T, E>(
+    value: &impl DeserializeInPlace<T, HighDeserializer<E>>,
+    target: &mut T,
+) -> Result<(), E> {
+    deserialize_into_using(value, target, &mut Pool::new())
+}
+
 #[cfg(test)]
 mod tests {
-    use rancor::Panic;
+    use core::mem::MaybeUninit;
+
+    use rancor::{Failure, Panic};
 
     use crate::{
-        alloc::{string::ToString, vec::Vec},
-        api::high::to_bytes_in,
+        alloc::{
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        api::high::{archived_size, to_bytes, to_bytes_in},
+        ser::writer::Buffer,
+        Archive, Serialize,
     };
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn to_bytes_with_limit_after_to_bytes() {
+        use std::{collections::HashMap, thread};
+
+        use crate::{api::high::to_bytes_with_limit, util::with_arena};
+
+        // Hash maps use much more scratch space than the size of their archive
+        let map = (0..4000u32).map(|i| (i, ())).collect::<HashMap<_, _>>();
+        let strings = (0..4000).map(|i| i.to_string()).collect::<Vec<_>>();
+
+        // Each thread has its own arena, so this starts with an empty arena
+        let capacity = thread::scope(|s| {
+            s.spawn(|| {
+                to_bytes::<Panic>(&map).unwrap();
+                with_arena(|arena| arena.capacity())
+            })
+            .join()
+            .unwrap()
+        });
+        let limit = capacity + capacity / 16;
+
+        thread::scope(|s| {
+            s.spawn(|| {
+                // Leaves the arena with some capacity, but not enough for the
+                // map
+                to_bytes::<Panic>(&strings).unwrap();
+                assert!(with_arena(|arena| arena.capacity()) < capacity);
+
+                to_bytes_with_limit::<Failure>(&map, limit).unwrap();
+                assert!(to_bytes_with_limit::<Failure>(&map, 0).is_err());
+            });
+        });
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn canonical_shared_pointers_and_padding() {
+        use std::collections::HashMap;
+
+        use crate::{alloc::rc::Rc, api::test::assert_canonical, with::Intern};
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Example {
+            tag: u8,
+            names: HashMap<u32, Rc<String>>,
+            #[rkyv(with = Intern)]
+            label: String,
+            value: u64,
+        }
+
+        let example = |order: &mut dyn Iterator<Item = u32>| {
+            let shared = [
+                Rc::new("a shared string which is not inline".to_string()),
+                Rc::new("another shared string".to_string()),
+            ];
+            Example {
+                tag: 1,
+                names: order
+                    .map(|i| (i, shared[i as usize % 2].clone()))
+                    .collect(),
+                label: "an interned label which is not inline".to_string(),
+                value: 2,
+            }
+        };
+
+        let a = example(&mut (0..100));
+        let b = example(&mut (0..100).rev());
+        assert_canonical(&a, &b);
+
+        // The padding after the `u8` is zeroed
+        let bytes = to_bytes::<Panic>(&(1u8, 2u64)).unwrap();
+        let padding_end = core::mem::offset_of!(
+            crate::tuple::ArchivedTuple2<u8, crate::Archived<u64>>,
+            1
+        );
+        assert!(bytes[1..padding_end].iter().all(|&b| b == 0));
+    }
+
     #[test]
     fn to_bytes_in_vec() {
         let value = "hello world".to_string();
         let bytes = to_bytes_in::<_, Panic>(&value, Vec::new()).unwrap();
         assert!(!bytes.is_empty());
     }
+
+    #[test]
+    fn archived_size_fits_exactly() {
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Example {
+            a: u8,
+            names: Vec<String>,
+            b: u64,
+            c: Option<Vec<u16>>,
+        }
+
+        let value = Example {
+            a: 1,
+            names: vec![
+                "short".to_string(),
+                "a string which is too long to inline".to_string(),
+            ],
+            b: 2,
+            c: Some(vec![1, 2, 3]),
+        };
+
+        let size = archived_size::<Panic>(&value).unwrap();
+        assert_eq!(size, to_bytes::<Panic>(&value).unwrap().len());
+
+        let mut bytes = vec![MaybeUninit::<u8>::uninit(); size];
+        let buffer =
+            to_bytes_in::<_, Panic>(&value, Buffer::from(&mut *bytes)).unwrap();
+        assert_eq!(buffer.len(), size);
+
+        let mut bytes = vec![MaybeUninit::<u8>::uninit(); size - 1];
+        assert!(to_bytes_in::<_, Failure>(&value, Buffer::from(&mut *bytes))
+            .is_err());
+    }
 }
@@ -0,0 +1,286 @@
+use core::{hash::BuildHasherDefault, mem::MaybeUninit};
+
+use hashbrown::HashSet;
+use rancor::{Source, Strategy};
+use rayon_1::{current_num_threads, prelude::*};
+
+use crate::{
+    alloc::vec::Vec,
+    hash::FxHasher64,
+    ser::{
+        allocator::ArenaHandle, sharing::Share, Positional as _, Serializer,
+        Writer, WriterExt as _,
+    },
+    util::{with_arena, AlignedVec},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Place, Serialize,
+};
+
+/// A serializer for one chunk of a parallel serialization.
+///
+/// Each chunk shares pointers with [`Share`]. See [`to_bytes_par`] for how
+/// pointers shared by more than one chunk are handled.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub type ParSerializer<'a, E> =
+    Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, E>;
+
+// The archived root of a parallel serialization.
+struct Root<'a, T>(&'a [T]);
+
+impl<T: Archive> Archive for Root<'_, T> {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedVec::resolve_from_slice(self.0, resolver, out);
+    }
+}
+
+impl<T, E> Serialize<ParSerializer<'_, E>> for Root<'_, T>
+where
+    T: for<'a> Serialize<ParSerializer<'a, E>>,
+    E: Source,
+{
+    fn serialize(
+        &self,
+        serializer: &mut ParSerializer<'_, E>,
+    ) -> Result<Self::Resolver, E> {
+        ArchivedVec::serialize_from_slice(self.0, serializer)
+    }
+}
+
+// Chunks are serialized into `AlignedVec`s, which are aligned to 16 bytes.
+const CHUNK_ALIGN: usize = 16;
+
+struct Chunk<R> {
+    bytes: AlignedVec,
+    resolvers: Vec<R>,
+    share: Share,
+}
+
+// Returns whether any shared pointer was started by more than one chunk. Each
+// chunk starts a shared pointer at most once.
+fn shared_across_chunks<R>(chunks: &[Chunk<R>]) -> bool {
+    let mut seen = HashSet::<usize, BuildHasherDefault<FxHasher64>>::default();
+    chunks.iter().any(|chunk| {
+        chunk
+            .share
+            .shared_addresses()
+            .any(|address| !seen.insert(address))
+    })
+}
+
+fn serialize_chunk<T, E>(values: &[T]) -> Result<Chunk<T::Resolver>, E>
+where
+    T: for<'a> Serialize<ParSerializer<'a, E>>,
+    E: Source,
+{
+    with_arena(|arena| {
+        let mut serializer =
+            Serializer::new(AlignedVec::new(), arena.acquire(), Share::new());
+        let resolvers = values
+            .iter()
+            .map(|value| value.serialize(Strategy::wrap(&mut serializer)))
+            .collect::<Result<Vec<_>, E>>()?;
+        let (bytes, _, share) = serializer.into_raw_parts();
+        Ok(Chunk {
+            bytes,
+            resolvers,
+            share,
+        })
+    })
+}
+
+fn serialize_sequential<T, E>(values: &[T]) -> Result<AlignedVec, E>
+where
+    T: for<'a> Serialize<ParSerializer<'a, E>>,
+    E: Source,
+{
+    with_arena(|arena| {
+        let mut serializer =
+            Serializer::new(AlignedVec::new(), arena.acquire(), Share::new());
+        crate::api::serialize_using(&Root(values), &mut serializer)?;
+        Ok(serializer.into_writer())
+    })
+}
+
+/// Serialize a slice of values to bytes in parallel.
+///
+/// The slice is split into chunks, and the values in each chunk are
+/// serialized into a separate buffer on the rayon thread pool. The buffers are
+/// then concatenated and followed by the archived values. The result can be
+/// accessed as an archived `Vec<T>`.
+///
+/// Shared pointers are shared within each chunk. If the same shared pointer
+/// appears in more than one chunk, the values are serialized again on the
+/// current thread so that every shared pointer is serialized only once. The
+/// archive is equivalent to one produced by [`to_bytes`](super::to_bytes), but
+/// may contain extra padding between chunks.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::to_bytes_par, rancor::Error, Archive, Archived, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Record {
+///     name: String,
+///     values: Vec<u32>,
+/// }
+///
+/// let records = (0..10_000)
+///     .map(|i| Record {
+///         name: format!("record {i}"),
+///         values: vec![i; 4],
+///     })
+///     .collect::<Vec<_>>();
+///
+/// let bytes = to_bytes_par::<_, Error>(&records).unwrap();
+/// let archived =
+///     rkyv::access::<Archived<Vec<Record>>, Error>(&bytes).unwrap();
+/// assert_eq!(archived.len(), 10_000);
+/// assert_eq!(archived[1234].name, "record 1234");
+/// ```
+pub fn to_bytes_par<T, E>(values: &[T]) -> Result<AlignedVec, E>
+where
+    T: Sync + for<'a> Serialize<ParSerializer<'a, E>>,
+    T::Resolver: Send,
+    E: Source + Send,
+{
+    let chunk_len = values.len().div_ceil(4 * current_num_threads()).max(1);
+
+    let chunks = values
+        .par_chunks(chunk_len)
+        .map(serialize_chunk)
+        .collect::<Result<Vec<_>, E>>()?;
+    if shared_across_chunks(&chunks) {
+        return serialize_sequential(values);
+    }
+
+    let mut bytes = AlignedVec::new();
+    let writer = Strategy::<_, E>::wrap(&mut bytes);
+
+    let mut offsets = Vec::with_capacity(chunks.len());
+    for chunk in chunks.iter() {
+        offsets.push(writer.align(CHUNK_ALIGN)?);
+        writer.write(&chunk.bytes)?;
+    }
+
+    let pos = writer.align_for::<T::Archived>()?;
+    for ((values, chunk), offset) in
+        values.chunks(chunk_len).zip(chunks).zip(offsets)
+    {
+        for (value, resolver) in values.iter().zip(chunk.resolvers) {
+            // Relative pointers only depend on the distance between positions,
+            // so values can be resolved at their position relative to the
+            // start of their chunk.
+            let local_pos = writer.pos() - offset;
+            T::check_resolver(&resolver, local_pos)?;
+            let mut resolved = MaybeUninit::<T::Archived>::uninit();
+            // SAFETY: `resolved` is properly aligned and valid for writes of
+            // `size_of::<T::Archived>()` bytes.
+            unsafe {
+                resolved.as_mut_ptr().write_bytes(0, 1);
+            }
+            // SAFETY: `resolved.as_mut_ptr()` points to a local zeroed
+            // `MaybeUninit`, and so is properly aligned, dereferenceable, and
+            // all of its bytes are initialized.
+            let out = unsafe {
+                Place::new_unchecked(local_pos, resolved.as_mut_ptr())
+            };
+            value.resolve(resolver, out);
+            writer.write(out.as_slice())?;
+        }
+    }
+
+    writer.align_for::<ArchivedVec<T::Archived>>()?;
+    // SAFETY: `VecResolver::from_pos(pos)` is a valid resolver for the root
+    // because the archived values were written at `pos`, and the writer is
+    // aligned for the root.
+    unsafe {
+        writer.resolve_aligned(&Root(values), VecResolver::from_pos(pos))?;
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rancor::Panic;
+
+    use super::to_bytes_par;
+    use crate::{
+        alloc::{string::String, vec::Vec},
+        api::high::to_bytes,
+        Archive, Archived, Deserialize, Serialize,
+    };
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(crate)]
+    struct Record {
+        name: String,
+        values: Vec<u32>,
+    }
+
+    fn records() -> Vec<Record> {
+        (0..1000)
+            .map(|i| Record {
+                name: format!("a record which is not inline {i}"),
+                values: (0..i % 8).collect(),
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn par_roundtrip() {
+        let value = records();
+        let bytes = to_bytes_par::<_, Panic>(&value).unwrap();
+        let deserialized =
+            crate::from_bytes::<Vec<Record>, Panic>(&bytes).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    fn par_matches_sequential() {
+        let value = records();
+        let par = to_bytes_par::<_, Panic>(&value).unwrap();
+        let seq = to_bytes::<Panic>(&value).unwrap();
+        let par =
+            unsafe { crate::access_unchecked::<Archived<Vec<Record>>>(&par) };
+        let seq =
+            unsafe { crate::access_unchecked::<Archived<Vec<Record>>>(&seq) };
+        assert_eq!(par.len(), seq.len());
+        for (a, b) in par.iter().zip(seq.iter()) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.values, b.values);
+        }
+    }
+
+    #[test]
+    fn par_shared_across_chunks() {
+        let shared = Arc::new(String::from("a string shared by every chunk"));
+        let value = (0..1000).map(|_| shared.clone()).collect::<Vec<_>>();
+
+        let bytes = to_bytes_par::<_, Panic>(&value).unwrap();
+        let archived = unsafe {
+            crate::access_unchecked::<Archived<Vec<Arc<String>>>>(&bytes)
+        };
+        let deserialized =
+            crate::api::high::deserialize::<Vec<Arc<String>>, Panic>(archived)
+                .unwrap();
+        assert!(deserialized.iter().all(|s| **s == *shared));
+
+        // The first and last values are in different chunks.
+        assert!(Arc::ptr_eq(&deserialized[0], &deserialized[1]));
+        assert!(Arc::ptr_eq(&deserialized[0], &deserialized[999]));
+        assert_eq!(&*bytes, &*to_bytes::<Panic>(&value).unwrap());
+    }
+}
@@ -0,0 +1,113 @@
+use rancor::{ResultExt as _, Source};
+use tokio_1::io::{AsyncWrite, AsyncWriteExt as _};
+
+use crate::{
+    api::{high::HighSerializer, serialize_using},
+    ser::{
+        allocator::ArenaHandle, sharing::Share, writer::ChunkedWriter,
+        Allocator, Serializer,
+    },
+    util::with_arena,
+    Serialize,
+};
+
+/// Serialize a value and write the bytes to the given async writer.
+///
+/// The value is serialized into a [`ChunkedWriter`] which is then written to
+/// `writer` and flushed. Use [`ChunkedWriter`] with a [`Serializer`] directly
+/// to reuse chunks between archives or to customize the allocator and sharing
+/// strategy.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// # tokio_1::runtime::Builder::new_current_thread().build().unwrap()
+/// #     .block_on(async {
+/// use rkyv::{
+///     api::high::to_async_writer, from_bytes, rancor::Error,
+///     util::AlignedVec, Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+///
+/// let mut output = Vec::new();
+/// to_async_writer::<_, Error>(&value, &mut output)
+///     .await
+///     .unwrap();
+///
+/// let mut bytes = AlignedVec::<16>::new();
+/// bytes.extend_from_slice(&output);
+/// let deserialized = from_bytes::<Example, Error>(&bytes).unwrap();
+/// assert_eq!(deserialized, value);
+/// # });
+/// ```
+pub async fn to_async_writer<W, E>(
+    value: &impl for<'a> Serialize<
+        HighSerializer<ChunkedWriter, ArenaHandle<'a>, E>,
+    >,
+    writer: &mut W,
+) -> Result<(), E>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+    E: Source,
+{
+    let chunks = with_arena(|arena| {
+        serialize_into_chunks(value, ChunkedWriter::new(), arena.acquire())
+    })?;
+    write_chunks(chunks, writer).await
+}
+
+/// Serialize a value using the given allocator and write the bytes to the given
+/// async writer.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub async fn to_async_writer_with_alloc<W, A, E>(
+    value: &impl Serialize<HighSerializer<ChunkedWriter, A, E>>,
+    writer: &mut W,
+    alloc: A,
+) -> Result<(), E>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+    A: Allocator<E>,
+    E: Source,
+{
+    let chunks = serialize_into_chunks(value, ChunkedWriter::new(), alloc)?;
+    write_chunks(chunks, writer).await
+}
+
+fn serialize_into_chunks<A, E>(
+    value: &impl Serialize<HighSerializer<ChunkedWriter, A, E>>,
+    chunks: ChunkedWriter,
+    alloc: A,
+) -> Result<ChunkedWriter, E>
+where
+    A: Allocator<E>,
+    E: Source,
+{
+    let mut serializer = Serializer::new(chunks, alloc, Share::new());
+    serialize_using(value, &mut serializer)?;
+    Ok(serializer.into_writer())
+}
+
+async fn write_chunks<W, E>(
+    mut chunks: ChunkedWriter,
+    writer: &mut W,
+) -> Result<(), E>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+    E: Source,
+{
+    chunks.write_to(writer).await.into_error()?;
+    writer.flush().await.into_error()
+}
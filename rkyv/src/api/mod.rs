@@ -30,7 +30,10 @@ use rancor::Strategy;
 
 #[cfg(feature = "bytecheck")]
 pub use self::checked::*;
-use crate::{seal::Seal, ser::Writer, Deserialize, Portable, SerializeUnsized};
+use crate::{
+    seal::Seal, ser::Writer, Deserialize, DeserializeInPlace, Portable,
+    SerializeUnsized,
+};
 
 #[cfg(debug_assertions)]
 fn sanity_check_buffer<T: Portable>(ptr: *const u8, pos: usize, size: usize) {
@@ -414,3 +417,37 @@ pub fn deserialize_using<T, D, E>(
 ) -> Result<T, E> {
     value.deserialize(Strategy::wrap(deserializer))
 }
+
+/// Deserialize a value into an existing value using the given deserializer.
+///
+/// The allocations of the target are reused where possible. Most of the time,
+/// [`deserialize_into`](high::deserialize_into) is a more ergonomic way to
+/// deserialize into an existing value.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     access, api::deserialize_into_using, de::Pool, rancor::Error, to_bytes,
+/// };
+///
+/// let bytes = to_bytes::<Error>(&vec![1, 2, 3]).unwrap();
+/// let archived = access::<rkyv::Archived<Vec<i32>>, Error>(&bytes).unwrap();
+///
+/// let mut target = Vec::<i32>::with_capacity(16);
+/// deserialize_into_using::<_, _, Error>(
+///     archived,
+///     &mut target,
+///     &mut Pool::new(),
+/// )
+/// .unwrap();
+/// assert_eq!(target, [1, 2, 3]);
+/// assert_eq!(target.capacity(), 16);
+/// ```
+pub fn deserialize_into_using<T, D, E>(
+    value: &impl DeserializeInPlace<T, Strategy<D, E>>,
+    target: &mut T,
+    deserializer: &mut D,
+) -> Result<(), E> {
+    value.deserialize_into(target, Strategy::wrap(deserializer))
+}
@@ -5,7 +5,7 @@ use crate::{
     de::Pool,
     ser::allocator::ArenaHandle,
     util::AlignedVec,
-    Deserialize, Serialize,
+    Deserialize, DeserializeInPlace, Serialize,
 };
 
 /// The serializer type for tests.
@@ -30,3 +30,29 @@ pub fn deserialize<T>(value: &impl Deserialize<T, TestDeserializer>) -> T {
     crate::api::deserialize_using::<T, _, Panic>(value, &mut Pool::new())
         .expect("failed to deserialize value")
 }
+
+/// Deserializes the given value into the target using the test deserializer.
+pub fn deserialize_into<T>(
+    value: &impl DeserializeInPlace<T, TestDeserializer>,
+    target: &mut T,
+) {
+    crate::api::deserialize_into_using::<T, _, Panic>(
+        value,
+        target,
+        &mut Pool::new(),
+    )
+    .expect("failed to deserialize value")
+}
+
+/// Asserts that the given values serialize to identical bytes with a canonical
+/// serializer.
+pub fn assert_canonical<T>(a: &T, b: &T)
+where
+    T: for<'a> Serialize<TestSerializer<'a>>,
+{
+    let a = crate::api::high::to_bytes_canonical(a)
+        .expect("failed to serialize value");
+    let b = crate::api::high::to_bytes_canonical(b)
+        .expect("failed to serialize value");
+    assert_eq!(*a, *b, "equal values serialized to different bytes");
+}
@@ -6,7 +6,7 @@ use crate::{
     api::low::{to_bytes_in_with_alloc, LowDeserializer, LowSerializer},
     ser::{allocator::SubAllocator, writer::Buffer},
     util::Align,
-    Deserialize, Serialize,
+    Deserialize, DeserializeInPlace, Serialize,
 };
 
 /// The serializer type for tests.
@@ -39,3 +39,12 @@ pub fn deserialize<T>(value: &impl Deserialize<T, TestDeserializer>) -> T {
     crate::api::deserialize_using::<T, _, Panic>(value, &mut ())
         .expect("failed to deserialize value")
 }
+
+/// Deserializes the given value into the target using the test deserializer.
+pub fn deserialize_into<T>(
+    value: &impl DeserializeInPlace<T, TestDeserializer>,
+    target: &mut T,
+) {
+    crate::api::deserialize_into_using::<T, _, Panic>(value, target, &mut ())
+        .expect("failed to deserialize value")
+}
@@ -6,14 +6,19 @@ use munge::munge;
 use rancor::Fallible;
 
 use crate::{
-    primitive::FixedUsize, seal::Seal, traits::ArchivePointee, ArchiveUnsized,
-    Place, Portable, RelPtr, SerializeUnsized,
+    primitive::ArchivedIsize,
+    rel_ptr::{Offset, RelPtr},
+    seal::Seal,
+    traits::ArchivePointee,
+    ArchiveUnsized, Place, Portable, SerializeUnsized,
 };
 
 /// An archived [`Box`].
 ///
 /// This is a thin `#[repr(transparent)]` wrapper around a [`RelPtr`] to the
-/// archived type.
+/// archived type. The offset type `O` of the relative pointer defaults to
+/// [`ArchivedIsize`], see [`WideOffset`](crate::with::WideOffset) and
+/// [`NarrowOffset`](crate::with::NarrowOffset) for other offset types.
 #[derive(Portable)]
 #[rkyv(crate)]
 #[cfg_attr(
@@ -22,11 +27,11 @@ use crate::{
     bytecheck(verify)
 )]
 #[repr(transparent)]
-pub struct ArchivedBox<T: ArchivePointee + ?Sized> {
-    ptr: RelPtr<T>,
+pub struct ArchivedBox<T: ArchivePointee + ?Sized, O = ArchivedIsize> {
+    ptr: RelPtr<T, O>,
 }
 
-impl<T: ArchivePointee + ?Sized> ArchivedBox<T> {
+impl<T: ArchivePointee + ?Sized, O: Offset> ArchivedBox<T, O> {
     /// Returns a reference to the value of this archived box.
     pub fn get(&self) -> &T {
         unsafe { &*self.ptr.as_ptr() }
@@ -47,6 +52,19 @@ impl<T: ArchivePointee + ?Sized> ArchivedBox<T> {
         Self::resolve_from_raw_parts(resolver, value.archived_metadata(), out)
     }
 
+    /// Resolves an archived box from a [`BoxResolver`] and the raw metadata
+    /// directly.
+    pub fn resolve_from_raw_parts(
+        resolver: BoxResolver,
+        metadata: T::ArchivedMetadata,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedBox { ptr } = out);
+        RelPtr::emplace_unsized(resolver.pos, metadata, ptr);
+    }
+}
+
+impl<T: ArchivePointee + ?Sized> ArchivedBox<T> {
     /// Serializes an archived box from the given value and serializer.
     pub fn serialize_from_ref<U, S>(
         value: &U,
@@ -57,44 +75,35 @@ impl<T: ArchivePointee + ?Sized> ArchivedBox<T> {
         S: Fallible + ?Sized,
     {
         Ok(BoxResolver {
-            pos: value.serialize_unsized(serializer)? as FixedUsize,
+            pos: value.serialize_unsized(serializer)?,
         })
     }
-
-    /// Resolves an archived box from a [`BoxResolver`] and the raw metadata
-    /// directly.
-    pub fn resolve_from_raw_parts(
-        resolver: BoxResolver,
-        metadata: T::ArchivedMetadata,
-        out: Place<Self>,
-    ) {
-        munge!(let ArchivedBox { ptr } = out);
-        RelPtr::emplace_unsized(resolver.pos as usize, metadata, ptr);
-    }
 }
 
-impl<T: ArchivePointee + ?Sized> AsRef<T> for ArchivedBox<T> {
+impl<T: ArchivePointee + ?Sized, O: Offset> AsRef<T> for ArchivedBox<T, O> {
     fn as_ref(&self) -> &T {
         self.get()
     }
 }
 
-impl<T: ArchivePointee + ?Sized> Borrow<T> for ArchivedBox<T> {
+impl<T: ArchivePointee + ?Sized, O: Offset> Borrow<T> for ArchivedBox<T, O> {
     fn borrow(&self) -> &T {
         self.get()
     }
 }
 
-impl<T: ArchivePointee + ?Sized> fmt::Debug for ArchivedBox<T>
+impl<T, O> fmt::Debug for ArchivedBox<T, O>
 where
+    T: ArchivePointee + ?Sized,
     T::ArchivedMetadata: fmt::Debug,
+    O: Offset + fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("ArchivedBox").field(&self.ptr).finish()
     }
 }
 
-impl<T: ArchivePointee + ?Sized> Deref for ArchivedBox<T> {
+impl<T: ArchivePointee + ?Sized, O: Offset> Deref for ArchivedBox<T, O> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -102,43 +111,57 @@ impl<T: ArchivePointee + ?Sized> Deref for ArchivedBox<T> {
     }
 }
 
-impl<T: ArchivePointee + fmt::Display + ?Sized> fmt::Display
-    for ArchivedBox<T>
+impl<T, O> fmt::Display for ArchivedBox<T, O>
+where
+    T: ArchivePointee + fmt::Display + ?Sized,
+    O: Offset,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.get().fmt(f)
     }
 }
 
-impl<T: ArchivePointee + Eq + ?Sized> Eq for ArchivedBox<T> {}
+impl<T: ArchivePointee + Eq + ?Sized, O: Offset> Eq for ArchivedBox<T, O> {}
 
-impl<T: ArchivePointee + hash::Hash + ?Sized> hash::Hash for ArchivedBox<T> {
+impl<T, O> hash::Hash for ArchivedBox<T, O>
+where
+    T: ArchivePointee + hash::Hash + ?Sized,
+    O: Offset,
+{
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         self.get().hash(state);
     }
 }
 
-impl<T: ArchivePointee + Ord + ?Sized> Ord for ArchivedBox<T> {
+impl<T: ArchivePointee + Ord + ?Sized, O: Offset> Ord for ArchivedBox<T, O> {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.as_ref().cmp(other.as_ref())
     }
 }
 
-impl<T: ArchivePointee + PartialEq<U> + ?Sized, U: ArchivePointee + ?Sized>
-    PartialEq<ArchivedBox<U>> for ArchivedBox<T>
+impl<T, U, O, P> PartialEq<ArchivedBox<U, P>> for ArchivedBox<T, O>
+where
+    T: ArchivePointee + PartialEq<U> + ?Sized,
+    U: ArchivePointee + ?Sized,
+    O: Offset,
+    P: Offset,
 {
-    fn eq(&self, other: &ArchivedBox<U>) -> bool {
+    fn eq(&self, other: &ArchivedBox<U, P>) -> bool {
         self.get().eq(other.get())
     }
 }
 
-impl<T: ArchivePointee + PartialOrd + ?Sized> PartialOrd for ArchivedBox<T> {
+impl<T, O> PartialOrd for ArchivedBox<T, O>
+where
+    T: ArchivePointee + PartialOrd + ?Sized,
+    O: Offset,
+{
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         self.get().partial_cmp(other.get())
     }
 }
 
-impl<T: ArchivePointee + ?Sized> fmt::Pointer for ArchivedBox<T> {
+impl<T: ArchivePointee + ?Sized, O: Offset> fmt::Pointer for ArchivedBox<T, O> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let ptr = self.get() as *const T;
         fmt::Pointer::fmt(&ptr, f)
@@ -147,7 +170,7 @@ impl<T: ArchivePointee + ?Sized> fmt::Pointer for ArchivedBox<T> {
 
 /// The resolver for `Box`.
 pub struct BoxResolver {
-    pos: FixedUsize,
+    pos: usize,
 }
 
 impl BoxResolver {
@@ -156,9 +179,13 @@ impl BoxResolver {
     /// In most cases, you won't need to create a [`BoxResolver`] yourself and
     /// can instead obtain it through [`ArchivedBox::serialize_from_ref`].
     pub fn from_pos(pos: usize) -> Self {
-        Self {
-            pos: pos as FixedUsize,
-        }
+        Self { pos }
+    }
+
+    /// Returns the position of the serialized value.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
     }
 }
 
@@ -171,22 +198,21 @@ mod verify {
 
     use crate::{
         boxed::ArchivedBox,
+        rel_ptr::Offset,
         traits::{ArchivePointee, LayoutRaw},
         validation::{ArchiveContext, ArchiveContextExt},
     };
 
-    unsafe impl<T, C> Verify<C> for ArchivedBox<T>
+    unsafe impl<T, O, C> Verify<C> for ArchivedBox<T, O>
     where
         T: ArchivePointee + CheckBytes<C> + LayoutRaw + ?Sized,
+        O: Offset,
         T::ArchivedMetadata: CheckBytes<C>,
         C: Fallible + ArchiveContext + ?Sized,
         C::Error: Source,
     {
         fn verify(&self, context: &mut C) -> Result<(), C::Error> {
-            let ptr = self.ptr.as_ptr_wrapping();
-            context.in_subtree(ptr, |context| unsafe {
-                T::check_bytes(ptr, context)
-            })
+            context.check_aliasable_subtree(self.ptr.as_ptr_wrapping())
         }
     }
 }
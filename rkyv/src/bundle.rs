@@ -0,0 +1,307 @@
+//! Combining existing archives without reserializing them.
+//!
+//! Relative pointers only depend on the distance between positions, so an
+//! archive can be copied into a larger buffer and remain valid as long as its
+//! alignment is preserved. An [`ArchiveBundler`] copies archives into one
+//! buffer and writes a root which points at each of them. The bundled archive
+//! can be accessed as an `ArchivedVec<ArchivedBox<T>>`.
+//!
+//! Archives can also be added to a multi-root archive by name with
+//! [`MultiRootBuilder::add_archive`].
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     boxed::ArchivedBox, bundle::ArchiveBundler, rancor::Error, to_bytes,
+//!     vec::ArchivedVec, Archive, Archived, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! struct Message {
+//!     id: u32,
+//!     body: String,
+//! }
+//!
+//! let mut bundler = ArchiveBundler::<ArchivedMessage>::new();
+//! for id in 0..3 {
+//!     let message = Message {
+//!         id,
+//!         body: format!("message number {id}"),
+//!     };
+//!     let bytes = to_bytes::<Error>(&message).unwrap();
+//!     bundler.push::<Error>(&bytes).unwrap();
+//! }
+//! let bytes = bundler.finish::<Error>().unwrap();
+//!
+//! let messages = rkyv::access::<
+//!     ArchivedVec<ArchivedBox<ArchivedMessage>>,
+//!     Error,
+//! >(&bytes)
+//! .unwrap();
+//! assert_eq!(messages.len(), 3);
+//! assert_eq!(messages[2].id, 2);
+//! assert_eq!(messages[2].body, "message number 2");
+//! ```
+//!
+//! [`MultiRootBuilder::add_archive`]:
+//! crate::multi_root::MultiRootBuilder::add_archive
+
+use core::{fmt, marker::PhantomData};
+
+use rancor::{Fallible, Source};
+
+use crate::{
+    alloc::vec::Vec,
+    api::root_position,
+    boxed::{ArchivedBox, BoxResolver},
+    util::AlignedVec,
+    Archive, Place, Portable, Serialize,
+};
+
+/// Copies an archive into a writer and returns the position of its root.
+///
+/// The archive is copied to a position aligned to
+/// [`AlignedVec::ALIGNMENT`], so the alignment of every value in it is
+/// preserved.
+pub(crate) fn append_archive<T: Portable>(
+    writer: &mut AlignedVec,
+    bytes: &[u8],
+) -> usize {
+    let start = writer.len().next_multiple_of(<AlignedVec>::ALIGNMENT);
+    writer.resize(start, 0);
+    writer.extend_from_slice(bytes);
+    start + root_position::<T>(bytes.len())
+}
+
+/// Checks the archive which was just appended to a writer, and removes it from
+/// the writer if it is invalid.
+#[cfg(feature = "bytecheck")]
+pub(crate) fn check_appended<T, E>(
+    writer: &mut AlignedVec,
+    prev_len: usize,
+    len: usize,
+) -> Result<(), E>
+where
+    T: Portable
+        + for<'a> bytecheck::CheckBytes<crate::api::high::HighValidator<'a, E>>,
+    E: Source,
+{
+    let start = writer.len() - len;
+    let result = crate::api::high::access_pos::<T, E>(
+        &writer.as_slice()[start..],
+        root_position::<T>(len),
+    );
+    if let Err(e) = result {
+        writer.resize(prev_len, 0);
+        return Err(e);
+    }
+    Ok(())
+}
+
+struct BundledRoot<T> {
+    pos: usize,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: Portable> Archive for BundledRoot<T> {
+    type Archived = ArchivedBox<T>;
+    type Resolver = ();
+
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedBox::resolve_from_raw_parts(
+            BoxResolver::from_pos(self.pos),
+            (),
+            out,
+        );
+    }
+}
+
+impl<T: Portable, S: Fallible + ?Sized> Serialize<S> for BundledRoot<T> {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+/// Combines existing archives of the same type into one archive without
+/// reserializing them.
+///
+/// `T` is the archived root type of the bundled archives. Each archive is
+/// copied into the bundle with its alignment preserved. The finished bundle
+/// can be accessed as an `ArchivedVec<ArchivedBox<T>>`. See the
+/// [module docs](crate::bundle) for an example.
+///
+/// Archives are aligned to [`AlignedVec::ALIGNMENT`], so archived types with a
+/// greater alignment can't be bundled.
+pub struct ArchiveBundler<T> {
+    bytes: AlignedVec,
+    roots: Vec<BundledRoot<T>>,
+}
+
+impl<T: Portable> Default for ArchiveBundler<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for ArchiveBundler<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchiveBundler")
+            .field("len", &self.roots.len())
+            .field("bytes", &self.bytes.len())
+            .finish()
+    }
+}
+
+impl<T: Portable> ArchiveBundler<T> {
+    /// Creates a new empty `ArchiveBundler`.
+    pub fn new() -> Self {
+        Self {
+            bytes: AlignedVec::new(),
+            roots: Vec::new(),
+        }
+    }
+
+    /// Returns the number of archives which have been added.
+    pub fn len(&self) -> usize {
+        self.roots.len()
+    }
+
+    /// Returns whether no archives have been added.
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Validates an archive and adds it to the bundle.
+    ///
+    /// The archive does not need to be aligned. Returns an error and leaves
+    /// the bundle unchanged if the archive is invalid.
+    #[cfg(feature = "bytecheck")]
+    pub fn push<E>(&mut self, bytes: &[u8]) -> Result<(), E>
+    where
+        T: for<'a> bytecheck::CheckBytes<
+            crate::api::high::HighValidator<'a, E>,
+        >,
+        E: Source,
+    {
+        let prev_len = self.bytes.len();
+        let pos = append_archive::<T>(&mut self.bytes, bytes);
+        check_appended::<T, E>(&mut self.bytes, prev_len, bytes.len())?;
+        self.push_root(pos);
+        Ok(())
+    }
+
+    /// Adds an archive to the bundle without validating it.
+    ///
+    /// # Safety
+    ///
+    /// The bytes must pass validation when passed to
+    /// [`access`](crate::access) as a `T`, except that they don't need to be
+    /// aligned.
+    pub unsafe fn push_unchecked(&mut self, bytes: &[u8]) {
+        let pos = append_archive::<T>(&mut self.bytes, bytes);
+        self.push_root(pos);
+    }
+
+    fn push_root(&mut self, pos: usize) {
+        self.roots.push(BundledRoot {
+            pos,
+            _phantom: PhantomData,
+        });
+    }
+
+    /// Writes the root of the bundle and returns its bytes.
+    pub fn finish<E: Source>(self) -> Result<AlignedVec, E> {
+        crate::api::high::to_bytes_in::<_, E>(&self.roots, self.bytes)
+    }
+}
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use rancor::{Error, Panic};
+
+    use super::ArchiveBundler;
+    use crate::{
+        alloc::{format, string::String, vec, vec::Vec},
+        boxed::ArchivedBox,
+        to_bytes,
+        vec::ArchivedVec,
+        Archive, Archived, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate)]
+    struct Record {
+        name: String,
+        values: Vec<u16>,
+    }
+
+    #[test]
+    fn bundle_archives() {
+        let mut bundler = ArchiveBundler::<ArchivedRecord>::new();
+        for i in 0..10 {
+            let record = Record {
+                name: format!("a record which is not inline {i}"),
+                values: vec![i; i as usize],
+            };
+            let bytes = to_bytes::<Panic>(&record).unwrap();
+            // Push the archives at an odd offset so that they aren't aligned
+            let mut unaligned = vec![0u8];
+            unaligned.extend_from_slice(&bytes);
+            bundler.push::<Panic>(&unaligned[1..]).unwrap();
+        }
+        assert_eq!(bundler.len(), 10);
+        let bytes = bundler.finish::<Panic>().unwrap();
+
+        let records = crate::access::<
+            ArchivedVec<ArchivedBox<ArchivedRecord>>,
+            Panic,
+        >(&bytes)
+        .unwrap();
+        assert_eq!(records.len(), 10);
+        for (i, record) in records.iter().enumerate() {
+            assert_eq!(
+                record.name,
+                format!("a record which is not inline {i}")
+            );
+            assert_eq!(record.values.len(), i);
+            assert!(record.values.iter().all(|v| *v == i as u16));
+        }
+    }
+
+    #[test]
+    fn bundle_rejects_invalid() {
+        let mut bundler = ArchiveBundler::<Archived<Vec<u32>>>::new();
+        let bytes = to_bytes::<Error>(&vec![1u32, 2, 3]).unwrap();
+        bundler.push::<Error>(&bytes).unwrap();
+        let len = bundler.bytes.len();
+
+        let mut invalid = to_bytes::<Error>(&vec![4u32, 5]).unwrap();
+        let last = invalid.len() - 1;
+        invalid[last] = 0xff;
+        assert!(bundler.push::<Error>(&invalid).is_err());
+        assert_eq!(bundler.bytes.len(), len);
+        assert_eq!(bundler.len(), 1);
+
+        let bytes = bundler.finish::<Error>().unwrap();
+        let vecs = crate::access::<
+            ArchivedVec<ArchivedBox<Archived<Vec<u32>>>>,
+            Error,
+        >(&bytes)
+        .unwrap();
+        assert_eq!(vecs.len(), 1);
+        assert_eq!(vecs[0].as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn bundle_empty() {
+        let bundler = ArchiveBundler::<Archived<u32>>::new();
+        let bytes = bundler.finish::<Panic>().unwrap();
+        let values = crate::access::<
+            ArchivedVec<ArchivedBox<Archived<u32>>>,
+            Panic,
+        >(&bytes)
+        .unwrap();
+        assert!(values.is_empty());
+    }
+}
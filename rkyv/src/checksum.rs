@@ -0,0 +1,523 @@
+//! Integrity checksums for archives.
+//!
+//! Archives can be protected against bit rot in two ways:
+//!
+//! - A checksum trailer covering the whole archive, written by a
+//!   [`ChecksumWriter`](crate::ser::writer::ChecksumWriter) and verified by
+//!   [`verify_trailer`] or
+//!   [`access_checksummed`](crate::api::high::access_checksummed).
+//! - A checksum for a single subtree, written by
+//!   [`Checksummed`](crate::with::Checksummed). Only the subtrees which are
+//!   read need to be verified.
+//!
+//! Both use [CRC-32C](Crc32c).
+
+#[cfg(feature = "alloc")]
+use core::{
+    alloc::Layout, any::TypeId, hash::BuildHasherDefault, ptr::NonNull,
+};
+use core::{error::Error, fmt, mem::size_of, slice};
+
+#[cfg(feature = "alloc")]
+use hashbrown::HashMap;
+use munge::munge;
+#[cfg(feature = "alloc")]
+use rancor::Fallible;
+use rancor::{fail, Source};
+
+#[cfg(feature = "alloc")]
+use crate::{
+    hash::FxHasher64,
+    ser::{sharing::SharingState, Allocator, Interning, Positional, Sharing},
+    Archive, Serialize,
+};
+use crate::{
+    primitive::{ArchivedU32, ArchivedUsize, FixedUsize},
+    ser::{Writer, WriterExt as _},
+    Place, Portable, RelPtr,
+};
+
+/// The size of the checksum trailer appended to an archive.
+pub const TRAILER_SIZE: usize = 8;
+
+const MAGIC: [u8; 4] = *b"rkyc";
+
+// The alignment of a checksummed subtree written from bytes. This matches the
+// alignment of an `AlignedVec`, which those subtrees are serialized into.
+const SUBTREE_ALIGN: usize = 16;
+
+const POLYNOMIAL: u32 = 0x82f6_3b78;
+
+const TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// An incremental CRC-32C (Castagnoli) checksum.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::checksum::Crc32c;
+///
+/// let mut crc = Crc32c::new();
+/// crc.update(b"1234");
+/// crc.update(b"56789");
+/// assert_eq!(crc.finish(), 0xe306_9283);
+/// assert_eq!(Crc32c::checksum(b"123456789"), 0xe306_9283);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Crc32c {
+    state: u32,
+}
+
+impl Default for Crc32c {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crc32c {
+    /// Creates a new checksum with no bytes.
+    pub const fn new() -> Self {
+        Self { state: !0 }
+    }
+
+    /// Returns the checksum of the given bytes.
+    pub fn checksum(bytes: &[u8]) -> u32 {
+        let mut crc = Self::new();
+        crc.update(bytes);
+        crc.finish()
+    }
+
+    /// Adds the given bytes to the checksum.
+    pub fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            let index = (self.state ^ byte as u32) & 0xff;
+            self.state = TABLE[index as usize] ^ (self.state >> 8);
+        }
+    }
+
+    /// Returns the checksum of the bytes added so far.
+    pub fn finish(&self) -> u32 {
+        !self.state
+    }
+}
+
+#[derive(Debug)]
+struct MissingTrailer;
+
+impl fmt::Display for MissingTrailer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "archive does not end with a checksum trailer")
+    }
+}
+
+impl Error for MissingTrailer {}
+
+#[derive(Debug)]
+struct ChecksumMismatch {
+    expected: u32,
+    actual: u32,
+}
+
+impl fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "checksum mismatch: expected {:#010x} but found {:#010x}",
+            self.expected, self.actual,
+        )
+    }
+}
+
+impl Error for ChecksumMismatch {}
+
+fn check<E: Source>(bytes: &[u8], expected: u32) -> Result<(), E> {
+    let actual = Crc32c::checksum(bytes);
+    if actual != expected {
+        fail!(ChecksumMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+pub(crate) fn trailer(checksum: u32) -> [u8; TRAILER_SIZE] {
+    let mut trailer = [0; TRAILER_SIZE];
+    trailer[0..4].copy_from_slice(&checksum.to_le_bytes());
+    trailer[4..8].copy_from_slice(&MAGIC);
+    trailer
+}
+
+/// Verifies the checksum trailer at the end of the given bytes and returns
+/// the archive without the trailer.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::to_bytes_in, checksum::verify_trailer, rancor::Error,
+///     ser::writer::ChecksumWriter, util::AlignedVec,
+/// };
+///
+/// let writer = ChecksumWriter::new(AlignedVec::<16>::new());
+/// let writer = to_bytes_in::<_, Error>(&42u32, writer).unwrap();
+/// let mut bytes = writer.finish::<Error>().unwrap();
+///
+/// assert_eq!(verify_trailer::<Error>(&bytes).unwrap().len(), 4);
+///
+/// bytes[0] ^= 1;
+/// assert!(verify_trailer::<Error>(&bytes).is_err());
+/// ```
+pub fn verify_trailer<E: Source>(bytes: &[u8]) -> Result<&[u8], E> {
+    let Some(len) = bytes.len().checked_sub(TRAILER_SIZE) else {
+        fail!(MissingTrailer);
+    };
+    let (archive, trailer) = bytes.split_at(len);
+    if trailer[4..8] != MAGIC {
+        fail!(MissingTrailer);
+    }
+
+    let expected = u32::from_le_bytes(trailer[0..4].try_into().unwrap());
+    check(archive, expected)?;
+    Ok(archive)
+}
+
+/// An archived value with a checksum of its subtree.
+///
+/// The subtree is laid out contiguously, ends with the archived value, and
+/// contains everything the value points to. Validation checks that the value
+/// doesn't point to any bytes outside of the subtree. Accessing the value with
+/// [`get`](Self::get) does not verify the checksum; use
+/// [`get_verified`](Self::get_verified) or [`verify`](Self::verify) to check it
+/// first.
+///
+/// This is the archived form of [`Checksummed`](crate::with::Checksummed).
+#[derive(Portable)]
+#[rkyv(crate)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[repr(C)]
+pub struct ArchivedChecksummed<T> {
+    ptr: RelPtr<T>,
+    len: ArchivedUsize,
+    checksum: ArchivedU32,
+}
+
+impl<T> ArchivedChecksummed<T> {
+    /// Returns a reference to the value without verifying the checksum.
+    pub fn get(&self) -> &T {
+        unsafe { &*self.ptr.as_ptr() }
+    }
+
+    /// Verifies the checksum and returns a reference to the value.
+    pub fn get_verified<E: Source>(&self) -> Result<&T, E> {
+        self.verify()?;
+        Ok(self.get())
+    }
+
+    /// Verifies the checksum of the subtree.
+    pub fn verify<E: Source>(&self) -> Result<(), E> {
+        check(self.as_bytes(), self.checksum())
+    }
+
+    /// Returns the stored checksum of the subtree.
+    pub fn checksum(&self) -> u32 {
+        self.checksum.to_native()
+    }
+
+    /// Returns the bytes of the subtree.
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = self.len.to_native() as usize;
+        unsafe {
+            let end = self.ptr.as_ptr().cast::<u8>().add(size_of::<T>());
+            slice::from_raw_parts(end.sub(len), len)
+        }
+    }
+
+    /// Serializes a value as a checksummed subtree and returns a resolver for
+    /// it.
+    ///
+    /// The value is serialized through a [`ChecksumSerializer`] which wraps
+    /// `serializer`.
+    #[cfg(feature = "alloc")]
+    pub fn serialize_from_value<U, S>(
+        value: &U,
+        serializer: &mut S,
+    ) -> Result<ChecksummedResolver, S::Error>
+    where
+        U: Archive<Archived = T> + for<'a> Serialize<ChecksumSerializer<'a, S>>,
+        S: Fallible + Writer + ?Sized,
+        S::Error: Source,
+    {
+        let mut subtree = ChecksumSerializer::new(serializer);
+        let resolver = value.serialize(&mut subtree)?;
+        subtree.align_for::<T>()?;
+        // SAFETY: `resolver` is the result of serializing `value`, and the
+        // serializer is aligned for a `T`.
+        let pos = unsafe { subtree.resolve_aligned(value, resolver)? };
+        Ok(ChecksummedResolver {
+            pos,
+            len: subtree.pos() - subtree.start,
+            checksum: subtree.checksum(),
+        })
+    }
+
+    /// Writes a serialized subtree and returns a resolver for it.
+    ///
+    /// `bytes` must be a complete archive of a `T` which is valid at any
+    /// position aligned to 16 bytes, such as one serialized into an
+    /// [`AlignedVec`](crate::util::AlignedVec).
+    pub fn serialize_from_bytes<W, E>(
+        bytes: &[u8],
+        writer: &mut W,
+    ) -> Result<ChecksummedResolver, E>
+    where
+        W: Writer<E> + ?Sized,
+        E: Source,
+    {
+        let start = writer.align(SUBTREE_ALIGN)?;
+        writer.write(bytes)?;
+        Ok(ChecksummedResolver {
+            pos: start + bytes.len() - size_of::<T>(),
+            len: bytes.len(),
+            checksum: Crc32c::checksum(bytes),
+        })
+    }
+
+    /// Resolves an archived checksummed value from a resolver.
+    pub fn resolve_from_resolver(
+        resolver: ChecksummedResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedChecksummed { ptr, len, checksum } = out);
+        RelPtr::emplace(resolver.pos, ptr);
+        len.write(ArchivedUsize::from_native(resolver.len as FixedUsize));
+        checksum.write(ArchivedU32::from_native(resolver.checksum));
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedChecksummed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+/// The resolver for [`ArchivedChecksummed`].
+pub struct ChecksummedResolver {
+    pos: usize,
+    len: usize,
+    checksum: u32,
+}
+
+/// A serializer which writes a checksummed subtree through another serializer.
+///
+/// Every byte written through a `ChecksumSerializer` is added to its checksum.
+/// Shared pointers and interned values which were serialized before the
+/// subtree started are serialized again, so that the subtree never points to
+/// bytes outside of it.
+#[cfg(feature = "alloc")]
+pub struct ChecksumSerializer<'a, S: ?Sized> {
+    inner: &'a mut S,
+    start: usize,
+    crc: Crc32c,
+    relocated: HashMap<usize, Option<usize>, BuildHasherDefault<FxHasher64>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, S: Positional + ?Sized> ChecksumSerializer<'a, S> {
+    /// Creates a new `ChecksumSerializer` which starts a subtree at the current
+    /// position of the given serializer.
+    pub fn new(inner: &'a mut S) -> Self {
+        Self {
+            start: inner.pos(),
+            inner,
+            crc: Crc32c::new(),
+            relocated: HashMap::default(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: ?Sized> ChecksumSerializer<'_, S> {
+    /// Returns the position where the subtree starts.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the checksum of the bytes written to the subtree so far.
+    pub fn checksum(&self) -> u32 {
+        self.crc.finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: Fallible + ?Sized> Fallible for ChecksumSerializer<'_, S> {
+    type Error = S::Error;
+}
+
+#[cfg(feature = "alloc")]
+impl<S: Positional + ?Sized> Positional for ChecksumSerializer<'_, S> {
+    fn pos(&self) -> usize {
+        self.inner.pos()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: Writer<E> + ?Sized, E> Writer<E> for ChecksumSerializer<'_, S> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.crc.update(bytes);
+        self.inner.write(bytes)
+    }
+
+    fn is_canonical(&self) -> bool {
+        self.inner.is_canonical()
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<S: Allocator<E> + ?Sized, E> Allocator<E>
+    for ChecksumSerializer<'_, S>
+{
+    unsafe fn push_alloc(
+        &mut self,
+        layout: Layout,
+    ) -> Result<NonNull<[u8]>, E> {
+        // SAFETY: The safety requirements for `S::push_alloc()` are the same as
+        // the safety requirements for `push_alloc()`.
+        unsafe { self.inner.push_alloc(layout) }
+    }
+
+    unsafe fn pop_alloc(
+        &mut self,
+        ptr: NonNull<u8>,
+        layout: Layout,
+    ) -> Result<(), E> {
+        // SAFETY: The safety requirements for `S::pop_alloc()` are the same as
+        // the safety requirements for `pop_alloc()`.
+        unsafe { self.inner.pop_alloc(ptr, layout) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: Sharing<E> + ?Sized, E> Sharing<E> for ChecksumSerializer<'_, S> {
+    fn start_sharing(&mut self, address: usize) -> SharingState {
+        if let Some(pos) = self.relocated.get(&address) {
+            return match pos {
+                Some(pos) => SharingState::Finished(*pos),
+                None => SharingState::Pending,
+            };
+        }
+
+        match self.inner.start_sharing(address) {
+            SharingState::Finished(pos) if pos < self.start => {
+                self.relocated.insert(address, None);
+                SharingState::Started
+            }
+            state => state,
+        }
+    }
+
+    fn finish_sharing(&mut self, address: usize, pos: usize) -> Result<(), E> {
+        if let Some(relocated) = self.relocated.get_mut(&address) {
+            *relocated = Some(pos);
+            Ok(())
+        } else {
+            self.inner.finish_sharing(address, pos)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: Interning + ?Sized> Interning for ChecksumSerializer<'_, S> {
+    fn get_interned(&self, type_id: TypeId, bytes: &[u8]) -> Option<usize> {
+        self.inner
+            .get_interned(type_id, bytes)
+            .filter(|pos| *pos >= self.start)
+    }
+
+    fn add_interned(&mut self, type_id: TypeId, bytes: &[u8], pos: usize) {
+        self.inner.add_interned(type_id, bytes, pos)
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{alloc::Layout, error::Error, fmt, mem::size_of};
+
+    use bytecheck::{CheckBytes, Verify};
+    use rancor::{fail, Fallible, ResultExt as _, Source};
+
+    use super::ArchivedChecksummed;
+    use crate::validation::{ArchiveContext, ArchiveContextExt};
+
+    #[derive(Debug)]
+    struct InvalidSubtreeLength {
+        len: usize,
+    }
+
+    impl fmt::Display for InvalidSubtreeLength {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "checksummed subtree of {} bytes is too short to contain its \
+                 value",
+                self.len,
+            )
+        }
+    }
+
+    impl Error for InvalidSubtreeLength {}
+
+    unsafe impl<T, C> Verify<C> for ArchivedChecksummed<T>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            let len = self.len.to_native() as usize;
+            if len < size_of::<T>() {
+                fail!(InvalidSubtreeLength { len });
+            }
+
+            let ptr = self.ptr.as_ptr_wrapping();
+            let start = ptr
+                .cast::<u8>()
+                .wrapping_add(size_of::<T>())
+                .wrapping_sub(len);
+            let layout = Layout::array::<u8>(len).into_error()?;
+            context.check_subtree_ptr(start, &layout)?;
+
+            // Claim everything before the subtree so that the value can only
+            // point to bytes covered by the checksum.
+            // SAFETY: `check_subtree_ptr` checked that `start` is located
+            // inside the archive.
+            unsafe {
+                let range = context.push_subtree_range(start, start)?;
+                context.pop_subtree_range(range)?;
+            }
+
+            context.in_subtree(ptr, |context| unsafe {
+                T::check_bytes(ptr, context)
+            })
+        }
+    }
+}
@@ -0,0 +1,400 @@
+//! A [`tokio_util`](tokio_util_0_7) codec for streams of archived values.
+//!
+//! [`RkyvCodec`] encodes each value as a [stream](crate::stream) frame: a
+//! length-prefixed header followed by the archive, padded to the next
+//! [`FRAME_ALIGN`]-byte boundary. Frames written by the codec can be read with
+//! a [`FrameReader`](crate::stream::FrameReader), and vice versa.
+
+use core::{error::Error, fmt, marker::PhantomData, ops::Deref};
+use std::io;
+
+use bytecheck::CheckBytes;
+use bytes_1::{Buf as _, BufMut as _, Bytes, BytesMut};
+use rancor::Source;
+use tokio_util_0_7::codec::{Decoder, Encoder};
+
+use crate::{
+    api::{
+        access_unchecked,
+        high::{access, to_bytes_in_with_alloc, HighSerializer, HighValidator},
+    },
+    ser::allocator::{Arena, ArenaHandle},
+    stream::{header, parse_header, FRAME_ALIGN, HEADER_SIZE},
+    util::AlignedVec,
+    Portable, Serialize,
+};
+
+#[derive(Debug)]
+struct FrameTooLarge {
+    len: usize,
+    max: usize,
+}
+
+impl fmt::Display for FrameTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frame of {} bytes exceeds the maximum frame length of {} bytes",
+            self.len, self.max,
+        )
+    }
+}
+
+impl Error for FrameTooLarge {}
+
+#[derive(Debug)]
+struct InvalidHeader;
+
+impl fmt::Display for InvalidHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid frame header")
+    }
+}
+
+impl Error for InvalidHeader {}
+
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Error + Send + Sync + 'static,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn padding(len: usize) -> usize {
+    (FRAME_ALIGN - len % FRAME_ALIGN) % FRAME_ALIGN
+}
+
+/// An encoder and decoder for frames of archived values.
+///
+/// Encoding serializes each value into a reusable scratch buffer and arena, so
+/// steady-state encoding does not allocate. Decoding validates each frame and
+/// yields it as an owned [`ArchivedFrame`]. Frames which are aligned in the
+/// read buffer are split off without copying; others are copied into an
+/// [`AlignedVec`].
+///
+/// Frames longer than the [maximum frame length](RkyvCodec::max_frame_len)
+/// are rejected when encoding, and rejected when decoding as soon as their
+/// header is read and before any space is reserved for them.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{codec::RkyvCodec, rancor::Error, Archive, Serialize};
+/// use tokio_util_0_7::codec::{Decoder, Encoder};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Message {
+///     id: u32,
+///     body: String,
+/// }
+///
+/// let mut codec = RkyvCodec::<ArchivedMessage, Error>::new();
+/// let mut buf = bytes_1::BytesMut::new();
+///
+/// let message = Message {
+///     id: 1,
+///     body: "hello".to_string(),
+/// };
+/// codec.encode(&message, &mut buf).unwrap();
+///
+/// let frame = codec.decode(&mut buf).unwrap().unwrap();
+/// assert_eq!(frame.id, 1);
+/// assert_eq!(frame.body, "hello");
+/// assert!(buf.is_empty());
+/// ```
+pub struct RkyvCodec<T, E = rancor::Error> {
+    scratch: AlignedVec,
+    arena: Arena,
+    max_frame_len: usize,
+    _phantom: PhantomData<fn() -> (T, E)>,
+}
+
+impl<T, E> fmt::Debug for RkyvCodec<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RkyvCodec")
+            .field("max_frame_len", &self.max_frame_len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, E> Default for RkyvCodec<T, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, E> RkyvCodec<T, E> {
+    /// The default maximum frame length: 8 MiB.
+    pub const DEFAULT_MAX_FRAME_LEN: usize = 8 * 1024 * 1024;
+
+    /// Creates a new `RkyvCodec` with the default maximum frame length.
+    pub fn new() -> Self {
+        Self::with_max_frame_len(Self::DEFAULT_MAX_FRAME_LEN)
+    }
+
+    /// Creates a new `RkyvCodec` with the given maximum frame length.
+    ///
+    /// The maximum applies to the length of each archive, excluding the frame
+    /// header and padding. It is clamped to `u32::MAX`, the longest archive a
+    /// frame header can describe.
+    pub fn with_max_frame_len(max_frame_len: usize) -> Self {
+        Self {
+            scratch: AlignedVec::new(),
+            arena: Arena::new(),
+            max_frame_len: max_frame_len.min(u32::MAX as usize),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the maximum frame length.
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    fn check_len(&self, len: usize) -> io::Result<()> {
+        if len > self.max_frame_len {
+            Err(invalid_data(FrameTooLarge {
+                len,
+                max: self.max_frame_len,
+            }))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<T, E, V> Encoder<&V> for RkyvCodec<T, E>
+where
+    V: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+    E: Source + Send + Sync + 'static,
+{
+    type Error = io::Error;
+
+    fn encode(&mut self, item: &V, dst: &mut BytesMut) -> io::Result<()> {
+        let mut scratch = core::mem::take(&mut self.scratch);
+        scratch.clear();
+        let scratch =
+            to_bytes_in_with_alloc(item, scratch, self.arena.acquire())
+                .map_err(invalid_data)?;
+        self.arena.shrink();
+
+        let len = scratch.len();
+        let result = self.check_len(len);
+        if result.is_ok() {
+            dst.reserve(HEADER_SIZE + len + padding(len));
+            dst.put_slice(&header(len as u32));
+            dst.put_slice(&scratch);
+            dst.put_bytes(0, padding(len));
+        }
+
+        self.scratch = scratch;
+        result
+    }
+}
+
+impl<T, E> Decoder for RkyvCodec<T, E>
+where
+    T: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source + Send + Sync + 'static,
+{
+    type Item = ArchivedFrame<T>;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<Self::Item>> {
+        if src.len() < HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let len =
+            parse_header(src).ok_or_else(|| invalid_data(InvalidHeader))?;
+        self.check_len(len)?;
+
+        let frame_len = HEADER_SIZE + len + padding(len);
+        if src.len() < frame_len {
+            src.reserve(frame_len - src.len());
+            return Ok(None);
+        }
+
+        let mut frame = src.split_to(frame_len);
+        frame.advance(HEADER_SIZE);
+        frame.truncate(len);
+
+        let bytes = if frame.as_ptr() as usize % FRAME_ALIGN == 0 {
+            FrameBytes::Shared(frame.freeze())
+        } else {
+            let mut aligned = AlignedVec::with_capacity(len);
+            aligned.extend_from_slice(&frame);
+            FrameBytes::Copied(aligned)
+        };
+
+        access::<T, E>(bytes.as_slice()).map_err(invalid_data)?;
+
+        Ok(Some(ArchivedFrame {
+            bytes,
+            _phantom: PhantomData,
+        }))
+    }
+}
+
+#[derive(Debug)]
+enum FrameBytes {
+    Shared(Bytes),
+    Copied(AlignedVec),
+}
+
+impl FrameBytes {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Self::Shared(bytes) => bytes,
+            Self::Copied(bytes) => bytes,
+        }
+    }
+}
+
+/// An owned, validated archive decoded by an [`RkyvCodec`].
+///
+/// Dereferences to the archived value.
+pub struct ArchivedFrame<T> {
+    bytes: FrameBytes,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ArchivedFrame<T> {
+    /// Returns the bytes of the archive.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Returns whether the archive had to be copied to align it.
+    pub fn is_copied(&self) -> bool {
+        matches!(self.bytes, FrameBytes::Copied(_))
+    }
+}
+
+impl<T: Portable> ArchivedFrame<T> {
+    /// Returns a reference to the archived value.
+    pub fn get(&self) -> &T {
+        // SAFETY: The bytes were validated as a `T` when the frame was decoded
+        // and are aligned to `FRAME_ALIGN`.
+        unsafe { access_unchecked::<T>(self.as_bytes()) }
+    }
+}
+
+impl<T: Portable> Deref for ArchivedFrame<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<T: Portable + fmt::Debug> fmt::Debug for ArchivedFrame<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes_1::{BufMut as _, BytesMut};
+    use rancor::Error;
+    use tokio_util_0_7::codec::{Decoder as _, Encoder as _};
+
+    use super::RkyvCodec;
+    use crate::{
+        alloc::{string::ToString, vec::Vec},
+        stream::FrameReader,
+        util::AlignedVec,
+        Archived,
+    };
+
+    type Codec = RkyvCodec<Archived<Vec<String>>, Error>;
+
+    fn value(n: usize) -> Vec<String> {
+        (0..n).map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn codec_roundtrip() {
+        let mut codec = Codec::new();
+        let mut buf = BytesMut::new();
+        for n in 1..4 {
+            codec.encode(&value(n * 10), &mut buf).unwrap();
+        }
+
+        let mut stream = AlignedVec::<16>::new();
+        stream.extend_from_slice(&buf);
+        let frames = FrameReader::<Archived<Vec<String>>, Error>::new(&stream)
+            .map(|frame| frame.unwrap().len())
+            .collect::<Vec<_>>();
+        assert_eq!(frames, [10, 20, 30]);
+
+        // Feed the frames one byte at a time to exercise partial reads.
+        let mut src = BytesMut::new();
+        let mut decoded = Vec::new();
+        for byte in buf.iter() {
+            src.put_u8(*byte);
+            while let Some(frame) = codec.decode(&mut src).unwrap() {
+                decoded.push(frame);
+            }
+        }
+        assert!(src.is_empty());
+        assert_eq!(decoded.len(), 3);
+        for (i, frame) in decoded.iter().enumerate() {
+            assert_eq!(frame.len(), (i + 1) * 10);
+            assert_eq!(frame[7], "7");
+        }
+    }
+
+    #[test]
+    fn codec_copies_unaligned_frames() {
+        let mut codec = Codec::new();
+        let mut buf = BytesMut::new();
+        codec.encode(&value(10), &mut buf).unwrap();
+
+        let mut src = BytesMut::with_capacity(buf.len() + 1);
+        src.put_u8(0);
+        src.extend_from_slice(&buf);
+        let _ = src.split_to(1);
+
+        let frame = codec.decode(&mut src).unwrap().unwrap();
+        assert!(frame.is_copied());
+        assert_eq!(frame.len(), 10);
+    }
+
+    #[test]
+    fn codec_rejects_large_frames() {
+        let mut codec = Codec::with_max_frame_len(64);
+        let mut buf = BytesMut::new();
+        codec.encode(&value(1), &mut buf).unwrap();
+        assert!(codec.encode(&value(100), &mut buf).is_err());
+
+        let mut large = BytesMut::new();
+        Codec::new().encode(&value(100), &mut large).unwrap();
+
+        // Only the header is needed to reject the frame.
+        let mut src = large.split_to(16);
+        let capacity = src.capacity();
+        assert!(codec.decode(&mut src).is_err());
+        assert_eq!(src.capacity(), capacity);
+
+        let frame = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(frame[0], "0");
+    }
+
+    #[test]
+    fn codec_rejects_invalid_frames() {
+        let mut codec = Codec::new();
+        let mut buf = BytesMut::new();
+        codec.encode(&value(10), &mut buf).unwrap();
+
+        let mut corrupt = buf.clone();
+        corrupt[0] = b'x';
+        assert!(codec.decode(&mut corrupt).is_err());
+
+        let last = buf.len() - 1;
+        let mut corrupt = buf.clone();
+        corrupt[16..last].fill(0xc0);
+        assert!(codec.decode(&mut corrupt).is_err());
+    }
+}
@@ -527,8 +527,10 @@ impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
             items.iter().zip(resolvers.drain()).enumerate()
         {
             let out_key = unsafe { keys.index(i).cast_unchecked() };
+            KU::check_resolver(&kr, out_key.pos())?;
             k.borrow().resolve(kr, out_key);
             let out_value = unsafe { values.index(i).cast_unchecked() };
+            VU::check_resolver(&vr, out_value.pos())?;
             v.borrow().resolve(vr, out_value);
         }
 
@@ -593,8 +595,10 @@ impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
             items.iter().zip(resolvers.drain()).enumerate()
         {
             let out_key = unsafe { keys.index(i).cast_unchecked() };
+            KU::check_resolver(&kr, out_key.pos())?;
             k.borrow().resolve(kr, out_key);
             let out_value = unsafe { values.index(i).cast_unchecked() };
+            VU::check_resolver(&vr, out_value.pos())?;
             v.borrow().resolve(vr, out_value);
 
             let out_lesser_node = unsafe { lesser_nodes.index(i) };
@@ -2,5 +2,7 @@
 
 pub mod btree_map;
 pub mod btree_set;
+pub mod roaring;
+pub mod segmented_vec;
 pub mod swiss_table;
 pub mod util;
@@ -0,0 +1,977 @@
+//! An archived compressed bitmap in the style of Roaring bitmaps.
+//!
+//! The 32-bit values in the set are partitioned by their high 16 bits into
+//! containers. Each container stores the low 16 bits of its values in one of
+//! three representations, whichever is smallest:
+//!
+//! - An array container stores a sorted array of values. It is used for sparse
+//!   containers with at most 4096 values.
+//! - A bitmap container stores a 65536-bit bitmap as 4096 16-bit words.
+//! - A run container stores sorted, non-overlapping runs of consecutive values
+//!   as pairs of a start value and a length minus one.
+
+use core::{
+    cmp::Ordering,
+    error::Error,
+    fmt,
+    iter::{FusedIterator, Peekable},
+    slice,
+};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    primitive::{ArchivedU16, ArchivedU32, ArchivedU64},
+    ser::{Allocator, Writer},
+    util::SerVec,
+    vec::{ArchivedVec, VecResolver},
+    Archive, Place, Portable, Serialize,
+};
+
+/// The maximum number of values an array container may hold.
+const ARRAY_MAX: usize = 4096;
+/// The number of 16-bit words in a bitmap container.
+const BITMAP_WORDS: usize = 4096;
+
+const KIND_ARRAY: u8 = 0;
+const KIND_BITMAP: u8 = 1;
+const KIND_RUN: u8 = 2;
+
+/// An archived compressed bitmap of `u32` values.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedRoaringBitmap {
+    containers: ArchivedVec<ArchivedContainer>,
+    len: ArchivedU64,
+}
+
+/// A single container of an [`ArchivedRoaringBitmap`].
+///
+/// A container holds all of the values which share the same high 16 bits.
+#[derive(Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedContainer {
+    data: ArchivedVec<ArchivedU16>,
+    cardinality: ArchivedU32,
+    key: ArchivedU16,
+    kind: u8,
+}
+
+/// The representation of an [`ArchivedContainer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContainerKind {
+    /// A sorted array of values.
+    Array,
+    /// A 65536-bit bitmap.
+    Bitmap,
+    /// Sorted, non-overlapping runs of consecutive values.
+    Run,
+}
+
+impl ArchivedContainer {
+    /// Returns the representation of the container.
+    pub fn kind(&self) -> ContainerKind {
+        match self.kind {
+            KIND_ARRAY => ContainerKind::Array,
+            KIND_BITMAP => ContainerKind::Bitmap,
+            _ => ContainerKind::Run,
+        }
+    }
+
+    /// Returns the high 16 bits shared by all of the values in the container.
+    pub fn key(&self) -> u16 {
+        self.key.to_native()
+    }
+
+    /// Returns the number of values in the container.
+    pub fn len(&self) -> usize {
+        self.cardinality.to_native() as usize
+    }
+
+    /// Returns whether the container is empty. Valid containers are never
+    /// empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn runs(&self) -> impl ExactSizeIterator<Item = (u16, u16)> + '_ {
+        self.data
+            .chunks_exact(2)
+            .map(|run| (run[0].to_native(), run[1].to_native()))
+    }
+
+    /// Returns whether the container contains the given low 16 bits.
+    pub fn contains(&self, low: u16) -> bool {
+        match self.kind {
+            KIND_ARRAY => self
+                .data
+                .binary_search_by(|x| x.to_native().cmp(&low))
+                .is_ok(),
+            KIND_BITMAP => {
+                let word = self.data[low as usize / 16].to_native();
+                word & (1 << (low % 16)) != 0
+            }
+            _ => {
+                let runs = self.data.len() / 2;
+                let index = partition_point(runs, |i| {
+                    self.data[2 * i].to_native() <= low
+                });
+                index != 0 && {
+                    let start = self.data[2 * (index - 1)].to_native();
+                    let len = self.data[2 * (index - 1) + 1].to_native();
+                    low - start <= len
+                }
+            }
+        }
+    }
+
+    /// Returns the number of values in the container which are less than or
+    /// equal to the given low 16 bits.
+    pub fn rank(&self, low: u16) -> usize {
+        match self.kind {
+            KIND_ARRAY => partition_point(self.data.len(), |i| {
+                self.data[i].to_native() <= low
+            }),
+            KIND_BITMAP => {
+                let index = low as usize / 16;
+                let full = self.data[..index]
+                    .iter()
+                    .map(|w| w.to_native().count_ones() as usize)
+                    .sum::<usize>();
+                let mask = u16::MAX >> (15 - low % 16);
+                full + (self.data[index].to_native() & mask).count_ones()
+                    as usize
+            }
+            _ => {
+                let mut rank = 0;
+                for (start, len) in self.runs() {
+                    if start > low {
+                        break;
+                    }
+                    rank += usize::from(u16::min(low - start, len)) + 1;
+                }
+                rank
+            }
+        }
+    }
+
+    /// Returns an iterator over the low 16 bits of the values in the
+    /// container, in ascending order.
+    pub fn iter(&self) -> ContainerIter<'_> {
+        let inner = match self.kind {
+            KIND_ARRAY => ContainerIterInner::Array(self.data.iter()),
+            KIND_BITMAP => ContainerIterInner::Bitmap {
+                words: self.data.iter(),
+                base: 0,
+                current: 0,
+            },
+            _ => ContainerIterInner::Run {
+                runs: self.data.chunks_exact(2),
+                next: 0,
+                remaining: 0,
+            },
+        };
+        ContainerIter {
+            inner,
+            remaining: self.len(),
+        }
+    }
+}
+
+fn partition_point(len: usize, pred: impl Fn(usize) -> bool) -> usize {
+    let (mut lo, mut hi) = (0, len);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(mid) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+impl ArchivedRoaringBitmap {
+    /// Returns the number of values in the bitmap.
+    pub fn len(&self) -> u64 {
+        self.len.to_native()
+    }
+
+    /// Returns whether the bitmap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the containers of the bitmap, sorted by key.
+    pub fn containers(&self) -> &[ArchivedContainer] {
+        self.containers.as_slice()
+    }
+
+    fn find(&self, key: u16) -> Result<usize, usize> {
+        self.containers().binary_search_by(|c| c.key().cmp(&key))
+    }
+
+    /// Returns whether the bitmap contains the given value.
+    pub fn contains(&self, value: u32) -> bool {
+        self.find((value >> 16) as u16)
+            .is_ok_and(|i| self.containers()[i].contains(value as u16))
+    }
+
+    /// Returns the number of values in the bitmap which are less than or equal
+    /// to the given value.
+    pub fn rank(&self, value: u32) -> u64 {
+        let containers = self.containers();
+        let (before, rank) = match self.find((value >> 16) as u16) {
+            Ok(i) => (i, containers[i].rank(value as u16) as u64),
+            Err(i) => (i, 0),
+        };
+        containers[..before]
+            .iter()
+            .map(|c| c.len() as u64)
+            .sum::<u64>()
+            + rank
+    }
+
+    /// Returns the smallest value in the bitmap.
+    pub fn min(&self) -> Option<u32> {
+        self.iter().next()
+    }
+
+    /// Returns an iterator over the values in the bitmap in ascending order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            containers: self.containers().iter(),
+            current: None,
+            remaining: self.len(),
+        }
+    }
+
+    /// Returns an iterator over the values which are in both `self` and
+    /// `other`, in ascending order.
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a> {
+        Intersection {
+            left: self.containers().iter(),
+            right: other.containers().iter(),
+            current: None,
+        }
+    }
+
+    /// Returns an iterator over the values which are in `self`, `other`, or
+    /// both, in ascending order.
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a> {
+        Union {
+            left: self.iter().peekable(),
+            right: other.iter().peekable(),
+        }
+    }
+
+    /// Serializes a strictly increasing iterator of values as a bitmap.
+    pub fn serialize_from_sorted_iter<I, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<RoaringBitmapResolver, S::Error>
+    where
+        I: Clone + Iterator<Item = u32>,
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        #[derive(Debug)]
+        struct UnsortedValues {
+            previous: u32,
+            next: u32,
+        }
+
+        impl fmt::Display for UnsortedValues {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(
+                    f,
+                    "bitmap values must be strictly increasing, but {} was \
+                     followed by {}",
+                    self.previous, self.next,
+                )
+            }
+        }
+
+        impl Error for UnsortedValues {}
+
+        let mut container_count = 0;
+        let mut previous = None;
+        for next in iter.clone() {
+            match previous {
+                Some(previous) if next <= previous => {
+                    fail!(UnsortedValues { previous, next });
+                }
+                Some(previous) if next >> 16 == previous >> 16 => (),
+                _ => container_count += 1,
+            }
+            previous = Some(next);
+        }
+
+        SerVec::<ContainerDescriptor>::with_capacity(
+            serializer,
+            container_count,
+            |descriptors, serializer| {
+                let mut values = iter.peekable();
+                let mut len = 0u64;
+
+                while let Some(&first) = values.peek() {
+                    let key = (first >> 16) as u16;
+                    let descriptor = serialize_container(
+                        key,
+                        values
+                            .clone()
+                            .take_while(move |v| (v >> 16) as u16 == key)
+                            .map(|v| v as u16),
+                        serializer,
+                    )?;
+                    let cardinality = descriptor.cardinality;
+                    descriptors.push(descriptor);
+
+                    len += cardinality as u64;
+                    for _ in 0..cardinality {
+                        values.next();
+                    }
+                }
+
+                let containers =
+                    ArchivedVec::<ArchivedContainer>::serialize_from_iter::<
+                        ContainerDescriptor,
+                        _,
+                        _,
+                    >(descriptors.iter(), serializer)?;
+
+                Ok(RoaringBitmapResolver {
+                    containers,
+                    containers_len: descriptors.len(),
+                    len,
+                })
+            },
+        )?
+    }
+
+    /// Resolves an archived bitmap from a resolver.
+    pub fn resolve_from_resolver(
+        resolver: RoaringBitmapResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedRoaringBitmap { containers, len } = out);
+        ArchivedVec::resolve_from_len(
+            resolver.containers_len,
+            resolver.containers,
+            containers,
+        );
+        len.write(ArchivedU64::from_native(resolver.len));
+    }
+}
+
+impl fmt::Debug for ArchivedRoaringBitmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for ArchivedRoaringBitmap {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for ArchivedRoaringBitmap {}
+
+/// The resolver for an [`ArchivedRoaringBitmap`].
+pub struct RoaringBitmapResolver {
+    containers: VecResolver,
+    containers_len: usize,
+    len: u64,
+}
+
+/// Describes a container whose data has already been serialized.
+struct ContainerDescriptor {
+    key: u16,
+    kind: u8,
+    cardinality: usize,
+    words: usize,
+    data_pos: usize,
+}
+
+impl Archive for ContainerDescriptor {
+    type Archived = ArchivedContainer;
+    type Resolver = ();
+
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(
+            let ArchivedContainer { data, cardinality, key, kind } = out
+        );
+        ArchivedVec::resolve_from_len(
+            self.words,
+            VecResolver::from_pos(self.data_pos),
+            data,
+        );
+        cardinality.write(ArchivedU32::from_native(self.cardinality as u32));
+        key.write(ArchivedU16::from_native(self.key));
+        kind.write(self.kind);
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for ContainerDescriptor {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+/// Serializes the data of a single container, choosing the smallest
+/// representation for its values.
+fn serialize_container<I, S>(
+    key: u16,
+    values: I,
+    serializer: &mut S,
+) -> Result<ContainerDescriptor, S::Error>
+where
+    I: Clone + Iterator<Item = u16>,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    let mut cardinality = 0usize;
+    let mut runs = 0usize;
+    let mut last = None;
+    for low in values.clone() {
+        if !last.is_some_and(|last: u16| low == last + 1) {
+            runs += 1;
+        }
+        cardinality += 1;
+        last = Some(low);
+    }
+
+    let (mut kind, mut words) = if cardinality <= ARRAY_MAX {
+        (KIND_ARRAY, cardinality)
+    } else {
+        (KIND_BITMAP, BITMAP_WORDS)
+    };
+    if 2 * runs < words {
+        kind = KIND_RUN;
+        words = 2 * runs;
+    }
+
+    let resolver = match kind {
+        KIND_ARRAY => ArchivedVec::serialize_from_unknown_length_iter(
+            &mut values.clone(),
+            serializer,
+        )?,
+        KIND_BITMAP => SerVec::<u16>::with_capacity(
+            serializer,
+            BITMAP_WORDS,
+            |bitmap, serializer| {
+                for _ in 0..BITMAP_WORDS {
+                    bitmap.push(0);
+                }
+                for low in values.clone() {
+                    bitmap[low as usize / 16] |= 1 << (low % 16);
+                }
+                ArchivedVec::<ArchivedU16>::serialize_from_slice(
+                    bitmap.as_slice(),
+                    serializer,
+                )
+            },
+        )??,
+        _ => ArchivedVec::<ArchivedU16>::serialize_from_unknown_length_iter(
+            &mut RunWords::new(values.clone()),
+            serializer,
+        )?,
+    };
+
+    Ok(ContainerDescriptor {
+        key,
+        kind,
+        cardinality,
+        words,
+        data_pos: resolver.pos(),
+    })
+}
+
+/// Yields the start and length minus one of each run of consecutive values.
+struct RunWords<I: Iterator> {
+    values: Peekable<I>,
+    pending: Option<u16>,
+}
+
+impl<I: Iterator<Item = u16>> RunWords<I> {
+    fn new(values: I) -> Self {
+        Self {
+            values: values.peekable(),
+            pending: None,
+        }
+    }
+}
+
+impl<I: Iterator<Item = u16>> Iterator for RunWords<I> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        if let Some(len) = self.pending.take() {
+            return Some(len);
+        }
+
+        let start = self.values.next()?;
+        let mut end = start;
+        while self.values.next_if(|&v| v == end + 1).is_some() {
+            end += 1;
+        }
+        self.pending = Some(end - start);
+        Some(start)
+    }
+}
+
+enum ContainerIterInner<'a> {
+    Array(slice::Iter<'a, ArchivedU16>),
+    Bitmap {
+        words: slice::Iter<'a, ArchivedU16>,
+        base: u32,
+        current: u16,
+    },
+    Run {
+        runs: slice::ChunksExact<'a, ArchivedU16>,
+        next: u32,
+        remaining: u32,
+    },
+}
+
+/// An iterator over the low 16 bits of the values in an [`ArchivedContainer`].
+pub struct ContainerIter<'a> {
+    inner: ContainerIterInner<'a>,
+    remaining: usize,
+}
+
+impl Iterator for ContainerIter<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        let result = match &mut self.inner {
+            ContainerIterInner::Array(values) => {
+                values.next().map(|v| v.to_native())
+            }
+            ContainerIterInner::Bitmap {
+                words,
+                base,
+                current,
+            } => loop {
+                if *current != 0 {
+                    let bit = current.trailing_zeros();
+                    *current &= *current - 1;
+                    break Some((*base - 16 + bit) as u16);
+                }
+                *current = words.next()?.to_native();
+                *base += 16;
+            },
+            ContainerIterInner::Run {
+                runs,
+                next,
+                remaining,
+            } => {
+                if *remaining == 0 {
+                    let run = runs.next()?;
+                    *next = u32::from(run[0].to_native());
+                    *remaining = u32::from(run[1].to_native()) + 1;
+                }
+                *remaining -= 1;
+                *next += 1;
+                Some((*next - 1) as u16)
+            }
+        };
+        if result.is_some() {
+            self.remaining -= 1;
+        }
+        result
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for ContainerIter<'_> {}
+
+impl FusedIterator for ContainerIter<'_> {}
+
+/// An iterator over the values of an [`ArchivedRoaringBitmap`].
+pub struct Iter<'a> {
+    containers: slice::Iter<'a, ArchivedContainer>,
+    current: Option<(u32, ContainerIter<'a>)>,
+    remaining: u64,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        loop {
+            if let Some((high, values)) = &mut self.current {
+                if let Some(low) = values.next() {
+                    self.remaining -= 1;
+                    return Some(*high | u32::from(low));
+                }
+            }
+            let container = self.containers.next()?;
+            self.current =
+                Some((u32::from(container.key()) << 16, container.iter()));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.remaining).ok();
+        (remaining.unwrap_or(usize::MAX), remaining)
+    }
+}
+
+impl FusedIterator for Iter<'_> {}
+
+/// An iterator over the intersection of two [`ArchivedRoaringBitmap`]s.
+pub struct Intersection<'a> {
+    left: slice::Iter<'a, ArchivedContainer>,
+    right: slice::Iter<'a, ArchivedContainer>,
+    current: Option<(u32, ContainerIter<'a>, &'a ArchivedContainer)>,
+}
+
+impl Iterator for Intersection<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        loop {
+            if let Some((high, values, other)) = &mut self.current {
+                for low in values.by_ref() {
+                    if other.contains(low) {
+                        return Some(*high | u32::from(low));
+                    }
+                }
+            }
+
+            // Advance to the next pair of containers with the same key
+            let mut left = self.left.next()?;
+            let mut right = self.right.next()?;
+            loop {
+                match left.key().cmp(&right.key()) {
+                    Ordering::Less => left = self.left.next()?,
+                    Ordering::Greater => right = self.right.next()?,
+                    Ordering::Equal => break,
+                }
+            }
+
+            // Iterate over the smaller container and probe the larger one
+            let (small, large) = if left.len() <= right.len() {
+                (left, right)
+            } else {
+                (right, left)
+            };
+            self.current =
+                Some((u32::from(small.key()) << 16, small.iter(), large));
+        }
+    }
+}
+
+impl FusedIterator for Intersection<'_> {}
+
+/// An iterator over the union of two [`ArchivedRoaringBitmap`]s.
+pub struct Union<'a> {
+    left: Peekable<Iter<'a>>,
+    right: Peekable<Iter<'a>>,
+}
+
+impl Iterator for Union<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        match (self.left.peek(), self.right.peek()) {
+            (Some(l), Some(r)) => match l.cmp(r) {
+                Ordering::Less => self.left.next(),
+                Ordering::Greater => self.right.next(),
+                Ordering::Equal => {
+                    self.right.next();
+                    self.left.next()
+                }
+            },
+            (Some(_), None) => self.left.next(),
+            (None, _) => self.right.next(),
+        }
+    }
+}
+
+impl FusedIterator for Union<'_> {}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt};
+
+    use bytecheck::Verify;
+    use rancor::{fail, Fallible, Source};
+
+    use super::{
+        ArchivedContainer, ArchivedRoaringBitmap, ARRAY_MAX, BITMAP_WORDS,
+        KIND_ARRAY, KIND_BITMAP, KIND_RUN,
+    };
+
+    #[derive(Debug)]
+    enum InvalidContainer {
+        UnsortedKeys { index: usize },
+        InvalidKind { index: usize, kind: u8 },
+        InvalidLength { index: usize },
+        UnsortedValues { index: usize },
+        CardinalityMismatch { index: usize },
+        TotalMismatch { expected: u64, actual: u64 },
+    }
+
+    impl fmt::Display for InvalidContainer {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::UnsortedKeys { index } => write!(
+                    f,
+                    "bitmap container keys are not strictly increasing at \
+                     container {index}",
+                ),
+                Self::InvalidKind { index, kind } => write!(
+                    f,
+                    "bitmap container {index} has invalid kind {kind}",
+                ),
+                Self::InvalidLength { index } => write!(
+                    f,
+                    "bitmap container {index} has an invalid data length",
+                ),
+                Self::UnsortedValues { index } => write!(
+                    f,
+                    "bitmap container {index} has values which are not \
+                     strictly increasing",
+                ),
+                Self::CardinalityMismatch { index } => write!(
+                    f,
+                    "bitmap container {index} has a cardinality which does \
+                     not match its contents",
+                ),
+                Self::TotalMismatch { expected, actual } => write!(
+                    f,
+                    "bitmap length {expected} does not match the total \
+                     cardinality of its containers {actual}",
+                ),
+            }
+        }
+    }
+
+    impl Error for InvalidContainer {}
+
+    fn verify_container(
+        index: usize,
+        container: &ArchivedContainer,
+    ) -> Result<(), InvalidContainer> {
+        let data = container.data.as_slice();
+        let cardinality = container.len();
+        let actual = match container.kind {
+            KIND_ARRAY => {
+                if data.len() > ARRAY_MAX {
+                    return Err(InvalidContainer::InvalidLength { index });
+                }
+                if data.windows(2).any(|w| w[0] >= w[1]) {
+                    return Err(InvalidContainer::UnsortedValues { index });
+                }
+                data.len()
+            }
+            KIND_BITMAP => {
+                if data.len() != BITMAP_WORDS {
+                    return Err(InvalidContainer::InvalidLength { index });
+                }
+                data.iter()
+                    .map(|w| w.to_native().count_ones() as usize)
+                    .sum()
+            }
+            KIND_RUN => {
+                if data.is_empty() || data.len() % 2 != 0 {
+                    return Err(InvalidContainer::InvalidLength { index });
+                }
+                let mut total = 0;
+                let mut next_start = 0u32;
+                for (start, len) in container.runs() {
+                    let (start, len) = (u32::from(start), u32::from(len));
+                    if start < next_start || start + len > u32::from(u16::MAX) {
+                        return Err(InvalidContainer::UnsortedValues { index });
+                    }
+                    next_start = start + len + 1;
+                    total += len as usize + 1;
+                }
+                total
+            }
+            kind => return Err(InvalidContainer::InvalidKind { index, kind }),
+        };
+
+        if cardinality == 0 || cardinality != actual {
+            return Err(InvalidContainer::CardinalityMismatch { index });
+        }
+
+        Ok(())
+    }
+
+    unsafe impl<C> Verify<C> for ArchivedRoaringBitmap
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            let mut total = 0u64;
+            let mut previous_key = None;
+            for (index, container) in self.containers().iter().enumerate() {
+                let key = container.key();
+                if previous_key.is_some_and(|previous| key <= previous) {
+                    fail!(InvalidContainer::UnsortedKeys { index });
+                }
+                previous_key = Some(key);
+
+                if let Err(error) = verify_container(index, container) {
+                    fail!(error);
+                }
+                total += container.len() as u64;
+            }
+
+            if total != self.len() {
+                fail!(InvalidContainer::TotalMismatch {
+                    expected: self.len(),
+                    actual: total,
+                });
+            }
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "bytecheck", feature = "alloc"))]
+mod tests {
+    use core::mem::size_of;
+
+    use rancor::{Failure, Panic};
+
+    use super::{ArchivedContainer, ContainerKind};
+    use crate::{
+        access, access_unchecked,
+        alloc::collections::BTreeSet,
+        primitive::{ArchivedU16, ArchivedU32},
+        to_bytes,
+        util::AlignedVec,
+        with::AsBitmap,
+        Archive, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate)]
+    struct Bitmap {
+        #[rkyv(with = AsBitmap)]
+        values: BTreeSet<u32>,
+    }
+
+    /// Serializes the values and checks that their containers have the
+    /// expected kinds.
+    fn serialize(
+        values: impl IntoIterator<Item = u32>,
+        kinds: &[ContainerKind],
+    ) -> AlignedVec {
+        let value = Bitmap {
+            values: values.into_iter().collect(),
+        };
+        let bytes = to_bytes::<Panic>(&value).unwrap();
+        let archived = access::<ArchivedBitmap, Failure>(&bytes).unwrap();
+        assert!(archived
+            .values
+            .containers()
+            .iter()
+            .map(|c| c.kind())
+            .eq(kinds.iter().copied()));
+        bytes
+    }
+
+    fn container(bytes: &[u8], index: usize) -> &ArchivedContainer {
+        let archived = unsafe { access_unchecked::<ArchivedBitmap>(bytes) };
+        &archived.values.containers()[index]
+    }
+
+    fn pos_of<T>(bytes: &[u8], value: &T) -> usize {
+        value as *const T as usize - bytes.as_ptr() as usize
+    }
+
+    fn write<T>(bytes: &mut [u8], pos: usize, value: T) {
+        let out = bytes[pos..pos + size_of::<T>()].as_mut_ptr();
+        unsafe { out.cast::<T>().write_unaligned(value) }
+    }
+
+    /// Overwrites the `index`th data word of the `container`th container.
+    fn set_word(bytes: &mut [u8], container: usize, index: usize, word: u16) {
+        let pos = pos_of(bytes, &self::container(bytes, container).data[index]);
+        write(bytes, pos, ArchivedU16::from_native(word));
+    }
+
+    fn set_key(bytes: &mut [u8], container: usize, key: u16) {
+        let pos = pos_of(bytes, &self::container(bytes, container).key);
+        write(bytes, pos, ArchivedU16::from_native(key));
+    }
+
+    fn assert_invalid(bytes: &[u8]) {
+        assert!(access::<ArchivedBitmap, Failure>(bytes).is_err());
+    }
+
+    #[test]
+    fn unsorted_array_container() {
+        let values = [1, 3, 5, 7];
+        let mut bytes = serialize(values, &[ContainerKind::Array]);
+        set_word(&mut bytes, 0, 2, 2);
+        assert_invalid(&bytes);
+
+        let mut bytes = serialize(values, &[ContainerKind::Array]);
+        set_word(&mut bytes, 0, 2, 3);
+        assert_invalid(&bytes);
+    }
+
+    #[test]
+    fn wrong_bitmap_cardinality() {
+        let values = (0..5000).map(|i| i * 2);
+        let mut bytes = serialize(values.clone(), &[ContainerKind::Bitmap]);
+        let pos = pos_of(&bytes, &container(&bytes, 0).cardinality);
+        write(&mut bytes, pos, ArchivedU32::from_native(4999));
+        assert_invalid(&bytes);
+
+        // Setting a bit changes the number of values in the bitmap.
+        let mut bytes = serialize(values, &[ContainerKind::Bitmap]);
+        set_word(&mut bytes, 0, 0, 0x5557);
+        assert_invalid(&bytes);
+    }
+
+    #[test]
+    fn overlapping_runs() {
+        let values = (0..10).chain(20..30);
+        let mut bytes = serialize(values.clone(), &[ContainerKind::Run]);
+        // The second run starts inside the first.
+        set_word(&mut bytes, 0, 2, 5);
+        assert_invalid(&bytes);
+
+        // The first run contains the second.
+        let mut bytes = serialize(values, &[ContainerKind::Run]);
+        set_word(&mut bytes, 0, 1, 14);
+        set_word(&mut bytes, 0, 2, 10);
+        set_word(&mut bytes, 0, 3, 4);
+        assert_invalid(&bytes);
+    }
+
+    #[test]
+    fn unsorted_keys() {
+        let values = [1, 3, (1 << 16) + 1, (1 << 16) + 3];
+        let kinds = [ContainerKind::Array; 2];
+        let mut bytes = serialize(values, &kinds);
+        set_key(&mut bytes, 1, 0);
+        assert_invalid(&bytes);
+
+        let mut bytes = serialize(values, &kinds);
+        set_key(&mut bytes, 0, 2);
+        assert_invalid(&bytes);
+    }
+}
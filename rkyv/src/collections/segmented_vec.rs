@@ -0,0 +1,606 @@
+//! An archived vector which is split into segments that are each within the
+//! range of a relative pointer.
+//!
+//! An [`ArchivedVec`](crate::vec::ArchivedVec) must be reachable with a single
+//! relative pointer, which limits its size to the range of [`FixedIsize`]. An
+//! [`ArchivedSegmentedVec`] stores its elements in a chain of segments instead.
+//! Each segment is written immediately after the segment that follows it, so
+//! every relative pointer in the chain spans at most one segment.
+//!
+//! The out-of-line data of each element is written right before the segment
+//! which contains it. Elements whose out-of-line data is larger than the range
+//! of a relative pointer still can't be archived.
+
+use core::{
+    fmt, hash, iter::FusedIterator, marker::PhantomData, mem::size_of, slice,
+};
+
+use munge::munge;
+use rancor::{Fallible, Source};
+
+use crate::{
+    primitive::{
+        ArchivedIsize, ArchivedU64, ArchivedUsize, FixedIsize, FixedUsize,
+    },
+    rel_ptr::{signed_offset, Offset as _},
+    ser::{Allocator, Writer, WriterExt as _},
+    util::SerVec,
+    Archive, Place, Portable, RelPtr, Serialize,
+};
+
+/// An archived vector which is split into segments.
+///
+/// Indexing walks the chain of segments, so it takes time linear in the number
+/// of segments. Iteration visits each segment once.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedSegmentedVec<T> {
+    head: RelPtr<ArchivedSegment<T>>,
+    len: ArchivedU64,
+}
+
+/// A single segment of an [`ArchivedSegmentedVec`].
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedSegment<T> {
+    next: RelPtr<ArchivedSegment<T>>,
+    data: RelPtr<T>,
+    len: ArchivedUsize,
+}
+
+impl<T> ArchivedSegment<T> {
+    /// Returns the number of elements in the segment.
+    pub fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns whether the segment is empty. Valid segments are never empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the elements of the segment as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.data.as_ptr(), self.len()) }
+    }
+
+    /// Returns the next segment, if any.
+    pub fn next(&self) -> Option<&Self> {
+        if self.next.is_invalid() {
+            None
+        } else {
+            Some(unsafe { &*self.next.as_ptr() })
+        }
+    }
+}
+
+impl<T> ArchivedSegmentedVec<T> {
+    /// Returns the number of elements in the vector.
+    pub fn len(&self) -> u64 {
+        self.len.to_native()
+    }
+
+    /// Returns whether the vector is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the first segment of the vector, if any.
+    pub fn head(&self) -> Option<&ArchivedSegment<T>> {
+        if self.head.is_invalid() {
+            None
+        } else {
+            Some(unsafe { &*self.head.as_ptr() })
+        }
+    }
+
+    /// Returns an iterator over the segments of the vector.
+    pub fn segments(&self) -> Segments<'_, T> {
+        Segments { next: self.head() }
+    }
+
+    /// Returns the element at the given index, or `None` if it is out of
+    /// bounds.
+    pub fn get(&self, mut index: u64) -> Option<&T> {
+        for segment in self.segments() {
+            let len = segment.len() as u64;
+            if index < len {
+                return Some(&segment.as_slice()[index as usize]);
+            }
+            index -= len;
+        }
+        None
+    }
+
+    /// Returns an iterator over the elements of the vector.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            current: [].iter(),
+            segments: self.segments(),
+            remaining: self.len(),
+        }
+    }
+
+    /// Returns the maximum number of elements in a segment.
+    ///
+    /// This is chosen so that each segment occupies at most half of the range
+    /// of a relative pointer.
+    pub const fn max_segment_len() -> usize {
+        let max_bytes = (FixedIsize::MAX as usize) / 2 + 1;
+        let size = if size_of::<T>() == 0 {
+            1
+        } else {
+            size_of::<T>()
+        };
+        let len = max_bytes / size;
+        if len == 0 {
+            1
+        } else if len > FixedUsize::MAX as usize {
+            FixedUsize::MAX as usize
+        } else {
+            len
+        }
+    }
+
+    /// Serializes an archived segmented vector from a slice, with at most
+    /// `segment_len` elements in each segment.
+    ///
+    /// `segment_len` is clamped to
+    /// [`max_segment_len`](ArchivedSegmentedVec::max_segment_len).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `segment_len` is zero.
+    pub fn serialize_from_slice<U, S>(
+        slice: &[U],
+        segment_len: usize,
+        serializer: &mut S,
+    ) -> Result<SegmentedVecResolver, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        assert!(segment_len > 0, "segment length must be nonzero");
+        let segment_len = segment_len.min(Self::max_segment_len());
+
+        // Segments are written back to front so that each segment can point to
+        // the segment after it, which was written just before it.
+        let mut next = None;
+        for chunk in slice.chunks(segment_len).rev() {
+            next = Some(SerVec::with_capacity(
+                serializer,
+                chunk.len(),
+                |resolvers, serializer| {
+                    for value in chunk.iter() {
+                        resolvers.push(value.serialize(serializer)?);
+                    }
+
+                    let data_pos = serializer.align_for::<T>()?;
+                    for (value, resolver) in chunk.iter().zip(resolvers.drain())
+                    {
+                        U::check_resolver(&resolver, serializer.pos())?;
+                        unsafe {
+                            serializer.resolve_aligned(value, resolver)?;
+                        }
+                    }
+
+                    let header = SegmentHeader::<T> {
+                        next,
+                        data_pos,
+                        len: chunk.len(),
+                        _phantom: PhantomData,
+                    };
+                    let header_pos =
+                        serializer.align_for::<ArchivedSegment<T>>()?;
+                    if let Some(next) = next {
+                        check_offset::<S::Error>(header_pos, next)?;
+                    }
+                    check_offset::<S::Error>(
+                        header_pos + size_of::<RelPtr<ArchivedSegment<T>>>(),
+                        data_pos,
+                    )?;
+                    unsafe { serializer.resolve_aligned(&header, ()) }
+                },
+            )??);
+        }
+
+        Ok(SegmentedVecResolver { head: next })
+    }
+
+    /// Resolves an archived segmented vector from a given length and resolver.
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: SegmentedVecResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedSegmentedVec { head, len: out_len } = out);
+        match resolver.head {
+            Some(pos) => RelPtr::emplace(pos, head),
+            None => RelPtr::emplace_invalid(head),
+        }
+        out_len.write(ArchivedU64::from_native(len as u64));
+    }
+}
+
+fn check_offset<E: Source>(from: usize, to: usize) -> Result<(), E> {
+    ArchivedIsize::from_isize::<E>(signed_offset(from, to)?)?;
+    Ok(())
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedSegmentedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for ArchivedSegmentedVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for ArchivedSegmentedVec<T> {}
+
+impl<T: hash::Hash> hash::Hash for ArchivedSegmentedVec<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for value in self.iter() {
+            value.hash(state);
+        }
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for ArchivedSegmentedVec<T> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.len() == other.len() as u64
+            && self.iter().zip(other.iter()).all(|(a, b)| a.eq(b))
+    }
+}
+
+/// The resolver for an [`ArchivedSegmentedVec`].
+pub struct SegmentedVecResolver {
+    head: Option<usize>,
+}
+
+/// The header of a segment whose elements have already been written.
+struct SegmentHeader<T> {
+    next: Option<usize>,
+    data_pos: usize,
+    len: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> Archive for SegmentHeader<T> {
+    type Archived = ArchivedSegment<T>;
+    type Resolver = ();
+
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedSegment { next, data, len } = out);
+        match self.next {
+            Some(pos) => RelPtr::emplace(pos, next),
+            None => RelPtr::emplace_invalid(next),
+        }
+        RelPtr::emplace(self.data_pos, data);
+        len.write(ArchivedUsize::from_native(self.len as FixedUsize));
+    }
+}
+
+/// An iterator over the segments of an [`ArchivedSegmentedVec`].
+pub struct Segments<'a, T> {
+    next: Option<&'a ArchivedSegment<T>>,
+}
+
+impl<'a, T> Iterator for Segments<'a, T> {
+    type Item = &'a ArchivedSegment<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let segment = self.next?;
+        self.next = segment.next();
+        Some(segment)
+    }
+}
+
+impl<T> FusedIterator for Segments<'_, T> {}
+
+/// An iterator over the elements of an [`ArchivedSegmentedVec`].
+pub struct Iter<'a, T> {
+    current: slice::Iter<'a, T>,
+    segments: Segments<'a, T>,
+    remaining: u64,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.current.next() {
+                self.remaining -= 1;
+                return Some(value);
+            }
+            self.current = self.segments.next()?.as_slice().iter();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.remaining).ok();
+        (remaining.unwrap_or(usize::MAX), remaining)
+    }
+}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a ArchivedSegmentedVec<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{alloc::Layout, error::Error, fmt, ptr};
+
+    use bytecheck::{CheckBytes, Verify};
+    use rancor::{fail, Fallible, Source};
+
+    use super::{ArchivedSegment, ArchivedSegmentedVec};
+    use crate::{
+        primitive::ArchivedUsize,
+        validation::{ArchiveContext, ArchiveContextExt},
+        RelPtr,
+    };
+
+    /// The number of segments which are claimed from a buffer on the stack.
+    /// Longer chains are split in half until they fit.
+    const CLAIM_BATCH: usize = 32;
+
+    #[derive(Debug)]
+    struct EmptySegment;
+
+    impl fmt::Display for EmptySegment {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "segmented vec contains an empty segment")
+        }
+    }
+
+    impl Error for EmptySegment {}
+
+    #[derive(Debug)]
+    struct UnorderedSegment;
+
+    impl fmt::Display for UnorderedSegment {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "segmented vec contains a segment which is not located before \
+                 the segment that points to it",
+            )
+        }
+    }
+
+    impl Error for UnorderedSegment {}
+
+    #[derive(Debug)]
+    struct LengthMismatch {
+        expected: u64,
+        actual: u64,
+    }
+
+    impl fmt::Display for LengthMismatch {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "segmented vec length {} does not match the total length of \
+                 its segments {}",
+                self.expected, self.actual,
+            )
+        }
+    }
+
+    impl Error for LengthMismatch {}
+
+    /// Returns the segment after the given segment.
+    ///
+    /// # Safety
+    ///
+    /// `segment` must point to a segment which is located within the archive.
+    unsafe fn next<T>(
+        segment: *const ArchivedSegment<T>,
+    ) -> *const ArchivedSegment<T> {
+        unsafe { (*segment).next.as_ptr_wrapping() }
+    }
+
+    /// Returns the number of segments in the chain which starts at `first`.
+    ///
+    /// Each segment must be located before the one which points to it, so the
+    /// chain can't contain cycles.
+    fn chain_len<T, C>(
+        first: &RelPtr<ArchivedSegment<T>>,
+        context: &mut C,
+    ) -> Result<usize, C::Error>
+    where
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        let mut len = 0;
+        let mut current = first;
+        let mut previous = ptr::from_ref(first).cast::<u8>();
+        while !current.is_invalid() {
+            let segment = current.as_ptr_wrapping();
+            context.check_subtree_ptr(
+                segment.cast(),
+                &Layout::new::<ArchivedSegment<T>>(),
+            )?;
+            if segment.cast::<u8>() >= previous {
+                fail!(UnorderedSegment);
+            }
+
+            len += 1;
+            previous = segment.cast();
+            // SAFETY: We checked that the segment is located within the
+            // archive.
+            current = unsafe { &(*segment).next };
+        }
+        Ok(len)
+    }
+
+    /// Claims the data of the given segment.
+    fn claim_data<T, C>(
+        segment: &ArchivedSegment<T>,
+        context: &mut C,
+    ) -> Result<(), C::Error>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        if segment.is_empty() {
+            fail!(EmptySegment);
+        }
+
+        let data = ptr::slice_from_raw_parts(
+            segment.data.as_ptr_wrapping(),
+            segment.len(),
+        );
+        context.in_subtree(data, |context| unsafe {
+            <[T]>::check_bytes(data, context)
+        })
+    }
+
+    /// Claims the data and header of the given segment.
+    ///
+    /// # Safety
+    ///
+    /// `segment` must point to a segment which is located within the archive.
+    unsafe fn claim_segment<T, C>(
+        segment: *const ArchivedSegment<T>,
+        context: &mut C,
+    ) -> Result<(), C::Error>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        // The data of each segment is written before its header.
+        claim_data(unsafe { &*segment }, context)?;
+        context.in_subtree(segment, |context| unsafe {
+            RelPtr::check_bytes(ptr::addr_of!((*segment).next), context)?;
+            RelPtr::check_bytes(ptr::addr_of!((*segment).data), context)?;
+            ArchivedUsize::check_bytes(ptr::addr_of!((*segment).len), context)
+        })
+    }
+
+    /// Claims `len` segments starting at `first` in reverse order.
+    ///
+    /// Later segments are located before earlier ones, so they must be claimed
+    /// first. Chains are split in half until they fit in a buffer on the stack,
+    /// which keeps both the recursion depth and the number of times each
+    /// segment is visited logarithmic in the length of the chain.
+    ///
+    /// # Safety
+    ///
+    /// `first` must start a chain of at least `len` segments which are located
+    /// within the archive.
+    unsafe fn claim_reversed<T, C>(
+        first: *const ArchivedSegment<T>,
+        len: usize,
+        context: &mut C,
+    ) -> Result<(), C::Error>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        if len <= CLAIM_BATCH {
+            let mut segments = [first; CLAIM_BATCH];
+            for i in 1..len {
+                segments[i] = unsafe { next(segments[i - 1]) };
+            }
+            for &segment in segments[..len].iter().rev() {
+                unsafe { claim_segment(segment, context)? };
+            }
+        } else {
+            let half = len / 2;
+            let mut middle = first;
+            for _ in 0..half {
+                middle = unsafe { next(middle) };
+            }
+            unsafe {
+                claim_reversed(middle, len - half, context)?;
+                claim_reversed(first, half, context)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Claims every segment in the chain which starts at `first`.
+    fn claim_chain<T, C>(
+        first: &RelPtr<ArchivedSegment<T>>,
+        context: &mut C,
+    ) -> Result<(), C::Error>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        let len = chain_len(first, context)?;
+        if len > 0 {
+            // SAFETY: `chain_len` checked that there are `len` segments in the
+            // chain, and that they are all located within the archive.
+            unsafe { claim_reversed(first.as_ptr_wrapping(), len, context)? };
+        }
+        Ok(())
+    }
+
+    unsafe impl<T, C> Verify<C> for ArchivedSegment<T>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            // The next segment is written before this segment's elements, so
+            // it must be claimed first.
+            claim_chain(&self.next, context)?;
+            claim_data(self, context)
+        }
+    }
+
+    unsafe impl<T, C> Verify<C> for ArchivedSegmentedVec<T>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            claim_chain(&self.head, context)?;
+
+            let actual =
+                self.segments().map(|segment| segment.len() as u64).sum();
+            if actual != self.len() {
+                fail!(LengthMismatch {
+                    expected: self.len(),
+                    actual,
+                });
+            }
+
+            Ok(())
+        }
+    }
+}
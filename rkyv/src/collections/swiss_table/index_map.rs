@@ -18,7 +18,7 @@ use crate::{
         swiss_table::{ArchivedHashTable, HashTableResolver},
         util::{Entry, EntryAdapter, EntryResolver},
     },
-    hash::{hash_value, FxHasher64},
+    hash::{hash_value, FxHasher64, HashStream},
     primitive::{ArchivedUsize, FixedUsize},
     seal::Seal,
     ser::{Allocator, Writer, WriterExt as _},
@@ -87,6 +87,121 @@ impl<K, V, H> ArchivedIndexMap<K, V, H> {
             inner: unsafe { self.raw_iter() },
         }
     }
+
+    /// Gets a key-value pair by index.
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        if index < self.len() {
+            let entry = &self.entries()[index];
+            Some((&entry.key, &entry.value))
+        } else {
+            None
+        }
+    }
+
+    /// Gets the index of the key with the given hash which satisfies the given
+    /// comparison function.
+    pub(crate) fn get_index_of_hashed<C>(
+        &self,
+        hash: u64,
+        cmp: C,
+    ) -> Option<usize>
+    where
+        C: Fn(&K) -> bool,
+    {
+        let entries = self.entries();
+        let index = self
+            .table
+            .get_with(hash, |i| cmp(&entries[i.to_native() as usize].key))?;
+        Some(index.to_native() as usize)
+    }
+
+    /// Resolves an archived index map from a given length and parameters.
+    pub fn resolve_from_len(
+        len: usize,
+        load_factor: (usize, usize),
+        resolver: IndexMapResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedIndexMap { table, entries, _phantom: _ } = out);
+        ArchivedHashTable::resolve_from_len(
+            len,
+            load_factor,
+            resolver.table_resolver,
+            table,
+        );
+        RelPtr::emplace(resolver.entries_pos as usize, entries);
+    }
+
+    /// Serializes an iterator of key-value pairs as an index map, hashing
+    /// each key with the given function.
+    pub(crate) fn serialize_from_iter_hashed<I, BKU, BVU, KU, VU, F, S>(
+        iter: I,
+        hash: F,
+        load_factor: (usize, usize),
+        serializer: &mut S,
+    ) -> Result<IndexMapResolver, S::Error>
+    where
+        I: Clone + ExactSizeIterator<Item = (BKU, BVU)>,
+        BKU: Borrow<KU>,
+        BVU: Borrow<VU>,
+        KU: Serialize<S, Archived = K> + Hash + Eq,
+        VU: Serialize<S, Archived = V>,
+        F: Fn(&KU) -> u64,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        use crate::util::SerVec;
+
+        // Serialize hash table
+        let table_resolver =
+            ArchivedHashTable::<ArchivedUsize>::serialize_from_iter_by(
+                0..iter.len(),
+                iter.clone().map(|(key, _)| hash(key.borrow())),
+                // Entries keep their order in the map, so colliding entries
+                // can be ordered by their indices. Big-endian bytes sort in
+                // the same order as the indices themselves.
+                |index: &usize, stream: &mut HashStream<'_>| {
+                    stream.write(&index.to_be_bytes())
+                },
+                load_factor,
+                serializer,
+            )?;
+
+        // Serialize entries
+        SerVec::with_capacity(
+            serializer,
+            iter.len(),
+            |resolvers, serializer| {
+                for (key, value) in iter.clone() {
+                    resolvers.push(EntryResolver {
+                        key: key.borrow().serialize(serializer)?,
+                        value: value.borrow().serialize(serializer)?,
+                    });
+                }
+
+                let entries_pos = serializer.align_for::<Entry<K, V>>()?;
+                for ((key, value), resolver) in
+                    iter.clone().zip(resolvers.drain())
+                {
+                    EntryAdapter::<BKU, BVU, KU, VU>::check_resolver(
+                        &resolver,
+                        serializer.pos(),
+                    )?;
+                    unsafe {
+                        serializer.resolve_aligned(
+                            &EntryAdapter::new(key, value),
+                            resolver,
+                        )?;
+                    }
+                }
+
+                Ok(IndexMapResolver {
+                    table_resolver,
+                    entries_pos: entries_pos as FixedUsize,
+                })
+            },
+        )?
+    }
 }
 
 impl<K, V, H: Hasher + Default> ArchivedIndexMap<K, V, H> {
@@ -249,16 +364,6 @@ impl<K, V, H: Hasher + Default> ArchivedIndexMap<K, V, H> {
         self.get(key).is_some()
     }
 
-    /// Gets a key-value pair by index.
-    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
-        if index < self.len() {
-            let entry = &self.entries()[index];
-            Some((&entry.key, &entry.value))
-        } else {
-            None
-        }
-    }
-
     /// Gets the index of a key if it exists in the map using the given
     /// comparison function.
     pub fn get_index_of_with<Q, C>(&self, key: &Q, cmp: C) -> Option<usize>
@@ -266,11 +371,7 @@ impl<K, V, H: Hasher + Default> ArchivedIndexMap<K, V, H> {
         Q: Hash + Eq + ?Sized,
         C: Fn(&Q, &K) -> bool,
     {
-        let entries = self.entries();
-        let index = self.table.get_with(hash_value::<Q, H>(key), |i| {
-            cmp(key, &entries[i.to_native() as usize].key)
-        })?;
-        Some(index.to_native() as usize)
+        self.get_index_of_hashed(hash_value::<Q, H>(key), |k| cmp(key, k))
     }
 
     /// Gets the index of a key if it exists in the map.
@@ -282,23 +383,6 @@ impl<K, V, H: Hasher + Default> ArchivedIndexMap<K, V, H> {
         self.get_index_of_with(key, |q, k| q == k.borrow())
     }
 
-    /// Resolves an archived index map from a given length and parameters.
-    pub fn resolve_from_len(
-        len: usize,
-        load_factor: (usize, usize),
-        resolver: IndexMapResolver,
-        out: Place<Self>,
-    ) {
-        munge!(let ArchivedIndexMap { table, entries, _phantom: _ } = out);
-        ArchivedHashTable::resolve_from_len(
-            len,
-            load_factor,
-            resolver.table_resolver,
-            table,
-        );
-        RelPtr::emplace(resolver.entries_pos as usize, entries);
-    }
-
     /// Serializes an iterator of key-value pairs as an index map.
     pub fn serialize_from_iter<I, BKU, BVU, KU, VU, S>(
         iter: I,
@@ -314,48 +398,12 @@ impl<K, V, H: Hasher + Default> ArchivedIndexMap<K, V, H> {
         S: Fallible + Writer + Allocator + ?Sized,
         S::Error: Source,
     {
-        use crate::util::SerVec;
-
-        // Serialize hash table
-        let table_resolver =
-            ArchivedHashTable::<ArchivedUsize>::serialize_from_iter(
-                0..iter.len(),
-                iter.clone()
-                    .map(|(key, _)| hash_value::<KU, H>(key.borrow())),
-                load_factor,
-                serializer,
-            )?;
-
-        // Serialize entries
-        SerVec::with_capacity(
+        Self::serialize_from_iter_hashed(
+            iter,
+            |key: &KU| hash_value::<KU, H>(key),
+            load_factor,
             serializer,
-            iter.len(),
-            |resolvers, serializer| {
-                for (key, value) in iter.clone() {
-                    resolvers.push(EntryResolver {
-                        key: key.borrow().serialize(serializer)?,
-                        value: value.borrow().serialize(serializer)?,
-                    });
-                }
-
-                let entries_pos = serializer.align_for::<Entry<K, V>>()?;
-                for ((key, value), resolver) in
-                    iter.clone().zip(resolvers.drain())
-                {
-                    unsafe {
-                        serializer.resolve_aligned(
-                            &EntryAdapter::new(key, value),
-                            resolver,
-                        )?;
-                    }
-                }
-
-                Ok(IndexMapResolver {
-                    table_resolver,
-                    entries_pos: entries_pos as FixedUsize,
-                })
-            },
-        )?
+        )
     }
 }
 
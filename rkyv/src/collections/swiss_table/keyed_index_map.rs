@@ -0,0 +1,283 @@
+//! An archived index map implementation based on Google's high-performance
+//! SwissTable hash map and a keyed hasher.
+
+use core::{borrow::Borrow, fmt, hash::Hash};
+
+use munge::munge;
+use rancor::{Fallible, Source};
+
+use crate::{
+    collections::swiss_table::index_map::{
+        ArchivedIndexMap, IndexMapResolver, Iter, Keys, Values,
+    },
+    hash::{
+        hash_value_keyed, ArchivedHashSeed, HashSeed, KeyedHasher, SipHasher13,
+    },
+    ser::{Allocator, Writer},
+    Place, Portable, Serialize,
+};
+
+/// An archived `IndexMap` which hashes its keys with a seeded [`KeyedHasher`].
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedKeyedIndexMap<K, V, H = SipHasher13> {
+    seed: ArchivedHashSeed,
+    inner: ArchivedIndexMap<K, V, H>,
+}
+
+impl<K, V, H> ArchivedKeyedIndexMap<K, V, H> {
+    /// Returns the seed used to hash the keys of the index map.
+    pub const fn seed(&self) -> HashSeed {
+        self.seed.to_native()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub const fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Gets the number of items in the index map.
+    pub const fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns an iterator over the key-value pairs of the map in order
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.inner.iter()
+    }
+
+    /// Returns an iterator over the keys of the map in order
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        self.inner.keys()
+    }
+
+    /// Returns an iterator over the values of the map in order.
+    pub fn values(&self) -> Values<'_, K, V> {
+        self.inner.values()
+    }
+
+    /// Gets a key-value pair by index.
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.inner.get_index(index)
+    }
+
+    /// Resolves an archived keyed index map from a given length and
+    /// parameters.
+    pub fn resolve_from_len(
+        len: usize,
+        load_factor: (usize, usize),
+        resolver: KeyedIndexMapResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedKeyedIndexMap { seed, inner } = out);
+        ArchivedHashSeed::emplace(resolver.seed, seed);
+        ArchivedIndexMap::resolve_from_len(
+            len,
+            load_factor,
+            resolver.inner,
+            inner,
+        );
+    }
+}
+
+impl<K, V, H: KeyedHasher> ArchivedKeyedIndexMap<K, V, H> {
+    fn hash<Q: Hash + ?Sized>(&self, key: &Q) -> u64 {
+        hash_value_keyed::<Q, H>(key, self.seed())
+    }
+
+    /// Gets the index of a key if it exists in the map using the given
+    /// comparison function.
+    pub fn get_index_of_with<Q, C>(&self, key: &Q, cmp: C) -> Option<usize>
+    where
+        Q: Hash + Eq + ?Sized,
+        C: Fn(&Q, &K) -> bool,
+    {
+        self.inner
+            .get_index_of_hashed(self.hash(key), |k| cmp(key, k))
+    }
+
+    /// Gets the index of a key if it exists in the map.
+    pub fn get_index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_index_of_with(key, |q, k| q == k.borrow())
+    }
+
+    /// Gets the index, key, and value corresponding to the supplied key using
+    /// the given comparison function.
+    pub fn get_full_with<Q, C>(
+        &self,
+        key: &Q,
+        cmp: C,
+    ) -> Option<(usize, &K, &V)>
+    where
+        Q: Hash + Eq + ?Sized,
+        C: Fn(&Q, &K) -> bool,
+    {
+        let index = self.get_index_of_with(key, cmp)?;
+        let (k, v) = self.get_index(index)?;
+        Some((index, k, v))
+    }
+
+    /// Gets the index, key, and value corresponding to the supplied key.
+    pub fn get_full<Q>(&self, key: &Q) -> Option<(usize, &K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_full_with(key, |q, k| q == k.borrow())
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (_, k, v) = self.get_full(key)?;
+        Some((k, v))
+    }
+
+    /// Returns a reference to the value corresponding to the supplied key using
+    /// the given comparison function.
+    pub fn get_with<Q, C>(&self, key: &Q, cmp: C) -> Option<&V>
+    where
+        Q: Hash + Eq + ?Sized,
+        C: Fn(&Q, &K) -> bool,
+    {
+        Some(self.get_full_with(key, cmp)?.2)
+    }
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        Some(self.get_full(key)?.2)
+    }
+
+    /// Returns whether a key is present in the hash map.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Serializes an iterator of key-value pairs as an index map keyed with
+    /// the given seed.
+    pub fn serialize_from_iter<I, BKU, BVU, KU, VU, S>(
+        iter: I,
+        seed: HashSeed,
+        load_factor: (usize, usize),
+        serializer: &mut S,
+    ) -> Result<KeyedIndexMapResolver, S::Error>
+    where
+        I: Clone + ExactSizeIterator<Item = (BKU, BVU)>,
+        BKU: Borrow<KU>,
+        BVU: Borrow<VU>,
+        KU: Serialize<S, Archived = K> + Hash + Eq,
+        VU: Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        let inner = ArchivedIndexMap::<K, V, H>::serialize_from_iter_hashed(
+            iter,
+            |key: &KU| hash_value_keyed::<KU, H>(key, seed),
+            load_factor,
+            serializer,
+        )?;
+        Ok(KeyedIndexMapResolver { seed, inner })
+    }
+}
+
+impl<K, V, H> fmt::Debug for ArchivedKeyedIndexMap<K, V, H>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, H> PartialEq for ArchivedKeyedIndexMap<K, V, H>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq, H> Eq for ArchivedKeyedIndexMap<K, V, H> {}
+
+/// The resolver for [`ArchivedKeyedIndexMap`].
+pub struct KeyedIndexMapResolver {
+    seed: HashSeed,
+    inner: IndexMapResolver,
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt, hash::Hash};
+
+    use bytecheck::Verify;
+    use rancor::{fail, Fallible, Source};
+
+    use super::ArchivedKeyedIndexMap;
+    use crate::hash::KeyedHasher;
+
+    #[derive(Debug)]
+    struct MismatchedSeed {
+        index: usize,
+    }
+
+    impl fmt::Display for MismatchedSeed {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "entry {} of keyed index map can't be found with the stored \
+                 hash seed",
+                self.index,
+            )
+        }
+    }
+
+    impl Error for MismatchedSeed {}
+
+    unsafe impl<C, K, V, H> Verify<C> for ArchivedKeyedIndexMap<K, V, H>
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+        K: Hash,
+        H: KeyedHasher,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            // Every key must be reachable by probing with the stored seed.
+            // Otherwise lookups would silently miss entries.
+            for (index, key) in self.keys().enumerate() {
+                let found =
+                    self.inner.get_index_of_hashed(self.hash(key), |k| {
+                        core::ptr::eq(k, key)
+                    });
+                if found != Some(index) {
+                    fail!(MismatchedSeed { index });
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
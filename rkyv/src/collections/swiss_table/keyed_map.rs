@@ -0,0 +1,329 @@
+//! Archived hash map implementation using an archived SwissTable and a keyed
+//! hasher.
+//!
+//! The seed for the hasher is stored alongside the hash table. Archives built
+//! from untrusted keys should use a random seed so that attackers can't choose
+//! keys which collide.
+
+use core::{borrow::Borrow, fmt, hash::Hash, ops::Index};
+
+use munge::munge;
+use rancor::{Fallible, Source};
+
+use crate::{
+    collections::swiss_table::map::{
+        ArchivedHashMap, HashMapResolver, Iter, IterMut, Keys, Values,
+        ValuesMut,
+    },
+    hash::{
+        hash_value_keyed, ArchivedHashSeed, HashSeed, KeyedHasher, SipHasher13,
+    },
+    seal::Seal,
+    ser::{Allocator, Writer},
+    Place, Portable, Serialize,
+};
+
+/// An archived SwissTable hash map which hashes its keys with a seeded
+/// [`KeyedHasher`].
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedKeyedHashMap<K, V, H = SipHasher13> {
+    seed: ArchivedHashSeed,
+    inner: ArchivedHashMap<K, V, H>,
+}
+
+impl<K, V, H> ArchivedKeyedHashMap<K, V, H> {
+    /// Returns the seed used to hash the keys of the hash map.
+    pub const fn seed(&self) -> HashSeed {
+        self.seed.to_native()
+    }
+
+    /// Returns whether the hash map is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the number of elements in the hash map.
+    pub const fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns the total capacity of the hash map.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Returns an iterator over the key-value entries in the hash map.
+    pub fn iter(&self) -> Iter<'_, K, V, H> {
+        self.inner.iter()
+    }
+
+    /// Returns an iterator over the sealed key-value entries in the hash map.
+    pub fn iter_seal(this: Seal<'_, Self>) -> IterMut<'_, K, V, H> {
+        munge!(let Self { inner, .. } = this);
+        ArchivedHashMap::iter_seal(inner)
+    }
+
+    /// Returns an iterator over the keys in the hash map.
+    pub fn keys(&self) -> Keys<'_, K, V, H> {
+        self.inner.keys()
+    }
+
+    /// Returns an iterator over the values in the hash map.
+    pub fn values(&self) -> Values<'_, K, V, H> {
+        self.inner.values()
+    }
+
+    /// Returns an iterator over the mutable values in the hash map.
+    pub fn values_seal(this: Seal<'_, Self>) -> ValuesMut<'_, K, V, H> {
+        munge!(let Self { inner, .. } = this);
+        ArchivedHashMap::values_seal(inner)
+    }
+
+    /// Resolves an archived keyed hash map from a given length and parameters.
+    pub fn resolve_from_len(
+        len: usize,
+        load_factor: (usize, usize),
+        resolver: KeyedHashMapResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedKeyedHashMap { seed, inner } = out);
+        ArchivedHashSeed::emplace(resolver.seed, seed);
+        ArchivedHashMap::resolve_from_len(
+            len,
+            load_factor,
+            resolver.inner,
+            inner,
+        );
+    }
+}
+
+impl<K, V, H: KeyedHasher> ArchivedKeyedHashMap<K, V, H> {
+    fn hash<Q: Hash + ?Sized>(&self, key: &Q) -> u64 {
+        hash_value_keyed::<Q, H>(key, self.seed())
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key using the
+    /// given comparison function.
+    pub fn get_key_value_with<Q, C>(&self, key: &Q, cmp: C) -> Option<(&K, &V)>
+    where
+        Q: Hash + Eq + ?Sized,
+        C: Fn(&Q, &K) -> bool,
+    {
+        self.inner
+            .get_key_value_hashed(self.hash(key), |k| cmp(key, k))
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value_with(key, |q, k| q == k.borrow())
+    }
+
+    /// Returns a reference to the value corresponding to the supplied key using
+    /// the given comparison function.
+    pub fn get_with<Q, C>(&self, key: &Q, cmp: C) -> Option<&V>
+    where
+        Q: Hash + Eq + ?Sized,
+        C: Fn(&Q, &K) -> bool,
+    {
+        Some(self.get_key_value_with(key, cmp)?.1)
+    }
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        Some(self.get_key_value(key)?.1)
+    }
+
+    /// Returns the mutable key-value pair corresponding to the supplied key
+    /// using the given comparison function.
+    pub fn get_key_value_seal_with<'a, Q, C>(
+        this: Seal<'a, Self>,
+        key: &Q,
+        cmp: C,
+    ) -> Option<(&'a K, Seal<'a, V>)>
+    where
+        Q: Hash + Eq + ?Sized,
+        C: Fn(&Q, &K) -> bool,
+    {
+        let hash = this.hash(key);
+        munge!(let Self { inner, .. } = this);
+        ArchivedHashMap::get_key_value_seal_hashed(inner, hash, |k| cmp(key, k))
+    }
+
+    /// Returns the mutable key-value pair corresponding to the supplied key.
+    pub fn get_key_value_seal<'a, Q>(
+        this: Seal<'a, Self>,
+        key: &Q,
+    ) -> Option<(&'a K, Seal<'a, V>)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        Self::get_key_value_seal_with(this, key, |q, k| q == k.borrow())
+    }
+
+    /// Returns a mutable reference to the value corresponding to the supplied
+    /// key.
+    pub fn get_seal<'a, Q>(this: Seal<'a, Self>, key: &Q) -> Option<Seal<'a, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        Some(Self::get_key_value_seal(this, key)?.1)
+    }
+
+    /// Returns whether the hash map contains the given key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Serializes an iterator of key-value pairs as a hash map keyed with the
+    /// given seed.
+    pub fn serialize_from_iter<I, BKU, BVU, KU, VU, S>(
+        iter: I,
+        seed: HashSeed,
+        load_factor: (usize, usize),
+        serializer: &mut S,
+    ) -> Result<KeyedHashMapResolver, S::Error>
+    where
+        I: Clone + ExactSizeIterator<Item = (BKU, BVU)>,
+        BKU: Borrow<KU>,
+        BVU: Borrow<VU>,
+        KU: Serialize<S, Archived = K> + Hash + Eq,
+        VU: Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        let inner = ArchivedHashMap::<K, V, H>::serialize_from_iter_hashed(
+            iter,
+            |key: &KU| hash_value_keyed::<KU, H>(key, seed),
+            load_factor,
+            serializer,
+        )?;
+        Ok(KeyedHashMapResolver { seed, inner })
+    }
+}
+
+impl<K, V, H> fmt::Debug for ArchivedKeyedHashMap<K, V, H>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, H> Eq for ArchivedKeyedHashMap<K, V, H>
+where
+    K: Hash + Eq,
+    V: Eq,
+    H: KeyedHasher,
+{
+}
+
+impl<K, V, H> PartialEq for ArchivedKeyedHashMap<K, V, H>
+where
+    K: Hash + Eq,
+    V: PartialEq,
+    H: KeyedHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            false
+        } else {
+            self.iter().all(|(key, value)| {
+                other.get(key).is_some_and(|v| *value == *v)
+            })
+        }
+    }
+}
+
+impl<K, Q, V, H> Index<&'_ Q> for ArchivedKeyedHashMap<K, V, H>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    H: KeyedHasher,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).unwrap()
+    }
+}
+
+/// The resolver for [`ArchivedKeyedHashMap`].
+pub struct KeyedHashMapResolver {
+    seed: HashSeed,
+    inner: HashMapResolver,
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt, hash::Hash, ptr};
+
+    use bytecheck::Verify;
+    use rancor::{fail, Fallible, Source};
+
+    use super::ArchivedKeyedHashMap;
+    use crate::hash::KeyedHasher;
+
+    #[derive(Debug)]
+    struct MismatchedSeed {
+        index: usize,
+    }
+
+    impl fmt::Display for MismatchedSeed {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "entry {} of keyed hash map can't be found with the stored \
+                 hash seed",
+                self.index,
+            )
+        }
+    }
+
+    impl Error for MismatchedSeed {}
+
+    unsafe impl<C, K, V, H> Verify<C> for ArchivedKeyedHashMap<K, V, H>
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+        K: Hash,
+        H: KeyedHasher,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            // Every key must be reachable by probing with the stored seed.
+            // Otherwise lookups would silently miss entries.
+            for (index, (key, _)) in self.iter().enumerate() {
+                let found = self
+                    .inner
+                    .get_key_value_hashed(self.hash(key), |k| ptr::eq(k, key));
+                if found.is_none() {
+                    fail!(MismatchedSeed { index });
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
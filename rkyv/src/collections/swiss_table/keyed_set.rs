@@ -0,0 +1,133 @@
+//! Archived hash set implementation using an archived SwissTable and a keyed
+//! hasher.
+//!
+//! The seed for the hasher is stored alongside the hash table. Archives built
+//! from untrusted keys should use a random seed so that attackers can't choose
+//! keys which collide.
+
+use core::{borrow::Borrow, fmt, hash::Hash};
+
+use munge::munge;
+use rancor::{Fallible, Source};
+
+use crate::{
+    collections::swiss_table::{
+        keyed_map::{ArchivedKeyedHashMap, KeyedHashMapResolver},
+        map::Keys,
+    },
+    hash::{HashSeed, KeyedHasher, SipHasher13},
+    ser::{Allocator, Writer},
+    Place, Portable, Serialize,
+};
+
+/// An archived `HashSet` which hashes its keys with a seeded [`KeyedHasher`].
+/// This is a wrapper around a keyed hash map with the same key and unit value.
+#[derive(Portable)]
+#[rkyv(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(transparent)]
+pub struct ArchivedKeyedHashSet<K, H = SipHasher13> {
+    inner: ArchivedKeyedHashMap<K, (), H>,
+}
+
+impl<K, H> ArchivedKeyedHashSet<K, H> {
+    /// Returns the seed used to hash the keys of the hash set.
+    pub const fn seed(&self) -> HashSeed {
+        self.inner.seed()
+    }
+
+    /// Gets the number of items in the hash set.
+    pub const fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns whether there are no items in the hash set.
+    pub const fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Gets an iterator over the keys of the underlying hash map.
+    pub fn iter(&self) -> Keys<'_, K, (), H> {
+        self.inner.keys()
+    }
+
+    /// Resolves an archived keyed hash set from the given length and
+    /// parameters.
+    pub fn resolve_from_len(
+        len: usize,
+        load_factor: (usize, usize),
+        resolver: KeyedHashSetResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedKeyedHashSet { inner } = out);
+        ArchivedKeyedHashMap::resolve_from_len(
+            len,
+            load_factor,
+            resolver.0,
+            inner,
+        );
+    }
+}
+
+impl<K, H: KeyedHasher> ArchivedKeyedHashSet<K, H> {
+    /// Gets the key corresponding to the given key in the hash set.
+    pub fn get<Q>(&self, k: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.inner.get_key_value(k).map(|(k, _)| k)
+    }
+
+    /// Returns whether the given key is in the hash set.
+    pub fn contains<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.inner.contains_key(k)
+    }
+
+    /// Serializes an iterator of keys as a hash set keyed with the given seed.
+    pub fn serialize_from_iter<I, KU, S>(
+        iter: I,
+        seed: HashSeed,
+        load_factor: (usize, usize),
+        serializer: &mut S,
+    ) -> Result<KeyedHashSetResolver, S::Error>
+    where
+        I: Clone + ExactSizeIterator,
+        I::Item: Borrow<KU>,
+        KU: Serialize<S, Archived = K> + Hash + Eq,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        Ok(KeyedHashSetResolver(
+            ArchivedKeyedHashMap::<K, (), H>::serialize_from_iter::<
+                _,
+                _,
+                (),
+                _,
+                _,
+                _,
+            >(iter.map(|x| (x, ())), seed, load_factor, serializer)?,
+        ))
+    }
+}
+
+impl<K: fmt::Debug, H> fmt::Debug for ArchivedKeyedHashSet<K, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Hash + Eq, H: KeyedHasher> PartialEq for ArchivedKeyedHashSet<K, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<K: Hash + Eq, H: KeyedHasher> Eq for ArchivedKeyedHashSet<K, H> {}
+
+/// The resolver for [`ArchivedKeyedHashSet`].
+pub struct KeyedHashSetResolver(KeyedHashMapResolver);
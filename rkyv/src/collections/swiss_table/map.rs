@@ -90,6 +90,84 @@ impl<K, V, H> ArchivedHashMap<K, V, H> {
             _phantom: PhantomData,
         }
     }
+
+    /// Returns the key-value pair with the given hash whose key satisfies the
+    /// given comparison function.
+    pub(crate) fn get_key_value_hashed<C>(
+        &self,
+        hash: u64,
+        cmp: C,
+    ) -> Option<(&K, &V)>
+    where
+        C: Fn(&K) -> bool,
+    {
+        let entry = self.table.get_with(hash, |e| cmp(&e.key))?;
+        Some((&entry.key, &entry.value))
+    }
+
+    /// Returns the mutable key-value pair with the given hash whose key
+    /// satisfies the given comparison function.
+    pub(crate) fn get_key_value_seal_hashed<C>(
+        this: Seal<'_, Self>,
+        hash: u64,
+        cmp: C,
+    ) -> Option<(&K, Seal<'_, V>)>
+    where
+        C: Fn(&K) -> bool,
+    {
+        munge!(let Self { table, .. } = this);
+        let entry =
+            ArchivedHashTable::get_seal_with(table, hash, |e| cmp(&e.key))?;
+        munge!(let Entry { key, value } = entry);
+        Some((key.unseal_ref(), value))
+    }
+
+    /// Serializes an iterator of key-value pairs as a hash map, hashing each
+    /// key with the given function.
+    pub(crate) fn serialize_from_iter_hashed<I, BKU, BVU, KU, VU, F, S>(
+        iter: I,
+        hash: F,
+        load_factor: (usize, usize),
+        serializer: &mut S,
+    ) -> Result<HashMapResolver, S::Error>
+    where
+        I: Clone + ExactSizeIterator<Item = (BKU, BVU)>,
+        BKU: Borrow<KU>,
+        BVU: Borrow<VU>,
+        KU: Serialize<S, Archived = K> + Hash + Eq,
+        VU: Serialize<S, Archived = V>,
+        F: Fn(&KU) -> u64,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        ArchivedHashTable::<Entry<K, V>>::serialize_from_iter_by(
+            iter.clone()
+                .map(|(key, value)| EntryAdapter::new(key, value)),
+            iter.map(|(key, _)| hash(key.borrow())),
+            |entry: &EntryAdapter<BKU, BVU, KU, VU>, stream| {
+                entry.key.borrow().hash(stream)
+            },
+            load_factor,
+            serializer,
+        )
+        .map(HashMapResolver)
+    }
+
+    /// Resolves an archived hash map from a given length and parameters.
+    pub fn resolve_from_len(
+        len: usize,
+        load_factor: (usize, usize),
+        resolver: HashMapResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedHashMap { table, _phantom: _ } = out);
+        ArchivedHashTable::<Entry<K, V>>::resolve_from_len(
+            len,
+            load_factor,
+            resolver.0,
+            table,
+        )
+    }
 }
 
 impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
@@ -100,10 +178,7 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
         Q: Hash + Eq + ?Sized,
         C: Fn(&Q, &K) -> bool,
     {
-        let entry = self
-            .table
-            .get_with(hash_value::<Q, H>(key), |e| cmp(key, &e.key))?;
-        Some((&entry.key, &entry.value))
+        self.get_key_value_hashed(hash_value::<Q, H>(key), |k| cmp(key, k))
     }
 
     /// Returns the key-value pair corresponding to the supplied key.
@@ -146,14 +221,9 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
         Q: Hash + Eq + ?Sized,
         C: Fn(&Q, &K) -> bool,
     {
-        munge!(let Self { table, .. } = this);
-        let entry = ArchivedHashTable::get_seal_with(
-            table,
-            hash_value::<Q, H>(key),
-            |e| cmp(key, &e.key),
-        )?;
-        munge!(let Entry { key, value } = entry);
-        Some((key.unseal_ref(), value))
+        Self::get_key_value_seal_hashed(this, hash_value::<Q, H>(key), |k| {
+            cmp(key, k)
+        })
     }
 
     /// Returns the mutable key-value pair corresponding to the supplied key.
@@ -217,30 +287,12 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
         S: Fallible + Writer + Allocator + ?Sized,
         S::Error: Source,
     {
-        ArchivedHashTable::<Entry<K, V>>::serialize_from_iter(
-            iter.clone()
-                .map(|(key, value)| EntryAdapter::new(key, value)),
-            iter.map(|(key, _)| hash_value::<KU, H>(key.borrow())),
+        Self::serialize_from_iter_hashed(
+            iter,
+            |key: &KU| hash_value::<KU, H>(key),
             load_factor,
             serializer,
         )
-        .map(HashMapResolver)
-    }
-
-    /// Resolves an archived hash map from a given length and parameters.
-    pub fn resolve_from_len(
-        len: usize,
-        load_factor: (usize, usize),
-        resolver: HashMapResolver,
-        out: Place<Self>,
-    ) {
-        munge!(let ArchivedHashMap { table, _phantom: _ } = out);
-        ArchivedHashTable::<Entry<K, V>>::resolve_from_len(
-            len,
-            load_factor,
-            resolver.0,
-            table,
-        )
     }
 }
 
@@ -2,12 +2,18 @@
 
 pub mod index_map;
 pub mod index_set;
+pub mod keyed_index_map;
+pub mod keyed_map;
+pub mod keyed_set;
 pub mod map;
 pub mod set;
 pub mod table;
 
 pub use index_map::{ArchivedIndexMap, IndexMapResolver};
 pub use index_set::{ArchivedIndexSet, IndexSetResolver};
+pub use keyed_index_map::{ArchivedKeyedIndexMap, KeyedIndexMapResolver};
+pub use keyed_map::{ArchivedKeyedHashMap, KeyedHashMapResolver};
+pub use keyed_set::{ArchivedKeyedHashSet, KeyedHashSetResolver};
 pub use map::{ArchivedHashMap, HashMapResolver};
 pub use set::{ArchivedHashSet, HashSetResolver};
 pub use table::{ArchivedHashTable, HashTableResolver};
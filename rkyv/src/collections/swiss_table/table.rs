@@ -24,6 +24,7 @@ use core::{
     fmt,
     marker::PhantomData,
     mem::{size_of, MaybeUninit},
+    ops::Range,
     ptr::{self, null, NonNull},
     slice::from_raw_parts,
 };
@@ -33,6 +34,7 @@ use rancor::{fail, Fallible, ResultExt as _, Source};
 
 use crate::{
     collections::util::IteratorLengthMismatch,
+    hash::HashStream,
     primitive::{ArchivedUsize, FixedUsize},
     seal::Seal,
     ser::{Allocator, Writer, WriterExt},
@@ -304,6 +306,10 @@ impl<T> ArchivedHashTable<T> {
     }
 
     /// Serializes an iterator of items as a hash table.
+    ///
+    /// When serializing canonically, items with equal hashes are inserted in
+    /// iteration order. Use a map or set type to order colliding items by
+    /// their contents instead.
     pub fn serialize_from_iter<I, U, H, S>(
         items: I,
         hashes: H,
@@ -317,6 +323,34 @@ impl<T> ArchivedHashTable<T> {
         H: ExactSizeIterator<Item = u64>,
         S: Fallible + Writer + Allocator + ?Sized,
         S::Error: Source,
+    {
+        Self::serialize_from_iter_by(
+            items,
+            hashes,
+            |_: &U, _: &mut HashStream<'_>| {},
+            load_factor,
+            serializer,
+        )
+    }
+
+    /// Serializes an iterator of items as a hash table. When serializing
+    /// canonically, items with equal hashes are ordered by the bytes that
+    /// `tie_break` writes for them.
+    pub(crate) fn serialize_from_iter_by<I, U, H, C, S>(
+        items: I,
+        hashes: H,
+        tie_break: C,
+        load_factor: (usize, usize),
+        serializer: &mut S,
+    ) -> Result<HashTableResolver, S::Error>
+    where
+        I: Clone + ExactSizeIterator,
+        I::Item: Borrow<U>,
+        U: Serialize<S, Archived = T>,
+        H: ExactSizeIterator<Item = u64>,
+        C: Fn(&U, &mut HashStream<'_>),
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
     {
         #[derive(Debug)]
         struct InvalidLoadFactor {
@@ -361,6 +395,103 @@ impl<T> ArchivedHashTable<T> {
             return Ok(HashTableResolver { pos: 0 });
         }
 
+        if serializer.is_canonical() {
+            // Insert items in order of their hashes so that the layout of the
+            // table doesn't depend on the iteration order of the items. Items
+            // with colliding hashes are ordered by the tie breaker.
+            SerVec::with_capacity(serializer, len, |sorted, serializer| {
+                for (item, hash) in items.zip(hashes) {
+                    sorted.push((item, hash, 0..0));
+                }
+                sorted.sort_unstable_by_key(|(_, hash, _)| *hash);
+
+                let mut start = 0;
+                while start < sorted.len() {
+                    let hash = sorted[start].1;
+                    let end = start
+                        + sorted[start..]
+                            .iter()
+                            .take_while(|(_, h, _)| *h == hash)
+                            .count();
+                    if end - start > 1 {
+                        Self::sort_colliding(
+                            &mut sorted[start..end],
+                            &tie_break,
+                            serializer,
+                        )?;
+                    }
+                    start = end;
+                }
+
+                Self::serialize_entries::<_, _, U, S>(
+                    sorted.drain().map(|(item, hash, _)| (item, hash)),
+                    len,
+                    load_factor,
+                    serializer,
+                )
+            })?
+        } else {
+            Self::serialize_entries::<_, _, U, S>(
+                items.zip(hashes),
+                len,
+                load_factor,
+                serializer,
+            )
+        }
+    }
+
+    /// Sorts items with colliding hashes by the bytes that `tie_break` writes
+    /// for them.
+    ///
+    /// The bytes for each item are recorded once, so sorting takes time linear
+    /// in the total length of the recorded bytes.
+    fn sort_colliding<B, U, C, S>(
+        items: &mut [(B, u64, Range<usize>)],
+        tie_break: &C,
+        serializer: &mut S,
+    ) -> Result<(), S::Error>
+    where
+        B: Borrow<U>,
+        U: ?Sized,
+        C: Fn(&U, &mut HashStream<'_>),
+        S: Fallible + Allocator + ?Sized,
+    {
+        let mut total = 0;
+        for (item, _, range) in items.iter_mut() {
+            let mut stream = HashStream::new(&mut []);
+            tie_break((*item).borrow(), &mut stream);
+            *range = total..total + stream.len();
+            total += stream.len();
+        }
+
+        SerVec::<u8>::with_capacity(serializer, total, |bytes, _| {
+            unsafe {
+                bytes.as_mut_ptr().write_bytes(0, total);
+                bytes.set_len(total);
+            }
+            for (item, _, range) in items.iter() {
+                let mut stream = HashStream::new(&mut bytes[range.clone()]);
+                tie_break(item.borrow(), &mut stream);
+            }
+            items.sort_unstable_by(|(_, _, a), (_, _, b)| {
+                bytes[a.clone()].cmp(&bytes[b.clone()])
+            });
+        })
+    }
+
+    fn serialize_entries<J, B, U, S>(
+        entries: J,
+        len: usize,
+        load_factor: (usize, usize),
+        serializer: &mut S,
+    ) -> Result<HashTableResolver, S::Error>
+    where
+        J: Iterator<Item = (B, u64)>,
+        B: Borrow<U>,
+        U: Serialize<S, Archived = T>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
         let capacity = Self::capacity_from_len(len, load_factor);
         let probe_cap = Self::probe_cap(capacity);
         let control_count = Self::control_count(probe_cap);
@@ -390,7 +521,7 @@ impl<T> ArchivedHashTable<T> {
 
                         let bucket_mask = Self::bucket_mask(control_count);
 
-                        for (item, hash) in items.zip(hashes) {
+                        for (item, hash) in entries {
                             let h2_hash = h2(hash);
                             let mut probe_seq = Self::probe_seq(hash, capacity);
 
@@ -460,10 +591,16 @@ impl<T> ArchivedHashTable<T> {
                                 let mut resolvers = resolvers.drain().rev();
                                 for item in ordered_items.iter().rev() {
                                     if let Some(item) = item {
+                                        let resolver =
+                                            resolvers.next().unwrap();
+                                        U::check_resolver(
+                                            &resolver,
+                                            serializer.pos(),
+                                        )?;
                                         unsafe {
                                             serializer.resolve_aligned(
                                                 item.borrow(),
-                                                resolvers.next().unwrap(),
+                                                resolver,
                                             )?;
                                         }
                                     } else {
@@ -1,6 +1,8 @@
 //! Utilities for archived collections.
 
-use core::{borrow::Borrow, error::Error, fmt, marker::PhantomData};
+use core::{
+    borrow::Borrow, error::Error, fmt, marker::PhantomData, mem::offset_of,
+};
 
 use munge::munge;
 use rancor::Fallible;
@@ -70,6 +72,20 @@ where
             value: self.value.borrow().serialize(serializer)?,
         })
     }
+
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        K::check_resolver(
+            &resolver.key,
+            pos + offset_of!(Entry<K::Archived, V::Archived>, key),
+        )?;
+        V::check_resolver(
+            &resolver.value,
+            pos + offset_of!(Entry<K::Archived, V::Archived>, value),
+        )
+    }
 }
 
 /// A key-value entry.
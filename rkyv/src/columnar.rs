@@ -0,0 +1,221 @@
+//! Columnar (struct-of-arrays) archiving for sequences of structs.
+//!
+//! Deriving `Archive` with `#[rkyv(columnar)]` generates a columns type which
+//! stores each field of the struct in its own contiguous [`ArchivedVec`], and a
+//! row type which borrows one element from each column. Sequences of the
+//! struct can then be archived in columnar form with
+//! [`Columnar`](crate::with::Columnar).
+//!
+//! [`ArchivedVec`]: crate::vec::ArchivedVec
+
+use core::{fmt, iter::FusedIterator};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    primitive::{ArchivedUsize, FixedUsize},
+    Archive, Place, Portable,
+};
+
+/// A type which can be archived as a set of columns.
+///
+/// This trait is implemented by deriving `Archive` with `#[rkyv(columnar)]`.
+pub trait ArchiveColumns: Archive {
+    /// The archived columns of a sequence of `Self`.
+    type Columns: Columns + Portable;
+    /// The resolver for the archived columns.
+    type ColumnsResolver;
+
+    /// Resolves the columns of `len` rows from a resolver.
+    fn resolve_columns(
+        len: usize,
+        resolver: Self::ColumnsResolver,
+        out: Place<Self::Columns>,
+    );
+}
+
+/// A type which can be serialized as a set of columns.
+///
+/// This trait is implemented by deriving `Serialize` with `#[rkyv(columnar)]`.
+pub trait SerializeColumns<S: Fallible + ?Sized>:
+    ArchiveColumns + Sized
+{
+    /// Writes the columns of the given rows to the serializer.
+    fn serialize_columns(
+        rows: &[Self],
+        serializer: &mut S,
+    ) -> Result<Self::ColumnsResolver, S::Error>;
+}
+
+/// The archived columns of a sequence of structs.
+pub trait Columns {
+    /// A row which borrows one element from each column.
+    type Row<'a>
+    where
+        Self: 'a;
+
+    /// Returns whether every column has exactly `len` elements.
+    fn has_len(&self, len: usize) -> bool;
+
+    /// Returns the row at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for any column.
+    fn row(&self, index: usize) -> Self::Row<'_>;
+}
+
+/// Archived columns which can be deserialized row by row.
+///
+/// This trait is implemented by deriving `Deserialize` with
+/// `#[rkyv(columnar)]`.
+pub trait DeserializeColumns<T, D: Fallible + ?Sized> {
+    /// Deserializes the row at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for any column.
+    fn deserialize_row(
+        &self,
+        index: usize,
+        deserializer: &mut D,
+    ) -> Result<T, D::Error>;
+}
+
+/// A sequence of structs archived as columns.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedColumnar<C> {
+    len: ArchivedUsize,
+    columns: C,
+}
+
+impl<C> ArchivedColumnar<C> {
+    /// Returns the number of rows.
+    pub fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns whether there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the columns.
+    pub fn columns(&self) -> &C {
+        &self.columns
+    }
+
+    /// Emplaces the length of the archived columns and returns an out pointer
+    /// to the columns.
+    pub fn emplace_len(len: usize, out: Place<Self>) -> Place<C> {
+        munge!(let ArchivedColumnar { len: out_len, columns } = out);
+        out_len.write(ArchivedUsize::from_native(len as FixedUsize));
+        columns
+    }
+}
+
+impl<C: Columns> ArchivedColumnar<C> {
+    /// Returns the row at the given index, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<C::Row<'_>> {
+        (index < self.len()).then(|| self.columns.row(index))
+    }
+
+    /// Returns an iterator over the rows.
+    pub fn rows(&self) -> Rows<'_, C> {
+        Rows {
+            columns: &self.columns,
+            index: 0,
+            len: self.len(),
+        }
+    }
+}
+
+impl<C: fmt::Debug> fmt::Debug for ArchivedColumnar<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedColumnar")
+            .field("len", &self.len())
+            .field("columns", &self.columns)
+            .finish()
+    }
+}
+
+/// An iterator over the rows of an [`ArchivedColumnar`].
+pub struct Rows<'a, C> {
+    columns: &'a C,
+    index: usize,
+    len: usize,
+}
+
+impl<'a, C: Columns> Iterator for Rows<'a, C> {
+    type Item = C::Row<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < self.len {
+            let row = self.columns.row(self.index);
+            self.index += 1;
+            Some(row)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<C: Columns> ExactSizeIterator for Rows<'_, C> {}
+
+impl<C: Columns> FusedIterator for Rows<'_, C> {}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt};
+
+    use bytecheck::Verify;
+    use rancor::{fail, Fallible, Source};
+
+    use super::{ArchivedColumnar, Columns};
+
+    #[derive(Debug)]
+    struct MismatchedColumnLength {
+        len: usize,
+    }
+
+    impl fmt::Display for MismatchedColumnLength {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "columnar archive has {} rows but not every column has that \
+                 many elements",
+                self.len,
+            )
+        }
+    }
+
+    impl Error for MismatchedColumnLength {}
+
+    unsafe impl<C, Ctx> Verify<Ctx> for ArchivedColumnar<C>
+    where
+        C: Columns,
+        Ctx: Fallible + ?Sized,
+        Ctx::Error: Source,
+    {
+        fn verify(&self, _: &mut Ctx) -> Result<(), Ctx::Error> {
+            if !self.columns.has_len(self.len()) {
+                fail!(MismatchedColumnLength { len: self.len() });
+            }
+
+            Ok(())
+        }
+    }
+}
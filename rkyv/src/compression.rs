@@ -0,0 +1,866 @@
+//! Compressed containers for archives.
+//!
+//! A container holds a serialized buffer compressed in independently
+//! decompressible blocks with a [`Codec`]. Containers are much smaller than
+//! archives, but must be decompressed into an [`AlignedVec`] before they can be
+//! accessed.
+//!
+//! A container starts with a header:
+//!
+//! - A four-byte magic number (`rkyz`)
+//! - The [`ID`](Codec::ID) of the codec
+//! - Three reserved bytes, which must be zero
+//! - The uncompressed size of each block as a little-endian `u32`
+//! - The number of blocks as a little-endian `u32`
+//! - The uncompressed length as a little-endian `u64`
+//!
+//! Each block follows as its compressed length as a little-endian `u32` and the
+//! compressed bytes. Every block except the last decompresses to exactly the
+//! block size.
+//!
+//! Individual fields can be compressed instead with
+//! [`Compressed`](crate::with::Compressed), which archives them as an
+//! [`ArchivedCompressed`] that is decompressed on demand.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     compression::{access_compressed, to_compressed_bytes, Lz4},
+//!     rancor::Error,
+//!     util::AlignedVec,
+//!     Archive, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! struct Example {
+//!     names: Vec<String>,
+//! }
+//!
+//! let value = Example {
+//!     names: vec!["hello world".to_string(); 1000],
+//! };
+//!
+//! let compressed = to_compressed_bytes::<Lz4, Error>(&value).unwrap();
+//! assert!(compressed.len() < rkyv::to_bytes::<Error>(&value).unwrap().len());
+//!
+//! let mut buffer = AlignedVec::<16>::new();
+//! let archived = access_compressed::<Lz4, ArchivedExample, Error, 16>(
+//!     &compressed,
+//!     &mut buffer,
+//! )
+//! .unwrap();
+//! assert_eq!(archived.names[999], "hello world");
+//! ```
+
+use core::{error::Error, fmt, marker::PhantomData};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    alloc::vec::Vec,
+    api::high::{to_bytes, HighSerializer},
+    primitive::ArchivedUsize,
+    ser::{allocator::ArenaHandle, Allocator, Writer},
+    util::AlignedVec,
+    vec::{ArchivedVec, VecResolver},
+    Archive as _, Place, Portable, Serialize,
+};
+
+/// The size of the header at the start of a container.
+pub const HEADER_SIZE: usize = 24;
+
+/// The default uncompressed size of each block: 256 KiB.
+pub const DEFAULT_BLOCK_SIZE: usize = 256 * 1024;
+
+const MAGIC: [u8; 4] = *b"rkyz";
+
+/// A block compression algorithm.
+pub trait Codec {
+    /// The identifier of the codec, which is stored in containers.
+    const ID: u8;
+
+    /// Returns the maximum compressed length of `len` bytes.
+    fn max_compressed_len(len: usize) -> usize;
+
+    /// Returns the maximum length that `len` compressed bytes can decompress
+    /// to.
+    ///
+    /// Decompression rejects lengths above this before allocating any output.
+    fn max_decompressed_len(len: usize) -> usize;
+
+    /// Compresses `input` and appends the compressed bytes to `output`.
+    fn compress(input: &[u8], output: &mut Vec<u8>);
+
+    /// Decompresses `input` into `output`, which is exactly the length of the
+    /// uncompressed bytes.
+    fn decompress<E: Source>(input: &[u8], output: &mut [u8]) -> Result<(), E>;
+}
+
+#[derive(Debug)]
+struct InvalidHeader;
+
+impl fmt::Display for InvalidHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid compressed container header")
+    }
+}
+
+impl Error for InvalidHeader {}
+
+#[derive(Debug)]
+struct CodecMismatch {
+    expected: u8,
+    found: u8,
+}
+
+impl fmt::Display for CodecMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "container was compressed with codec {} but codec {} was expected",
+            self.found, self.expected,
+        )
+    }
+}
+
+impl Error for CodecMismatch {}
+
+#[derive(Debug)]
+struct InvalidBlock {
+    index: usize,
+}
+
+impl fmt::Display for InvalidBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "block {} of the container is truncated or invalid",
+            self.index
+        )
+    }
+}
+
+impl Error for InvalidBlock {}
+
+#[derive(Debug)]
+struct TrailingBytes {
+    len: usize,
+}
+
+impl fmt::Display for TrailingBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} unexpected bytes after the last block", self.len)
+    }
+}
+
+impl Error for TrailingBytes {}
+
+#[derive(Debug)]
+struct UncompressedLenTooLarge {
+    len: usize,
+    max: usize,
+}
+
+impl fmt::Display for UncompressedLenTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "uncompressed length {} is larger than the maximum of {} for the \
+             compressed bytes",
+            self.len, self.max,
+        )
+    }
+}
+
+impl Error for UncompressedLenTooLarge {}
+
+struct Header {
+    block_size: usize,
+    block_count: usize,
+    len: usize,
+}
+
+impl Header {
+    fn to_bytes<C: Codec>(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0; HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&MAGIC);
+        bytes[4] = C::ID;
+        bytes[8..12].copy_from_slice(&(self.block_size as u32).to_le_bytes());
+        bytes[12..16].copy_from_slice(&(self.block_count as u32).to_le_bytes());
+        bytes[16..24].copy_from_slice(&(self.len as u64).to_le_bytes());
+        bytes
+    }
+
+    fn parse<C: Codec, E: Source>(bytes: &[u8]) -> Result<Self, E> {
+        if bytes.len() < HEADER_SIZE
+            || bytes[0..4] != MAGIC
+            || bytes[5..8] != [0; 3]
+        {
+            fail!(InvalidHeader);
+        }
+        if bytes[4] != C::ID {
+            fail!(CodecMismatch {
+                expected: C::ID,
+                found: bytes[4],
+            });
+        }
+
+        let block_size =
+            u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        let block_count =
+            u32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        let Ok(len) = usize::try_from(u64::from_le_bytes(
+            bytes[16..24].try_into().unwrap(),
+        )) else {
+            fail!(InvalidHeader);
+        };
+
+        let expected_count = match block_size {
+            0 => 0,
+            _ => len.div_ceil(block_size),
+        };
+        if (block_size == 0 && len != 0) || block_count != expected_count {
+            fail!(InvalidHeader);
+        }
+
+        Ok(Self {
+            block_size,
+            block_count,
+            len,
+        })
+    }
+
+    fn block_len(&self, index: usize) -> usize {
+        (self.len - index * self.block_size).min(self.block_size)
+    }
+}
+
+fn block_prefix<E: Source>(bytes: &[u8], index: usize) -> Result<usize, E> {
+    match bytes.get(..4) {
+        Some(prefix) => {
+            Ok(u32::from_le_bytes(prefix.try_into().unwrap()) as usize)
+        }
+        None => fail!(InvalidBlock { index }),
+    }
+}
+
+fn decompress_block<C: Codec, E: Source, const A: usize>(
+    header: &Header,
+    index: usize,
+    compressed: &[u8],
+    out: &mut AlignedVec<A>,
+) -> Result<(), E> {
+    let block_len = header.block_len(index);
+    if compressed.len() > C::max_compressed_len(block_len)
+        || block_len > C::max_decompressed_len(compressed.len())
+    {
+        fail!(InvalidBlock { index });
+    }
+
+    let start = out.len();
+    out.resize(start + block_len, 0);
+    C::decompress(compressed, &mut out.as_mut_slice()[start..])
+}
+
+/// Compresses a serialized buffer into a container with the default block
+/// size.
+pub fn compress<C: Codec>(bytes: &[u8]) -> Vec<u8> {
+    compress_with_block_size::<C>(bytes, DEFAULT_BLOCK_SIZE)
+}
+
+/// Compresses a serialized buffer into a container with the given block size.
+///
+/// # Panics
+///
+/// Panics if `block_size` is zero or greater than `u32::MAX`, or if `bytes`
+/// has more than `u32::MAX` blocks.
+pub fn compress_with_block_size<C: Codec>(
+    bytes: &[u8],
+    block_size: usize,
+) -> Vec<u8> {
+    assert!(block_size > 0, "block size must be greater than zero");
+    assert!(
+        block_size <= u32::MAX as usize,
+        "block size must fit in a u32"
+    );
+
+    let header = Header {
+        block_size,
+        block_count: bytes.len().div_ceil(block_size),
+        len: bytes.len(),
+    };
+    assert!(
+        header.block_count <= u32::MAX as usize,
+        "too many blocks for a container",
+    );
+
+    let mut result = Vec::with_capacity(HEADER_SIZE + bytes.len() / 2);
+    result.extend_from_slice(&header.to_bytes::<C>());
+    for block in bytes.chunks(block_size) {
+        let prefix = result.len();
+        result.extend_from_slice(&[0; 4]);
+        C::compress(block, &mut result);
+        let len = (result.len() - prefix - 4) as u32;
+        result[prefix..prefix + 4].copy_from_slice(&len.to_le_bytes());
+    }
+    result
+}
+
+/// Serializes a value and compresses it into a container.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub fn to_compressed_bytes<C, E>(
+    #[rustfmt::skip] value: &impl for<'a> Serialize<
+        HighSerializer<AlignedVec, ArenaHandle<'a>, E>,
+    >,
+) -> Result<Vec<u8>, E>
+where
+    C: Codec,
+    E: Source,
+{
+    Ok(compress::<C>(&to_bytes(value)?))
+}
+
+/// Decompresses a container and appends the serialized buffer to an
+/// [`AlignedVec`].
+///
+/// The decompressed bytes are written directly into `out`, so they have its
+/// alignment without being copied again. To access the archive, `out` should
+/// be empty.
+pub fn decompress_into_aligned<C, E, const A: usize>(
+    compressed: &[u8],
+    out: &mut AlignedVec<A>,
+) -> Result<(), E>
+where
+    C: Codec,
+    E: Source,
+{
+    let header = Header::parse::<C, E>(compressed)?;
+
+    let mut rest = &compressed[HEADER_SIZE..];
+    for index in 0..header.block_count {
+        let len = block_prefix(rest, index)?;
+        let Some(block) = rest.get(4..4 + len) else {
+            fail!(InvalidBlock { index });
+        };
+        decompress_block::<C, E, A>(&header, index, block, out)?;
+        rest = &rest[4 + len..];
+    }
+
+    if !rest.is_empty() {
+        fail!(TrailingBytes { len: rest.len() });
+    }
+
+    Ok(())
+}
+
+/// Decompresses a container into a new [`AlignedVec`].
+pub fn decompress<C, E>(compressed: &[u8]) -> Result<AlignedVec, E>
+where
+    C: Codec,
+    E: Source,
+{
+    let mut result = AlignedVec::new();
+    decompress_into_aligned::<C, E, 16>(compressed, &mut result)?;
+    Ok(result)
+}
+
+/// An archived value compressed with a [`Codec`].
+///
+/// The value is serialized into its own archive, which is compressed as a
+/// single block and stored with its uncompressed length. It must be
+/// decompressed before it can be accessed.
+///
+/// This is the archived form of [`Compressed`](crate::with::Compressed).
+#[derive(Portable)]
+#[rkyv(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(C)]
+pub struct ArchivedCompressed<T, C> {
+    data: ArchivedVec<u8>,
+    len: ArchivedUsize,
+    _phantom: PhantomData<(T, C)>,
+}
+
+impl<T, C> ArchivedCompressed<T, C> {
+    /// Returns the compressed bytes.
+    pub fn compressed_bytes(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+
+    /// Returns the length of the decompressed archive.
+    pub fn uncompressed_len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Compresses a serialized archive of a `T` and returns a resolver for it.
+    pub fn serialize_from_bytes<S>(
+        bytes: &[u8],
+        serializer: &mut S,
+    ) -> Result<CompressedResolver, S::Error>
+    where
+        C: Codec,
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        let mut compressed = Vec::new();
+        C::compress(bytes, &mut compressed);
+        Ok(CompressedResolver {
+            data: ArchivedVec::serialize_from_slice(&compressed, serializer)?,
+            data_len: compressed.len(),
+            len: bytes.len(),
+        })
+    }
+
+    /// Resolves an archived compressed value from a resolver.
+    pub fn resolve_from_resolver(
+        resolver: CompressedResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedCompressed { data, len, _phantom: _ } = out);
+        ArchivedVec::resolve_from_len(resolver.data_len, resolver.data, data);
+        resolver.len.resolve((), len);
+    }
+}
+
+impl<T, C: Codec> ArchivedCompressed<T, C> {
+    /// Decompresses the archive into a new [`AlignedVec`].
+    pub fn decompress<E: Source>(&self) -> Result<AlignedVec, E> {
+        let mut result = AlignedVec::new();
+        self.decompress_into_aligned(&mut result)?;
+        Ok(result)
+    }
+
+    /// Decompresses the archive into the given [`AlignedVec`], replacing its
+    /// contents.
+    ///
+    /// Fails without allocating if the uncompressed length is larger than the
+    /// compressed bytes could decompress to.
+    pub fn decompress_into_aligned<E: Source, const A: usize>(
+        &self,
+        out: &mut AlignedVec<A>,
+    ) -> Result<(), E> {
+        let len = self.uncompressed_len();
+        let max = C::max_decompressed_len(self.compressed_bytes().len());
+        if len > max {
+            fail!(UncompressedLenTooLarge { len, max });
+        }
+
+        out.clear();
+        out.resize(len, 0);
+        C::decompress(self.compressed_bytes(), out.as_mut_slice())
+    }
+}
+
+impl<T, C> fmt::Debug for ArchivedCompressed<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedCompressed")
+            .field("compressed_len", &self.data.len())
+            .field("uncompressed_len", &self.uncompressed_len())
+            .finish()
+    }
+}
+
+/// The resolver for [`ArchivedCompressed`].
+pub struct CompressedResolver {
+    data: VecResolver,
+    data_len: usize,
+    len: usize,
+}
+
+#[cfg(feature = "bytecheck")]
+mod checked {
+    use bytecheck::CheckBytes;
+    use rancor::Source;
+
+    use super::{decompress_into_aligned, ArchivedCompressed, Codec};
+    use crate::{
+        api::high::{access, HighValidator},
+        util::AlignedVec,
+        Portable,
+    };
+
+    impl<T, C> ArchivedCompressed<T, C>
+    where
+        T: Portable,
+        C: Codec,
+    {
+        /// Decompresses the archive into the given buffer and accesses the
+        /// value.
+        ///
+        /// The buffer is cleared first and can be reused once the returned
+        /// reference is dropped.
+        pub fn decompress_and_access<'a, E, const A: usize>(
+            &self,
+            buffer: &'a mut AlignedVec<A>,
+        ) -> Result<&'a T, E>
+        where
+            T: for<'b> CheckBytes<HighValidator<'b, E>>,
+            E: Source,
+        {
+            self.decompress_into_aligned(buffer)?;
+            access::<T, E>(buffer)
+        }
+    }
+
+    /// Decompresses a container into the given buffer and accesses the
+    /// archive.
+    ///
+    /// The buffer is cleared first and can be reused for other containers
+    /// once the returned reference is dropped.
+    ///
+    /// This is part of the [high-level API](crate::api::high).
+    pub fn access_compressed<'a, C, T, E, const A: usize>(
+        compressed: &[u8],
+        buffer: &'a mut AlignedVec<A>,
+    ) -> Result<&'a T, E>
+    where
+        C: Codec,
+        T: Portable + for<'b> CheckBytes<HighValidator<'b, E>>,
+        E: Source,
+    {
+        buffer.clear();
+        decompress_into_aligned::<C, E, A>(compressed, buffer)?;
+        access::<T, E>(buffer)
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+pub use self::checked::*;
+
+#[cfg(feature = "std")]
+mod io {
+    use std::io::Read;
+
+    use rancor::{fail, ResultExt as _, Source};
+
+    use super::{decompress_block, Codec, Header, InvalidBlock, HEADER_SIZE};
+    use crate::{alloc::vec::Vec, util::AlignedVec};
+
+    /// Decompresses a container from a reader directly into an
+    /// [`AlignedVec`].
+    ///
+    /// Only one compressed block is buffered at a time, and each block is
+    /// decompressed straight into the output. Unlike
+    /// [`decompress_into_aligned`](super::decompress_into_aligned), bytes
+    /// after the last block are not read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rkyv::{
+    ///     compression::{decompress_from_reader, to_compressed_bytes, Lz4},
+    ///     rancor::Error,
+    ///     Archived,
+    /// };
+    ///
+    /// let value = vec![42u32; 10_000];
+    /// let compressed = to_compressed_bytes::<Lz4, Error>(&value).unwrap();
+    ///
+    /// let mut reader = compressed.as_slice();
+    /// let result = decompress_from_reader::<Lz4, _, Error>(&mut reader);
+    /// let bytes = result.unwrap();
+    ///
+    /// let archived = rkyv::access::<Archived<Vec<u32>>, Error>(&bytes);
+    /// assert_eq!(archived.unwrap().len(), 10_000);
+    /// ```
+    pub fn decompress_from_reader<C, R, E>(
+        reader: &mut R,
+    ) -> Result<AlignedVec, E>
+    where
+        C: Codec,
+        R: Read + ?Sized,
+        E: Source,
+    {
+        let mut header = [0; HEADER_SIZE];
+        reader.read_exact(&mut header).into_error()?;
+        let header = Header::parse::<C, E>(&header)?;
+
+        let mut result = AlignedVec::new();
+        let mut block = Vec::new();
+        for index in 0..header.block_count {
+            let mut prefix = [0; 4];
+            reader.read_exact(&mut prefix).into_error()?;
+            let len = u32::from_le_bytes(prefix) as usize;
+            if len > C::max_compressed_len(header.block_len(index)) {
+                fail!(InvalidBlock { index });
+            }
+
+            // Read through `take` so that the block only grows as large as the
+            // bytes actually available.
+            block.clear();
+            reader
+                .take(len as u64)
+                .read_to_end(&mut block)
+                .into_error()?;
+            if block.len() != len {
+                fail!(InvalidBlock { index });
+            }
+            decompress_block::<C, E, 16>(&header, index, &block, &mut result)?;
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::io::*;
+
+#[cfg(feature = "lz4_flex-0_11")]
+mod lz4 {
+    use core::{error::Error, fmt};
+
+    use lz4_flex_0_11::block;
+    use rancor::{fail, ResultExt as _, Source};
+
+    use super::Codec;
+    use crate::alloc::vec::Vec;
+
+    /// The [LZ4](https://docs.rs/lz4_flex/0.11) block codec.
+    ///
+    /// # Example
+    ///
+    /// Compressing a field with [`Compressed`](crate::with::Compressed):
+    ///
+    /// ```
+    /// use rkyv::{
+    ///     compression::Lz4, rancor::Error, util::AlignedVec, with::Compressed,
+    ///     Archive, Archived, Deserialize, Serialize,
+    /// };
+    ///
+    /// #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    /// struct Page {
+    ///     title: String,
+    ///     #[rkyv(with = Compressed<Lz4>)]
+    ///     body: String,
+    /// }
+    ///
+    /// let page = Page {
+    ///     title: "Lorem".to_string(),
+    ///     body: "lorem ipsum ".repeat(1000),
+    /// };
+    /// let bytes = rkyv::to_bytes::<Error>(&page).unwrap();
+    /// let archived = rkyv::access::<ArchivedPage, Error>(&bytes).unwrap();
+    /// assert!(archived.body.compressed_bytes().len() < page.body.len());
+    ///
+    /// let mut buffer = AlignedVec::<16>::new();
+    /// let body: &Archived<String> = archived
+    ///     .body
+    ///     .decompress_and_access::<Error, 16>(&mut buffer)
+    ///     .unwrap();
+    /// assert_eq!(body, &page.body);
+    ///
+    /// let deserialized = rkyv::deserialize::<Page, Error>(archived).unwrap();
+    /// assert_eq!(deserialized, page);
+    /// ```
+    #[derive(Debug)]
+    pub struct Lz4;
+
+    #[derive(Debug)]
+    struct LengthMismatch {
+        expected: usize,
+        actual: usize,
+    }
+
+    impl fmt::Display for LengthMismatch {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "block decompressed to {} bytes but {} were expected",
+                self.actual, self.expected,
+            )
+        }
+    }
+
+    impl Error for LengthMismatch {}
+
+    impl Codec for Lz4 {
+        const ID: u8 = 1;
+
+        fn max_compressed_len(len: usize) -> usize {
+            block::get_maximum_output_size(len)
+        }
+
+        fn max_decompressed_len(len: usize) -> usize {
+            // Each byte of a sequence's match length extension adds at most
+            // 255 bytes of output, and no other part expands by more.
+            len.saturating_mul(255)
+        }
+
+        fn compress(input: &[u8], output: &mut Vec<u8>) {
+            let start = output.len();
+            output.resize(start + Self::max_compressed_len(input.len()), 0);
+            let len = block::compress_into(input, &mut output[start..])
+                .expect("output was sized for the maximum compressed length");
+            output.truncate(start + len);
+        }
+
+        fn decompress<E: Source>(
+            input: &[u8],
+            output: &mut [u8],
+        ) -> Result<(), E> {
+            let actual = block::decompress_into(input, output).into_error()?;
+            if actual != output.len() {
+                fail!(LengthMismatch {
+                    expected: output.len(),
+                    actual,
+                });
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "lz4_flex-0_11")]
+pub use self::lz4::*;
+
+#[cfg(all(test, feature = "lz4_flex-0_11", feature = "bytecheck"))]
+mod tests {
+    use rancor::{Failure, Panic};
+
+    use super::{
+        access_compressed, compress_with_block_size, decompress,
+        to_compressed_bytes, Lz4, HEADER_SIZE,
+    };
+    use crate::{
+        alloc::{string::ToString, vec::Vec},
+        util::AlignedVec,
+        Archived,
+    };
+
+    #[test]
+    fn compressed_roundtrip() {
+        let value = (0..1000).map(|i| i.to_string()).collect::<Vec<_>>();
+        let bytes = crate::to_bytes::<Panic>(&value).unwrap();
+
+        for block_size in [1, 7, 64, 1 << 20] {
+            let compressed =
+                compress_with_block_size::<Lz4>(&bytes, block_size);
+            let decompressed = decompress::<Lz4, Panic>(&compressed).unwrap();
+            assert_eq!(decompressed.as_slice(), bytes.as_slice());
+        }
+
+        let compressed = to_compressed_bytes::<Lz4, Panic>(&value).unwrap();
+        let mut buffer = AlignedVec::<16>::new();
+        buffer.extend_from_slice(b"stale");
+        let archived =
+            access_compressed::<Lz4, Archived<Vec<String>>, Panic, 16>(
+                &compressed,
+                &mut buffer,
+            )
+            .unwrap();
+        assert_eq!(archived[999], "999");
+
+        let empty = compress_with_block_size::<Lz4>(&[], 16);
+        assert_eq!(empty.len(), HEADER_SIZE);
+        assert!(decompress::<Lz4, Panic>(&empty).unwrap().is_empty());
+    }
+
+    #[test]
+    fn compressed_rejects_invalid_containers() {
+        let value = (0..1000).map(|i| i.to_string()).collect::<Vec<_>>();
+        let bytes = crate::to_bytes::<Panic>(&value).unwrap();
+        let compressed = compress_with_block_size::<Lz4>(&bytes, 256);
+
+        let truncated = &compressed[..compressed.len() - 1];
+        assert!(decompress::<Lz4, Failure>(truncated).is_err());
+
+        let mut trailing = compressed.clone();
+        trailing.push(0);
+        assert!(decompress::<Lz4, Failure>(&trailing).is_err());
+
+        let mut wrong_codec = compressed.clone();
+        wrong_codec[4] = 0xff;
+        assert!(decompress::<Lz4, Failure>(&wrong_codec).is_err());
+
+        let mut wrong_len = compressed.clone();
+        wrong_len[16] ^= 1;
+        assert!(decompress::<Lz4, Failure>(&wrong_len).is_err());
+
+        // A tiny block that claims to decompress to 4 GiB must be rejected
+        // before any output is allocated.
+        let huge = huge_block_container();
+        assert!(decompress::<Lz4, Failure>(&huge).is_err());
+    }
+
+    fn huge_block_container() -> Vec<u8> {
+        let mut result = compress_with_block_size::<Lz4>(&[0], 16);
+        result[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        result[16..24].copy_from_slice(&u64::from(u32::MAX).to_le_bytes());
+        result
+    }
+
+    #[test]
+    fn compressed_field_rejects_large_len() {
+        use crate::{
+            primitive::{ArchivedUsize, FixedUsize},
+            with::Compressed,
+            Archive, Serialize,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Test {
+            #[rkyv(with = Compressed<Lz4>)]
+            bytes: Vec<u8>,
+        }
+
+        let value = Test {
+            bytes: Vec::from([0; 16]),
+        };
+        let mut bytes = crate::to_bytes::<Panic>(&value).unwrap();
+        let archived = crate::access::<ArchivedTest, Failure>(&bytes).unwrap();
+        assert!(archived.bytes.decompress::<Failure>().is_ok());
+
+        let len = &archived.bytes.len as *const ArchivedUsize;
+        let pos = len as usize - bytes.as_ptr() as usize;
+        // SAFETY: `pos` is the position of the archived length in `bytes`.
+        unsafe {
+            bytes
+                .as_mut_ptr()
+                .add(pos)
+                .cast::<ArchivedUsize>()
+                .write_unaligned(ArchivedUsize::from_native(FixedUsize::MAX));
+        }
+
+        let archived = crate::access::<ArchivedTest, Failure>(&bytes).unwrap();
+        assert!(archived.bytes.decompress::<Failure>().is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn compressed_from_reader() {
+        use super::decompress_from_reader;
+
+        let value = (0..1000).map(|i| i.to_string()).collect::<Vec<_>>();
+        let bytes = crate::to_bytes::<Panic>(&value).unwrap();
+        let mut compressed = compress_with_block_size::<Lz4>(&bytes, 100);
+        compressed.extend_from_slice(b"next");
+
+        let mut reader = compressed.as_slice();
+        let decompressed =
+            decompress_from_reader::<Lz4, _, Panic>(&mut reader).unwrap();
+        assert_eq!(decompressed.as_slice(), bytes.as_slice());
+        assert_eq!(reader, b"next");
+
+        let mut reader = &compressed[..compressed.len() - 10];
+        let result = decompress_from_reader::<Lz4, _, Failure>(&mut reader);
+        assert!(result.is_err());
+
+        let huge = huge_block_container();
+        let mut reader = huge.as_slice();
+        let result = decompress_from_reader::<Lz4, _, Failure>(&mut reader);
+        assert!(result.is_err());
+
+        // A block length prefix larger than the rest of the input fails
+        // without allocating the whole block.
+        let mut long_prefix = huge_block_container();
+        long_prefix.truncate(HEADER_SIZE + 4);
+        long_prefix[HEADER_SIZE..].copy_from_slice(&(1u32 << 30).to_le_bytes());
+        let mut reader = long_prefix.as_slice();
+        let result = decompress_from_reader::<Lz4, _, Failure>(&mut reader);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! Embedding archives in binaries at build time.
+//!
+//! A build script can serialize a value with [`write_archive`], which validates
+//! the archive and writes it to `OUT_DIR`. The [`include_archived!`] macro then
+//! embeds the file in the binary with the correct alignment and accesses it
+//! without validating it again at runtime. Because the file isn't validated
+//! again, the macro must be invoked with `unsafe` before the type.
+//!
+//! Embedded archives start with a small header which records the format
+//! features that rkyv was built with. rkyv is usually built separately for
+//! build scripts, so the build dependency on rkyv must enable the same format
+//! features (`big_endian`, `unaligned`, and `pointer_width_*`) as the regular
+//! dependency. [`include_archived!`] checks the header at compile time and
+//! fails to compile if the formats don't match.
+//!
+//! # Example
+//!
+//! In `build.rs`:
+//!
+//! ```ignore
+//! use std::collections::HashMap;
+//!
+//! fn main() {
+//!     let table = HashMap::from([("one".to_string(), 1u32)]);
+//!     rkyv::embed::write_archive::<_, rkyv::rancor::Error>(
+//!         &table,
+//!         "table.rkyv",
+//!     )
+//!     .unwrap();
+//! }
+//! ```
+//!
+//! In the crate:
+//!
+//! ```ignore
+//! use std::collections::HashMap;
+//!
+//! use rkyv::{include_archived, Archived};
+//!
+//! // SAFETY: `table.rkyv` was written by `write_archive` for a
+//! // `HashMap<String, u32>` in the build script.
+//! static TABLE: &Archived<HashMap<String, u32>> = include_archived!(
+//!     unsafe HashMap<String, u32>,
+//!     concat!(env!("OUT_DIR"), "/table.rkyv"),
+//! );
+//!
+//! assert_eq!(TABLE.get("one"), Some(&1));
+//! ```
+//!
+//! [`include_archived!`]: crate::include_archived
+
+use core::mem::{align_of, size_of};
+
+use crate::{primitive::FixedUsize, Portable};
+
+/// The size of the header at the start of an embedded archive.
+pub const HEADER_SIZE: usize = 16;
+
+const MAGIC: [u8; 4] = *b"rkye";
+
+/// The format features that rkyv was built with.
+///
+/// Bit 0 is set for `big_endian`, bit 1 is set for `unaligned`, and bits 8-15
+/// hold the size of archived `usize`s in bytes.
+pub const FORMAT: u32 = (cfg!(feature = "big_endian") as u32)
+    | ((cfg!(feature = "unaligned") as u32) << 1)
+    | ((size_of::<FixedUsize>() as u32) << 8);
+
+const fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[pos],
+        bytes[pos + 1],
+        bytes[pos + 2],
+        bytes[pos + 3],
+    ])
+}
+
+/// Returns the header of an embedded archive with a root of type `T`.
+pub const fn header<T: Portable>() -> [u8; HEADER_SIZE] {
+    let format = FORMAT.to_le_bytes();
+    let size = (size_of::<T>() as u32).to_le_bytes();
+    let align = (align_of::<T>() as u32).to_le_bytes();
+    [
+        MAGIC[0], MAGIC[1], MAGIC[2], MAGIC[3], format[0], format[1],
+        format[2], format[3], size[0], size[1], size[2], size[3], align[0],
+        align[1], align[2], align[3],
+    ]
+}
+
+/// Checks the header of an embedded archive with a root of type `T`.
+///
+/// This is called by [`include_archived!`] at compile time.
+///
+/// # Panics
+///
+/// Panics if the bytes don't start with a header for the format features that
+/// rkyv was built with and a root of type `T`.
+#[doc(hidden)]
+pub const fn check_embedded<T: Portable>(bytes: &[u8]) {
+    if bytes.len() < HEADER_SIZE + size_of::<T>()
+        || bytes[0] != MAGIC[0]
+        || bytes[1] != MAGIC[1]
+        || bytes[2] != MAGIC[2]
+        || bytes[3] != MAGIC[3]
+    {
+        panic!("file is not an embedded archive written by rkyv::embed");
+    }
+    if read_u32(bytes, 4) != FORMAT {
+        panic!(
+            "embedded archive was written with different format features; the \
+             build dependency on rkyv must enable the same format features as \
+             the regular dependency"
+        );
+    }
+    if read_u32(bytes, 8) != size_of::<T>() as u32
+        || read_u32(bytes, 12) != align_of::<T>() as u32
+    {
+        panic!("embedded archive has a different root type");
+    }
+}
+
+/// Accesses an embedded archive with a root of type `T`.
+///
+/// This is called by [`include_archived!`].
+///
+/// # Safety
+///
+/// The bytes must be aligned to 16 bytes, and must have been written by
+/// [`write_archive`] or [`to_embedded_bytes`] for a value with an archived
+/// type of `T`.
+#[doc(hidden)]
+pub const unsafe fn access_embedded<T: Portable>(bytes: &[u8]) -> &T {
+    // SAFETY: The caller has guaranteed that the bytes contain a valid archive
+    // with a root of type `T`, which is located at the end of the bytes.
+    unsafe { &*bytes.as_ptr().add(bytes.len() - size_of::<T>()).cast() }
+}
+
+/// Embeds an archive written by [`write_archive`] in the binary and accesses
+/// it.
+///
+/// The first argument is `unsafe` followed by the unarchived root type, and the
+/// second is the path of the archive, like for [`include_bytes!`]. The macro
+/// evaluates to a `&'static Archived<T>`, and can be used to initialize a
+/// `static`. The archive is validated when it is written, and its header is
+/// checked at compile time, so it is not validated again at runtime.
+///
+/// See the [module docs](crate::embed) for an example.
+///
+/// # Safety
+///
+/// The file must have been written by [`write_archive`] or
+/// [`to_embedded_bytes`] for a value of the same type. Other files with a
+/// valid header are not detected, and accessing them is undefined behavior.
+/// The `unsafe` token acknowledges this requirement, like an `unsafe` block.
+///
+/// [`write_archive`]: crate::embed::write_archive
+/// [`to_embedded_bytes`]: crate::embed::to_embedded_bytes
+#[macro_export]
+macro_rules! include_archived {
+    (unsafe $ty:ty, $path:expr $(,)?) => {{
+        const LEN: usize = ::core::include_bytes!($path).len();
+        const DATA: $crate::util::Align<[u8; LEN]> =
+            $crate::util::Align(*::core::include_bytes!($path));
+        const _: () =
+            $crate::embed::check_embedded::<$crate::Archived<$ty>>(&DATA.0);
+        static STORAGE: $crate::util::Align<[u8; LEN]> = DATA;
+        // SAFETY: `STORAGE` is aligned to 16 bytes and has a header for
+        // `Archived<$ty>`. The caller has guaranteed with the `unsafe` token
+        // that it was written by `rkyv::embed`, which validated the archive.
+        unsafe {
+            $crate::embed::access_embedded::<$crate::Archived<$ty>>(&STORAGE.0)
+        }
+    }};
+}
+
+#[cfg(all(feature = "alloc", feature = "bytecheck"))]
+mod checked {
+    use bytecheck::CheckBytes;
+    use rancor::Source;
+
+    use super::{header, HEADER_SIZE};
+    use crate::{
+        api::high::{access, to_bytes, HighSerializer, HighValidator},
+        ser::allocator::ArenaHandle,
+        util::AlignedVec,
+        Archive, Serialize,
+    };
+
+    /// Serializes and validates a value, and returns the bytes of an embedded
+    /// archive.
+    ///
+    /// The bytes start with a header for [`include_archived!`], which is
+    /// followed by the archive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rkyv::{
+    ///     embed::{to_embedded_bytes, HEADER_SIZE},
+    ///     rancor::Error,
+    ///     Archived,
+    /// };
+    ///
+    /// let bytes = to_embedded_bytes::<_, Error>(&vec![1u32, 2, 3]).unwrap();
+    /// let archived =
+    ///     rkyv::access::<Archived<Vec<u32>>, Error>(&bytes[HEADER_SIZE..])
+    ///         .unwrap();
+    /// assert_eq!(archived.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn to_embedded_bytes<T, E>(value: &T) -> Result<AlignedVec, E>
+    where
+        T: Archive
+            + for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+        E: Source,
+    {
+        let archive = to_bytes::<E>(value)?;
+        access::<T::Archived, E>(&archive)?;
+
+        let mut result = AlignedVec::with_capacity(HEADER_SIZE + archive.len());
+        result.extend_from_slice(&header::<T::Archived>());
+        result.extend_from_slice(&archive);
+        Ok(result)
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "bytecheck"))]
+pub use self::checked::*;
+
+#[cfg(all(feature = "std", feature = "bytecheck"))]
+mod io {
+    use core::{error::Error, fmt};
+    use std::{env, fs, path::PathBuf};
+
+    use bytecheck::CheckBytes;
+    use rancor::{fail, ResultExt as _, Source};
+
+    use super::to_embedded_bytes;
+    use crate::{
+        api::high::{HighSerializer, HighValidator},
+        ser::allocator::ArenaHandle,
+        util::AlignedVec,
+        Archive, Serialize,
+    };
+
+    #[derive(Debug)]
+    struct MissingOutDir;
+
+    impl fmt::Display for MissingOutDir {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "the OUT_DIR environment variable is not set; write_archive \
+                 must be called from a build script"
+            )
+        }
+    }
+
+    impl Error for MissingOutDir {}
+
+    /// Serializes and validates a value, and writes it to a file in `OUT_DIR`
+    /// for [`include_archived!`].
+    ///
+    /// This must be called from a build script. Returns the path of the
+    /// written file.
+    ///
+    /// See the [module docs](crate::embed) for an example.
+    pub fn write_archive<T, E>(value: &T, file_name: &str) -> Result<PathBuf, E>
+    where
+        T: Archive
+            + for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+        E: Source,
+    {
+        let Some(out_dir) = env::var_os("OUT_DIR") else {
+            fail!(MissingOutDir);
+        };
+        let path = PathBuf::from(out_dir).join(file_name);
+        let bytes = to_embedded_bytes::<T, E>(value)?;
+        fs::write(&path, &bytes).into_error()?;
+        Ok(path)
+    }
+}
+
+#[cfg(all(feature = "std", feature = "bytecheck"))]
+pub use self::io::*;
+
+#[cfg(all(test, feature = "alloc", feature = "bytecheck"))]
+mod tests {
+    use rancor::Panic;
+
+    use super::{
+        access_embedded, check_embedded, header, to_embedded_bytes, HEADER_SIZE,
+    };
+    use crate::{
+        alloc::{format, string::String, vec::Vec},
+        Archived,
+    };
+
+    #[test]
+    fn embedded_roundtrip() {
+        let value = (0..10)
+            .map(|i| format!("a string which is not inline {i}"))
+            .collect::<Vec<_>>();
+        let bytes = to_embedded_bytes::<_, Panic>(&value).unwrap();
+        assert_eq!(bytes[..HEADER_SIZE], header::<Archived<Vec<String>>>());
+
+        check_embedded::<Archived<Vec<String>>>(&bytes);
+        let archived =
+            unsafe { access_embedded::<Archived<Vec<String>>>(&bytes) };
+        assert_eq!(archived.len(), 10);
+        assert_eq!(archived[3], "a string which is not inline 3");
+    }
+
+    #[test]
+    #[should_panic = "different root type"]
+    fn embedded_wrong_type() {
+        let bytes = to_embedded_bytes::<_, Panic>(&[1u32, 2, 3, 4]).unwrap();
+        check_embedded::<Archived<u64>>(&bytes);
+    }
+
+    #[test]
+    #[should_panic = "different format features"]
+    fn embedded_wrong_format() {
+        let mut bytes = to_embedded_bytes::<_, Panic>(&42u32).unwrap();
+        bytes[4] ^= 1;
+        check_embedded::<Archived<u32>>(&bytes);
+    }
+
+    // The fixture is written with the default format features
+    #[cfg(not(any(
+        feature = "big_endian",
+        feature = "unaligned",
+        feature = "pointer_width_16",
+        feature = "pointer_width_64",
+    )))]
+    #[test]
+    fn include_archived() {
+        // SAFETY: The fixture was written by `to_embedded_bytes` for a `u32`,
+        // which is checked below.
+        static VALUE: &Archived<u32> = include_archived!(
+            unsafe u32,
+            "../tests/fixtures/embedded_u32.rkyv"
+        );
+        assert_eq!(*VALUE, 42);
+        assert_eq!(VALUE as *const _ as usize % 4, 0);
+
+        let bytes = to_embedded_bytes::<_, Panic>(&42u32).unwrap();
+        assert_eq!(
+            bytes.as_slice(),
+            include_bytes!("../tests/fixtures/embedded_u32.rkyv"),
+        );
+    }
+
+    #[test]
+    #[should_panic = "not an embedded archive"]
+    fn embedded_wrong_magic() {
+        let bytes = crate::to_bytes::<Panic>(&[0u32; 8]).unwrap();
+        check_embedded::<Archived<u32>>(&bytes);
+    }
+}
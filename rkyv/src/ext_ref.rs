@@ -0,0 +1,746 @@
+//! References between archives.
+//!
+//! Large datasets are often split into several archives, like shards of a
+//! table. An [`ArchivedExtRef`] refers to a value in another archive by the ID
+//! of that archive and the position of the value in it, so records can refer
+//! to each other across archives without copying.
+//!
+//! External values are written to an [`ExternalArchive`] while the main
+//! archive is serialized, usually through the [`External`] wrapper. To follow
+//! references, the bytes of each external archive are registered by ID in an
+//! [`ExtRefRegistry`]. The main archive can then be validated with
+//! [`access_with_ext_refs`], which also checks that each referenced value
+//! exists in its archive.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     ext_ref::{
+//!         access_with_ext_refs, to_bytes_with_external, ExtRefRegistry,
+//!         ExternalArchive,
+//!     },
+//!     rancor::Error,
+//!     with::External,
+//!     Archive, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! struct Record {
+//!     id: u32,
+//!     #[rkyv(with = External)]
+//!     payload: Vec<u8>,
+//! }
+//!
+//! let mut shard = ExternalArchive::new(1);
+//! let record = Record {
+//!     id: 42,
+//!     payload: vec![1, 2, 3, 4],
+//! };
+//! let bytes = to_bytes_with_external::<Error>(&record, &mut shard).unwrap();
+//! let shard = shard.into_bytes();
+//!
+//! let mut registry = ExtRefRegistry::new();
+//! registry.insert(1, &shard);
+//!
+//! let archived =
+//!     access_with_ext_refs::<ArchivedRecord, Error>(&bytes, &registry)
+//!         .unwrap();
+//! assert_eq!(archived.id, 42);
+//! let payload = archived.payload.get::<Error>(&registry).unwrap();
+//! assert_eq!(payload.as_slice(), &[1, 2, 3, 4]);
+//! ```
+//!
+//! [`External`]: crate::with::External
+
+use core::{
+    alloc::Layout, error::Error, fmt, hash::BuildHasherDefault,
+    marker::PhantomData, mem::take, ptr::NonNull,
+};
+
+use hashbrown::HashMap;
+use munge::munge;
+use rancor::{fail, Fallible, Source, Strategy};
+
+use crate::{
+    api::{high::HighSerializer, serialize_using},
+    hash::FxHasher64,
+    primitive::{ArchivedU32, ArchivedUsize, FixedUsize},
+    ser::{
+        allocator::{Arena, ArenaHandle},
+        sharing::{Interning, Share, SharingState},
+        Allocator, Positional, Serializer, Sharing, Writer,
+    },
+    util::{with_arena, AlignedVec},
+    Place, Portable, Serialize,
+};
+
+/// A reference to a value of type `T` in another archive.
+///
+/// The referenced value is identified by the ID of its archive and its
+/// position in that archive. See the [module docs](crate::ext_ref) for an
+/// example.
+#[derive(Portable)]
+#[rkyv(crate)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[repr(C)]
+pub struct ArchivedExtRef<T> {
+    archive: ArchivedU32,
+    pos: ArchivedUsize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ArchivedExtRef<T> {
+    /// Returns the ID of the archive which contains the referenced value.
+    pub fn archive_id(&self) -> u32 {
+        self.archive.to_native()
+    }
+
+    /// Returns the position of the referenced value in its archive.
+    pub fn pos(&self) -> usize {
+        self.pos.to_native() as usize
+    }
+
+    /// Resolves an `ArchivedExtRef` from a resolver.
+    pub fn resolve_from_resolver(resolver: ExtRefResolver, out: Place<Self>) {
+        munge!(let ArchivedExtRef { archive, pos, _phantom: _ } = out);
+        archive.write(ArchivedU32::from_native(resolver.archive));
+        pos.write(ArchivedUsize::from_native(resolver.pos as FixedUsize));
+    }
+}
+
+impl<T: Portable> ArchivedExtRef<T> {
+    /// Gets the referenced value from the registry without validating it.
+    ///
+    /// Returns an error if the registry doesn't contain the archive of the
+    /// value.
+    ///
+    /// # Safety
+    ///
+    /// The referenced archive must contain a valid `T` at the position of this
+    /// reference. This is the case when the archive containing this reference
+    /// was validated with the same registry, for example by
+    /// [`access_with_ext_refs`].
+    pub unsafe fn get_unchecked<'a, E: Source>(
+        &self,
+        registry: &ExtRefRegistry<'a>,
+    ) -> Result<&'a T, E> {
+        let bytes = registry.archive(self.archive_id())?;
+        // SAFETY: The caller has guaranteed that a valid `T` is located at
+        // `self.pos()` in `bytes`.
+        unsafe { Ok(crate::api::access_pos_unchecked::<T>(bytes, self.pos())) }
+    }
+}
+
+impl<T> fmt::Debug for ArchivedExtRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedExtRef")
+            .field("archive", &self.archive_id())
+            .field("pos", &self.pos())
+            .finish()
+    }
+}
+
+/// The resolver for an [`ArchivedExtRef`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExtRefResolver {
+    archive: u32,
+    pos: usize,
+}
+
+impl ExtRefResolver {
+    /// Creates a new `ExtRefResolver` from the ID of an archive and the
+    /// position of a value in it.
+    pub fn new(archive: u32, pos: usize) -> Self {
+        Self { archive, pos }
+    }
+}
+
+#[derive(Debug)]
+struct UnknownArchive {
+    id: u32,
+}
+
+impl fmt::Display for UnknownArchive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "external archive {} is not registered", self.id)
+    }
+}
+
+impl Error for UnknownArchive {}
+
+/// A context that can provide the bytes of external archives.
+pub trait ExtRefContext<E = <Self as Fallible>::Error> {
+    /// Returns the bytes of the archive with the given ID.
+    fn external_archive(&self, id: u32) -> Result<&[u8], E>;
+}
+
+impl<T, E> ExtRefContext<E> for Strategy<T, E>
+where
+    T: ExtRefContext<E> + ?Sized,
+{
+    fn external_archive(&self, id: u32) -> Result<&[u8], E> {
+        T::external_archive(self, id)
+    }
+}
+
+/// A registry of external archives by ID.
+///
+/// Archives are registered without being validated. They are validated when
+/// the values referenced in them are validated or gotten.
+#[derive(Debug, Default)]
+pub struct ExtRefRegistry<'a> {
+    archives: HashMap<u32, &'a [u8], BuildHasherDefault<FxHasher64>>,
+}
+
+impl<'a> ExtRefRegistry<'a> {
+    /// Creates a new empty `ExtRefRegistry`.
+    pub fn new() -> Self {
+        Self {
+            archives: HashMap::default(),
+        }
+    }
+
+    /// Registers the bytes of the archive with the given ID.
+    ///
+    /// Returns the bytes previously registered with the ID, if any.
+    pub fn insert(&mut self, id: u32, bytes: &'a [u8]) -> Option<&'a [u8]> {
+        self.archives.insert(id, bytes)
+    }
+
+    /// Returns the bytes of the archive with the given ID.
+    pub fn archive<E: Source>(&self, id: u32) -> Result<&'a [u8], E> {
+        match self.archives.get(&id) {
+            Some(bytes) => Ok(bytes),
+            None => fail!(UnknownArchive { id }),
+        }
+    }
+}
+
+impl<E: Source> ExtRefContext<E> for ExtRefRegistry<'_> {
+    fn external_archive(&self, id: u32) -> Result<&[u8], E> {
+        self.archive(id)
+    }
+}
+
+/// An archive of values which are referenced from other archives.
+///
+/// Values are serialized as they are added, and shared pointers are shared
+/// between all of them. The archive has no root, and its values can only be
+/// reached through [`ArchivedExtRef`]s.
+pub struct ExternalArchive {
+    id: u32,
+    writer: AlignedVec,
+    arena: Arena,
+    share: Share,
+}
+
+impl fmt::Debug for ExternalArchive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExternalArchive")
+            .field("id", &self.id)
+            .field("len", &self.writer.len())
+            .finish()
+    }
+}
+
+impl ExternalArchive {
+    /// Creates a new empty `ExternalArchive` with the given ID.
+    pub fn new(id: u32) -> Self {
+        Self {
+            id,
+            writer: AlignedVec::new(),
+            arena: Arena::new(),
+            share: Share::new(),
+        }
+    }
+
+    /// Returns the ID of the archive.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the bytes of the archive written so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.writer.as_slice()
+    }
+
+    /// Consumes the `ExternalArchive` and returns its bytes.
+    pub fn into_bytes(self) -> AlignedVec {
+        self.writer
+    }
+}
+
+/// A serializer that can write values to an external archive.
+pub trait ExternalWriter<E = <Self as Fallible>::Error> {
+    /// Serializes a value into an external archive and returns a resolver
+    /// which refers to it.
+    fn serialize_external<T>(&mut self, value: &T) -> Result<ExtRefResolver, E>
+    where
+        T: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>;
+}
+
+impl<T, E> ExternalWriter<E> for &mut T
+where
+    T: ExternalWriter<E> + ?Sized,
+{
+    fn serialize_external<U>(&mut self, value: &U) -> Result<ExtRefResolver, E>
+    where
+        U: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+    {
+        T::serialize_external(*self, value)
+    }
+}
+
+impl<T, E> ExternalWriter<E> for Strategy<T, E>
+where
+    T: ExternalWriter<E> + ?Sized,
+{
+    fn serialize_external<U>(&mut self, value: &U) -> Result<ExtRefResolver, E>
+    where
+        U: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+    {
+        T::serialize_external(self, value)
+    }
+}
+
+impl<E: Source> ExternalWriter<E> for ExternalArchive {
+    fn serialize_external<T>(&mut self, value: &T) -> Result<ExtRefResolver, E>
+    where
+        T: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+    {
+        let mut serializer = Serializer::new(
+            take(&mut self.writer),
+            self.arena.acquire(),
+            take(&mut self.share),
+        );
+        let result = serialize_using(value, &mut serializer);
+        let (writer, _, share) = serializer.into_raw_parts();
+        self.writer = writer;
+        self.share = share;
+
+        Ok(ExtRefResolver::new(self.id, result?))
+    }
+}
+
+/// A serializer which writes external values to an [`ExternalArchive`].
+///
+/// All other serializer operations are forwarded to the inner serializer.
+#[derive(Debug)]
+pub struct ExtSerializer<'x, S> {
+    /// The inner serializer.
+    pub inner: S,
+    /// The archive that external values are written to.
+    pub external: &'x mut ExternalArchive,
+}
+
+impl<'x, S> ExtSerializer<'x, S> {
+    /// Creates a new `ExtSerializer` from an inner serializer and an external
+    /// archive.
+    pub fn new(inner: S, external: &'x mut ExternalArchive) -> Self {
+        Self { inner, external }
+    }
+
+    /// Consumes the `ExtSerializer` and returns the inner serializer.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Positional> Positional for ExtSerializer<'_, S> {
+    fn pos(&self) -> usize {
+        self.inner.pos()
+    }
+}
+
+impl<S: Writer<E>, E> Writer<E> for ExtSerializer<'_, S> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.inner.write(bytes)
+    }
+
+    fn is_canonical(&self) -> bool {
+        self.inner.is_canonical()
+    }
+}
+
+unsafe impl<S: Allocator<E>, E> Allocator<E> for ExtSerializer<'_, S> {
+    unsafe fn push_alloc(
+        &mut self,
+        layout: Layout,
+    ) -> Result<NonNull<[u8]>, E> {
+        // SAFETY: The safety requirements for `S::push_alloc()` are the same as
+        // the safety requirements for `push_alloc()`.
+        unsafe { self.inner.push_alloc(layout) }
+    }
+
+    unsafe fn pop_alloc(
+        &mut self,
+        ptr: NonNull<u8>,
+        layout: Layout,
+    ) -> Result<(), E> {
+        // SAFETY: The safety requirements for `S::pop_alloc()` are the same as
+        // the safety requirements for `pop_alloc()`.
+        unsafe { self.inner.pop_alloc(ptr, layout) }
+    }
+}
+
+impl<S: Sharing<E>, E> Sharing<E> for ExtSerializer<'_, S> {
+    fn start_sharing(&mut self, address: usize) -> SharingState {
+        self.inner.start_sharing(address)
+    }
+
+    fn finish_sharing(&mut self, address: usize, pos: usize) -> Result<(), E> {
+        self.inner.finish_sharing(address, pos)
+    }
+}
+
+impl<S: Interning> Interning for ExtSerializer<'_, S> {
+    fn get_interned(
+        &self,
+        type_id: core::any::TypeId,
+        bytes: &[u8],
+    ) -> Option<usize> {
+        self.inner.get_interned(type_id, bytes)
+    }
+
+    fn add_interned(
+        &mut self,
+        type_id: core::any::TypeId,
+        bytes: &[u8],
+        pos: usize,
+    ) {
+        self.inner.add_interned(type_id, bytes, pos)
+    }
+}
+
+impl<S, E: Source> ExternalWriter<E> for ExtSerializer<'_, S> {
+    fn serialize_external<T>(&mut self, value: &T) -> Result<ExtRefResolver, E>
+    where
+        T: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+    {
+        ExternalWriter::<E>::serialize_external(&mut *self.external, value)
+    }
+}
+
+/// A high-level serializer which writes external values to an
+/// [`ExternalArchive`].
+pub type ExtHighSerializer<'a, 'x, E> = Strategy<
+    ExtSerializer<'x, Serializer<AlignedVec, ArenaHandle<'a>, Share>>,
+    E,
+>;
+
+/// Serializes a value to bytes, writing its external values to the given
+/// [`ExternalArchive`].
+///
+/// See the [module docs](crate::ext_ref) for an example.
+pub fn to_bytes_with_external<'x, E>(
+    value: &impl for<'a> Serialize<ExtHighSerializer<'a, 'x, E>>,
+    external: &'x mut ExternalArchive,
+) -> Result<AlignedVec, E>
+where
+    E: Source,
+{
+    with_arena(|arena| {
+        let mut serializer = ExtSerializer::new(
+            Serializer::new(AlignedVec::new(), arena.acquire(), Share::new()),
+            external,
+        );
+        serialize_using(value, &mut serializer)?;
+        Ok(serializer.into_inner().into_writer())
+    })
+}
+
+#[cfg(feature = "bytecheck")]
+mod checked {
+    use core::ops::Range;
+
+    use bytecheck::{CheckBytes, Verify};
+    use rancor::{Fallible, Source, Strategy};
+
+    use super::{ArchivedExtRef, ExtRefContext, ExtRefRegistry};
+    use crate::{
+        api::{
+            access_pos_with_context,
+            high::{access_pos, HighValidator},
+            root_position,
+        },
+        validation::{
+            archive::{AliasState, ArchiveValidator, CheckId},
+            shared::{SharedValidator, ValidationState},
+            ArchiveContext, SharedContext, Validator,
+        },
+        Portable,
+    };
+
+    unsafe impl<T, C> Verify<C> for ArchivedExtRef<T>
+    where
+        T: Portable + for<'a> CheckBytes<HighValidator<'a, C::Error>>,
+        C: Fallible + ExtRefContext + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            let bytes = context.external_archive(self.archive_id())?;
+            access_pos::<T, C::Error>(bytes, self.pos())?;
+            Ok(())
+        }
+    }
+
+    impl<T: Portable> ArchivedExtRef<T> {
+        /// Gets the referenced value from the registry.
+        ///
+        /// The referenced value is validated each time it is gotten. Use
+        /// [`get_unchecked`](Self::get_unchecked) to skip validation when the
+        /// archive containing this reference was validated with the same
+        /// registry.
+        pub fn get<'a, E>(
+            &self,
+            registry: &ExtRefRegistry<'a>,
+        ) -> Result<&'a T, E>
+        where
+            T: for<'b> CheckBytes<HighValidator<'b, E>>,
+            E: Source,
+        {
+            access_pos::<T, E>(registry.archive(self.archive_id())?, self.pos())
+        }
+    }
+
+    /// A validator which also checks [`ArchivedExtRef`]s against a registry
+    /// of external archives.
+    ///
+    /// All other validation is forwarded to the inner context. The referenced
+    /// values are validated on their own with a [`HighValidator`], so they
+    /// can't contain external references themselves.
+    #[derive(Debug)]
+    pub struct ExtRefValidator<'r, C> {
+        /// The inner validation context.
+        pub inner: C,
+        /// The registry of external archives.
+        pub registry: &'r ExtRefRegistry<'r>,
+    }
+
+    impl<'r, C> ExtRefValidator<'r, C> {
+        /// Creates a new `ExtRefValidator` from an inner context and a
+        /// registry.
+        pub fn new(inner: C, registry: &'r ExtRefRegistry<'r>) -> Self {
+            Self { inner, registry }
+        }
+    }
+
+    unsafe impl<C: ArchiveContext<E>, E> ArchiveContext<E>
+        for ExtRefValidator<'_, C>
+    {
+        fn check_subtree_ptr(
+            &mut self,
+            ptr: *const u8,
+            layout: &core::alloc::Layout,
+        ) -> Result<(), E> {
+            self.inner.check_subtree_ptr(ptr, layout)
+        }
+
+        unsafe fn push_subtree_range(
+            &mut self,
+            root: *const u8,
+            end: *const u8,
+        ) -> Result<Range<usize>, E> {
+            // SAFETY: This just forwards the call to the inner context, which
+            // has the same safety requirements.
+            unsafe { self.inner.push_subtree_range(root, end) }
+        }
+
+        unsafe fn pop_subtree_range(
+            &mut self,
+            range: Range<usize>,
+        ) -> Result<(), E> {
+            // SAFETY: This just forwards the call to the inner context, which
+            // has the same safety requirements.
+            unsafe { self.inner.pop_subtree_range(range) }
+        }
+
+        fn check_aliased_subtree(
+            &mut self,
+            ptr: *const u8,
+            layout: &core::alloc::Layout,
+            check: CheckId,
+        ) -> Result<AliasState, E> {
+            self.inner.check_aliased_subtree(ptr, layout, check)
+        }
+
+        unsafe fn finish_aliasable_subtree(
+            &mut self,
+            ptr: *const u8,
+            layout: &core::alloc::Layout,
+            check: CheckId,
+        ) -> Result<(), E> {
+            // SAFETY: This just forwards the call to the inner context, which
+            // has the same safety requirements.
+            unsafe { self.inner.finish_aliasable_subtree(ptr, layout, check) }
+        }
+    }
+
+    impl<C: SharedContext<E>, E> SharedContext<E> for ExtRefValidator<'_, C> {
+        fn start_shared(
+            &mut self,
+            address: usize,
+            type_id: core::any::TypeId,
+        ) -> Result<ValidationState, E> {
+            self.inner.start_shared(address, type_id)
+        }
+
+        fn finish_shared(
+            &mut self,
+            address: usize,
+            type_id: core::any::TypeId,
+        ) -> Result<(), E> {
+            self.inner.finish_shared(address, type_id)
+        }
+    }
+
+    impl<C, E: Source> ExtRefContext<E> for ExtRefValidator<'_, C> {
+        fn external_archive(&self, id: u32) -> Result<&[u8], E> {
+            self.registry.archive(id)
+        }
+    }
+
+    /// A high-level validator which also checks [`ArchivedExtRef`]s.
+    pub type ExtHighValidator<'a, 'r, E> = Strategy<
+        ExtRefValidator<'r, Validator<ArchiveValidator<'a>, SharedValidator>>,
+        E,
+    >;
+
+    /// Accesses a byte slice, checking that every [`ArchivedExtRef`] in it
+    /// refers to a valid value in its external archive.
+    ///
+    /// See the [module docs](crate::ext_ref) for an example.
+    pub fn access_with_ext_refs<'a, T, E>(
+        bytes: &'a [u8],
+        registry: &ExtRefRegistry<'_>,
+    ) -> Result<&'a T, E>
+    where
+        T: Portable + for<'b, 'r> CheckBytes<ExtHighValidator<'b, 'r, E>>,
+        E: Source,
+    {
+        let mut archive = ArchiveValidator::new(bytes);
+        archive.allow_aliasing();
+        let mut validator = ExtRefValidator::new(
+            Validator::new(archive, SharedValidator::new()),
+            registry,
+        );
+        access_pos_with_context::<T, _, E>(
+            bytes,
+            root_position::<T>(bytes.len()),
+            &mut validator,
+        )
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+pub use self::checked::*;
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use rancor::Error;
+
+    use super::{
+        access_with_ext_refs, to_bytes_with_external, ExtRefRegistry,
+        ExternalArchive,
+    };
+    use crate::{
+        alloc::{format, string::String, vec, vec::Vec},
+        Archive, Deserialize, Serialize,
+    };
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(crate)]
+    struct Record {
+        id: u32,
+        #[rkyv(with = crate::with::External)]
+        name: String,
+        #[rkyv(with = crate::with::External)]
+        values: Vec<u64>,
+    }
+
+    fn records() -> Vec<Record> {
+        (0..10)
+            .map(|i| Record {
+                id: i,
+                name: format!("a record which is not inline {i}"),
+                values: (0..i as u64).collect(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ext_ref_roundtrip() {
+        let value = records();
+        let mut shard = ExternalArchive::new(7);
+        let bytes =
+            to_bytes_with_external::<Error>(&value, &mut shard).unwrap();
+        let shard = shard.into_bytes();
+
+        let mut registry = ExtRefRegistry::new();
+        registry.insert(7, &shard);
+        let archived = access_with_ext_refs::<
+            crate::Archived<Vec<Record>>,
+            Error,
+        >(&bytes, &registry)
+        .unwrap();
+
+        for (i, record) in archived.iter().enumerate() {
+            assert_eq!(record.id, i as u32);
+            assert_eq!(record.name.archive_id(), 7);
+            let name = record.name.get::<Error>(&registry).unwrap();
+            assert_eq!(*name, format!("a record which is not inline {i}"));
+            let values =
+                unsafe { record.values.get_unchecked::<Error>(&registry) }
+                    .unwrap();
+            assert!(values.iter().copied().eq(0..i as u64));
+        }
+
+        let deserialized =
+            crate::api::deserialize_using::<Vec<Record>, _, Error>(
+                archived,
+                &mut registry,
+            )
+            .unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    fn ext_ref_invalid() {
+        let value = records();
+        let mut shard = ExternalArchive::new(1);
+        let bytes =
+            to_bytes_with_external::<Error>(&value, &mut shard).unwrap();
+        let shard = shard.into_bytes();
+
+        // The external archive is missing
+        let registry = ExtRefRegistry::new();
+        assert!(access_with_ext_refs::<crate::Archived<Vec<Record>>, Error>(
+            &bytes, &registry,
+        )
+        .is_err());
+
+        // The external archive is registered with the wrong bytes
+        let mut registry = ExtRefRegistry::new();
+        registry.insert(1, &shard[..shard.len() / 2]);
+        assert!(access_with_ext_refs::<crate::Archived<Vec<Record>>, Error>(
+            &bytes, &registry,
+        )
+        .is_err());
+
+        let unused = vec![0u8; 4];
+        let mut registry = ExtRefRegistry::new();
+        assert!(registry.insert(1, &unused).is_none());
+        assert_eq!(registry.insert(1, &shard), Some(unused.as_slice()));
+        let archived = access_with_ext_refs::<
+            crate::Archived<Vec<Record>>,
+            Error,
+        >(&bytes, &registry)
+        .unwrap();
+        assert_eq!(archived.len(), 10);
+    }
+}
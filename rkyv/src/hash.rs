@@ -5,7 +5,12 @@ use core::{
     ops::BitXor as _,
 };
 
-use crate::primitive::{FixedIsize, FixedUsize};
+use munge::munge;
+
+use crate::{
+    primitive::{ArchivedU64, FixedIsize, FixedUsize},
+    Place, Portable,
+};
 
 /// A cross-platform 64-bit implementation of fxhash.
 #[derive(Default)]
@@ -123,3 +128,416 @@ where
     value.hash(&mut state);
     state.finish()
 }
+
+/// A hasher which records the bytes written to it.
+///
+/// Bytes are written into the provided buffer until it is full, and the rest
+/// are only counted. The recorded streams depend only on the contents of the
+/// hashed values and not on where they are stored, so they can be used to
+/// order values with colliding hashes deterministically.
+pub(crate) struct HashStream<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> HashStream<'a> {
+    /// Creates a new `HashStream` which records bytes into `buf`.
+    pub(crate) fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// Returns the total number of bytes written to the stream.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl Hasher for HashStream<'_> {
+    fn write(&mut self, bytes: &[u8]) {
+        if let Some(rest) = self.buf.get_mut(self.len..) {
+            let count = usize::min(rest.len(), bytes.len());
+            rest[..count].copy_from_slice(&bytes[..count]);
+        }
+        self.len += bytes.len();
+    }
+
+    fn finish(&self) -> u64 {
+        0
+    }
+
+    // Integers are written little-endian so that the stream is the same on
+    // every platform.
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as FixedUsize as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as FixedIsize as i64);
+    }
+}
+
+/// A cross-platform implementation of SipHash-1-3.
+///
+/// Unlike [`FxHasher64`], `SipHasher13` is keyed. When the key is kept secret,
+/// the hashes it produces cannot be predicted and so it is resistant to
+/// hash-flooding attacks. The default hasher uses an all-zero key.
+#[derive(Clone, Debug)]
+pub struct SipHasher13 {
+    k0: u64,
+    k1: u64,
+    length: usize,
+    state: SipState,
+    tail: u64,
+    ntail: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct SipState {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+}
+
+impl SipState {
+    #[inline]
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13);
+        self.v1 ^= self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16);
+        self.v3 ^= self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21);
+        self.v3 ^= self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17);
+        self.v1 ^= self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    #[inline]
+    fn compress(&mut self, word: u64) {
+        self.v3 ^= word;
+        self.round();
+        self.v0 ^= word;
+    }
+}
+
+/// Loads up to 8 bytes from `bytes` as a little-endian integer.
+#[inline]
+fn load_le(bytes: &[u8]) -> u64 {
+    debug_assert!(bytes.len() <= 8);
+    let mut word = [0u8; 8];
+    word[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(word)
+}
+
+impl SipHasher13 {
+    /// Creates a new `SipHasher13` with the two given 64-bit keys.
+    pub const fn new_with_keys(k0: u64, k1: u64) -> Self {
+        Self {
+            k0,
+            k1,
+            length: 0,
+            state: SipState {
+                v0: k0 ^ 0x736f_6d65_7073_6575,
+                v1: k1 ^ 0x646f_7261_6e64_6f6d,
+                v2: k0 ^ 0x6c79_6765_6e65_7261,
+                v3: k1 ^ 0x7465_6462_7974_6573,
+            },
+            tail: 0,
+            ntail: 0,
+        }
+    }
+
+    /// Returns the keys of this hasher.
+    pub const fn keys(&self) -> (u64, u64) {
+        (self.k0, self.k1)
+    }
+}
+
+impl Default for SipHasher13 {
+    fn default() -> Self {
+        Self::new_with_keys(0, 0)
+    }
+}
+
+impl Hasher for SipHasher13 {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.length += bytes.len();
+
+        if self.ntail != 0 {
+            let needed = 8 - self.ntail;
+            let fill = usize::min(needed, bytes.len());
+            self.tail |= load_le(&bytes[..fill]) << (8 * self.ntail);
+            if bytes.len() < needed {
+                self.ntail += bytes.len();
+                return;
+            }
+            self.state.compress(self.tail);
+            self.tail = 0;
+            self.ntail = 0;
+            bytes = &bytes[needed..];
+        }
+
+        let mut words = bytes.chunks_exact(8);
+        for word in &mut words {
+            self.state.compress(load_le(word));
+        }
+
+        let rest = words.remainder();
+        self.tail = load_le(rest);
+        self.ntail = rest.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let mut state = self.state;
+        let b = ((self.length as u64 & 0xff) << 56) | self.tail;
+
+        state.compress(b);
+        state.v2 ^= 0xff;
+        state.round();
+        state.round();
+        state.round();
+
+        state.v0 ^ state.v1 ^ state.v2 ^ state.v3
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write(&(i as FixedUsize).to_le_bytes());
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write(&(i as FixedIsize).to_le_bytes());
+    }
+}
+
+/// The seed for a [`KeyedHasher`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct HashSeed {
+    /// The first half of the seed.
+    pub k0: u64,
+    /// The second half of the seed.
+    pub k1: u64,
+}
+
+impl HashSeed {
+    /// Returns a new `HashSeed` from the given halves.
+    pub const fn new(k0: u64, k1: u64) -> Self {
+        Self { k0, k1 }
+    }
+
+    /// Returns a new randomly-generated `HashSeed`.
+    ///
+    /// The random state is drawn from the same source used to seed the
+    /// standard library's `HashMap`.
+    #[cfg(feature = "std")]
+    pub fn random() -> Self {
+        use std::hash::{BuildHasher as _, RandomState};
+
+        let state = RandomState::new();
+        Self {
+            k0: state.hash_one(0u8),
+            k1: state.hash_one(1u8),
+        }
+    }
+}
+
+/// A source of [`HashSeed`]s for keyed hash tables.
+///
+/// This is used by [`Keyed`](crate::with::Keyed) to choose the seed for each
+/// hash table it serializes. Implement it to supply seeds of your own.
+pub trait SeedSource {
+    /// Returns the seed to hash a new table with.
+    ///
+    /// `canonical` is whether the serializer produces canonical archives. See
+    /// [`Serializer::new_canonical`](crate::ser::Serializer::new_canonical)
+    /// for more details.
+    fn seed(canonical: bool) -> HashSeed;
+}
+
+/// The default [`SeedSource`].
+///
+/// A new random seed is chosen for each table, except when serializing
+/// canonically. Canonical archives are hashed with a fixed seed instead so that
+/// equal values produce identical bytes.
+///
+/// Random seeds are only available with the `std` feature. Without it, supply a
+/// seed with [`FixedSeed`] or your own [`SeedSource`].
+#[derive(Debug)]
+pub struct DefaultSeed;
+
+impl DefaultSeed {
+    /// The seed used when serializing canonically.
+    pub const CANONICAL: HashSeed =
+        HashSeed::new(0x736f_6d65_7073_6575, 0x646f_7261_6e64_6f6d);
+}
+
+#[cfg(feature = "std")]
+impl SeedSource for DefaultSeed {
+    fn seed(canonical: bool) -> HashSeed {
+        if canonical {
+            Self::CANONICAL
+        } else {
+            HashSeed::random()
+        }
+    }
+}
+
+/// A [`SeedSource`] which always returns the seed `(K0, K1)`.
+///
+/// A fixed seed makes archives reproducible, but attackers who know it can
+/// choose keys which collide. Keep it secret when archiving untrusted keys.
+#[derive(Debug)]
+pub struct FixedSeed<const K0: u64, const K1: u64>;
+
+impl<const K0: u64, const K1: u64> SeedSource for FixedSeed<K0, K1> {
+    fn seed(_: bool) -> HashSeed {
+        HashSeed::new(K0, K1)
+    }
+}
+
+/// An archived [`HashSeed`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedHashSeed {
+    k0: ArchivedU64,
+    k1: ArchivedU64,
+}
+
+impl ArchivedHashSeed {
+    /// Returns the native seed.
+    pub const fn to_native(&self) -> HashSeed {
+        HashSeed {
+            k0: self.k0.to_native(),
+            k1: self.k1.to_native(),
+        }
+    }
+
+    /// Emplaces an archived version of the given seed into the output place.
+    pub fn emplace(seed: HashSeed, out: Place<Self>) {
+        munge!(let ArchivedHashSeed { k0, k1 } = out);
+        k0.write(ArchivedU64::from_native(seed.k0));
+        k1.write(ArchivedU64::from_native(seed.k1));
+    }
+}
+
+/// A [`Hasher`] which is keyed by a [`HashSeed`].
+pub trait KeyedHasher: Hasher {
+    /// Returns a new hasher keyed with the given seed.
+    fn with_seed(seed: HashSeed) -> Self;
+}
+
+impl KeyedHasher for SipHasher13 {
+    fn with_seed(seed: HashSeed) -> Self {
+        Self::new_with_keys(seed.k0, seed.k1)
+    }
+}
+
+/// Hashes the given value with a new `KeyedHasher` using the given seed.
+pub fn hash_value_keyed<Q, H: KeyedHasher>(value: &Q, seed: HashSeed) -> u64
+where
+    Q: Hash + ?Sized,
+{
+    let mut state = H::with_seed(seed);
+    value.hash(&mut state);
+    state.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use core::hash::Hasher as _;
+
+    use super::SipHasher13;
+
+    #[test]
+    fn siphash_1_3_test_vectors() {
+        const EXPECTED: [u64; 8] = [
+            0xabac_0158_050f_c4dc,
+            0xc9f4_9bf3_7d57_ca93,
+            0x82cb_9b02_4dc7_d44d,
+            0x8bf8_0ab8_e7dd_f7fb,
+            0xcf75_5760_88d3_8328,
+            0xdef9_d52f_4953_3b67,
+            0xc50d_2b50_c59f_22a7,
+            0xd392_7d98_9bb1_1140,
+        ];
+
+        let k0 = 0x0706_0504_0302_0100;
+        let k1 = 0x0f0e_0d0c_0b0a_0908;
+        let input = [0u8, 1, 2, 3, 4, 5, 6, 7];
+
+        for (len, expected) in EXPECTED.iter().enumerate() {
+            let mut hasher = SipHasher13::new_with_keys(k0, k1);
+            hasher.write(&input[..len]);
+            assert_eq!(hasher.finish(), *expected);
+
+            // Splitting the input across writes must not change the hash
+            let mut hasher = SipHasher13::new_with_keys(k0, k1);
+            for byte in &input[..len] {
+                hasher.write(core::slice::from_ref(byte));
+            }
+            assert_eq!(hasher.finish(), *expected);
+        }
+    }
+}
@@ -6,9 +6,10 @@ use crate::{
     alloc::{alloc::alloc, boxed::Box},
     boxed::{ArchivedBox, BoxResolver},
     niche::option_box::ArchivedOptionBox,
+    rel_ptr::Offset,
     traits::{ArchivePointee, LayoutRaw},
-    Archive, ArchiveUnsized, Deserialize, DeserializeUnsized, Place, Serialize,
-    SerializeUnsized,
+    Archive, ArchiveUnsized, Deserialize, DeserializeInPlace,
+    DeserializeUnsized, Place, Serialize, SerializeUnsized,
 };
 
 impl<T: ArchiveUnsized + ?Sized> Archive for Box<T> {
@@ -33,9 +34,10 @@ where
     }
 }
 
-impl<T, D> Deserialize<Box<T>, D> for ArchivedBox<T::Archived>
+impl<T, O, D> Deserialize<Box<T>, D> for ArchivedBox<T::Archived, O>
 where
     T: ArchiveUnsized + LayoutRaw + ?Sized,
+    O: Offset,
     T::Archived: DeserializeUnsized<T, D>,
     D: Fallible + ?Sized,
     D::Error: Source,
@@ -58,20 +60,75 @@ where
     }
 }
 
-impl<T, U> PartialEq<Box<U>> for ArchivedBox<T>
+impl<T, O, D> DeserializeInPlace<Box<T>, D> for ArchivedBox<T::Archived, O>
+where
+    T: ArchiveUnsized + LayoutRaw,
+    O: Offset,
+    T::Archived: DeserializeUnsized<T, D> + DeserializeInPlace<T, D>,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_into(
+        &self,
+        target: &mut Box<T>,
+        deserializer: &mut D,
+    ) -> Result<(), D::Error> {
+        self.get().deserialize_into(target, deserializer)
+    }
+}
+
+impl<O, D> DeserializeInPlace<Box<str>, D> for ArchivedBox<str, O>
+where
+    O: Offset,
+    str: DeserializeUnsized<str, D>,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+}
+
+impl<T, O, D> DeserializeInPlace<Box<[T]>, D> for ArchivedBox<[T::Archived], O>
+where
+    T: Archive,
+    O: Offset,
+    T::Archived: DeserializeInPlace<T, D>,
+    [T::Archived]: DeserializeUnsized<[T], D>,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_into(
+        &self,
+        target: &mut Box<[T]>,
+        deserializer: &mut D,
+    ) -> Result<(), D::Error> {
+        // Boxed slices can't be resized, so they can only be reused if they
+        // already have the right length.
+        if target.len() == self.len() {
+            for (value, target) in self.iter().zip(target.iter_mut()) {
+                value.deserialize_into(target, deserializer)?;
+            }
+        } else {
+            *target = self.deserialize(deserializer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, U, O> PartialEq<Box<U>> for ArchivedBox<T, O>
 where
     T: ArchivePointee + PartialEq<U> + ?Sized,
     U: ?Sized,
+    O: Offset,
 {
     fn eq(&self, other: &Box<U>) -> bool {
         self.get().eq(other.as_ref())
     }
 }
 
-impl<T, U> PartialOrd<Box<U>> for ArchivedBox<T>
+impl<T, U, O> PartialOrd<Box<U>> for ArchivedBox<T, O>
 where
     T: ArchivePointee + PartialOrd<U> + ?Sized,
     U: ?Sized,
+    O: Offset,
 {
     fn partial_cmp(&self, other: &Box<U>) -> Option<cmp::Ordering> {
         self.get().partial_cmp(other.as_ref())
@@ -95,8 +152,13 @@ where
 #[cfg(test)]
 mod tests {
     use crate::{
-        alloc::{boxed::Box, string::ToString, vec, vec::Vec},
-        api::test::roundtrip,
+        alloc::{
+            boxed::Box,
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        api::test::{deserialize_into, roundtrip, to_archived},
     };
 
     #[test]
@@ -168,4 +230,32 @@ mod tests {
         roundtrip(&Err::<(), _>(Vec::<i32>::new().into_boxed_slice()));
         roundtrip(&Err::<(), _>(vec![1, 2, 3, 4].into_boxed_slice()));
     }
+
+    #[test]
+    fn deserialize_boxed_slice_in_place() {
+        let value = vec!["a string which is not inline".to_string(); 3]
+            .into_boxed_slice();
+        to_archived(&value, |archived| {
+            // Targets with the same length keep their elements
+            let mut target = (0..3)
+                .map(|_| String::with_capacity(64))
+                .collect::<Box<[_]>>();
+            let element = target[1].as_ptr();
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+            assert_eq!(target[1].as_ptr(), element);
+
+            // Targets with a different length are replaced
+            let mut target = vec!["x".to_string()].into_boxed_slice();
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+        });
+
+        let value = "hello world".to_string().into_boxed_str();
+        to_archived(&value, |archived| {
+            let mut target = Box::<str>::from("x");
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+        });
+    }
 }
@@ -1,4 +1,4 @@
-use core::ops::ControlFlow;
+use core::{mem, ops::ControlFlow};
 
 use rancor::{Fallible, Source};
 
@@ -6,7 +6,7 @@ use crate::{
     alloc::collections::BTreeMap,
     collections::btree_map::{ArchivedBTreeMap, BTreeMapResolver},
     ser::{Allocator, Writer},
-    Archive, Deserialize, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl<K: Archive + Ord, V: Archive> Archive for BTreeMap<K, V>
@@ -73,6 +73,51 @@ where
     }
 }
 
+impl<K, V, D> DeserializeInPlace<BTreeMap<K, V>, D>
+    for ArchivedBTreeMap<K::Archived, V::Archived>
+where
+    K: Archive + Ord,
+    K::Archived: Deserialize<K, D> + Ord,
+    V: Archive,
+    V::Archived: DeserializeInPlace<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_into(
+        &self,
+        target: &mut BTreeMap<K, V>,
+        deserializer: &mut D,
+    ) -> Result<(), D::Error> {
+        // B-trees don't have any capacity to reuse, so move the existing
+        // values out and deserialize into them as their keys are visited.
+        // Values for keys which aren't in the archive are dropped at the end.
+        let mut existing = mem::take(target);
+        let r = self.visit(|ak, av| {
+            let k = match ak.deserialize(deserializer) {
+                Ok(k) => k,
+                Err(e) => return ControlFlow::Break(e),
+            };
+            let v = match existing.remove(&k) {
+                Some(mut v) => {
+                    match av.deserialize_into(&mut v, deserializer) {
+                        Ok(()) => v,
+                        Err(e) => return ControlFlow::Break(e),
+                    }
+                }
+                None => match av.deserialize(deserializer) {
+                    Ok(v) => v,
+                    Err(e) => return ControlFlow::Break(e),
+                },
+            };
+            target.insert(k, v);
+            ControlFlow::Continue(())
+        });
+        match r {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
 impl<K, V, AK, AV> PartialEq<BTreeMap<K, V>> for ArchivedBTreeMap<AK, AV>
 where
     AK: PartialEq<K>,
@@ -107,7 +152,9 @@ mod tests {
             vec,
             vec::Vec,
         },
-        api::test::{roundtrip, to_archived},
+        api::test::{
+            assert_canonical, deserialize_into, roundtrip, to_archived,
+        },
         collections::btree_map::ArchivedBTreeMap,
         primitive::ArchivedI32,
         seal::Seal,
@@ -130,6 +177,19 @@ mod tests {
         roundtrip(&BTreeMap::<String, i32>::new());
     }
 
+    #[test]
+    fn canonical_btree_map() {
+        let a = (0..200)
+            .map(|i| (i.to_string(), i))
+            .collect::<BTreeMap<_, _>>();
+        let b = (0..200)
+            .rev()
+            .map(|i| (i.to_string(), i))
+            .collect::<BTreeMap<_, _>>();
+
+        assert_canonical(&a, &b);
+    }
+
     #[test]
     fn roundtrip_btree_map_zst() {
         let mut value = BTreeMap::new();
@@ -272,4 +332,22 @@ mod tests {
             assert_eq!(i.next(), None);
         });
     }
+
+    #[test]
+    fn deserialize_btree_map_in_place() {
+        let mut value = BTreeMap::new();
+        value.insert("foo".to_string(), vec![1, 2, 3]);
+        value.insert("bar".to_string(), vec![4, 5]);
+
+        to_archived(&value, |archived| {
+            let mut target = BTreeMap::new();
+            target.insert("foo".to_string(), Vec::with_capacity(16));
+            target.insert("baz".to_string(), vec![6]);
+            let foo = target["foo"].as_ptr();
+
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+            assert_eq!(target["foo"].as_ptr(), foo);
+        });
+    }
 }
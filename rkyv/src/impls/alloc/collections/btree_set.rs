@@ -6,7 +6,7 @@ use crate::{
     alloc::collections::BTreeSet,
     collections::btree_set::{ArchivedBTreeSet, BTreeSetResolver},
     ser::{Allocator, Writer},
-    Archive, Deserialize, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl<K: Archive + Ord> Archive for BTreeSet<K>
@@ -69,6 +69,14 @@ where
     }
 }
 
+impl<K, D> DeserializeInPlace<BTreeSet<K>, D> for ArchivedBTreeSet<K::Archived>
+where
+    K: Archive + Ord,
+    K::Archived: Deserialize<K, D> + Ord,
+    D: Fallible + ?Sized,
+{
+}
+
 impl<K, AK: PartialEq<K>> PartialEq<BTreeSet<K>> for ArchivedBTreeSet<AK> {
     fn eq(&self, other: &BTreeSet<K>) -> bool {
         if self.len() != other.len() {
@@ -7,7 +7,8 @@ use crate::{
     ser::{Allocator, Writer},
     traits::LayoutRaw,
     vec::{ArchivedVec, VecResolver},
-    Archive, Deserialize, DeserializeUnsized, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, DeserializeUnsized, Place,
+    Serialize,
 };
 
 impl<T: Archive> Archive for VecDeque<T> {
@@ -69,6 +70,15 @@ where
     }
 }
 
+impl<T, D> DeserializeInPlace<VecDeque<T>, D> for ArchivedVec<T::Archived>
+where
+    T: Archive,
+    [T::Archived]: DeserializeUnsized<[T], D>,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+}
+
 impl<T: PartialEq<U>, U> PartialEq<VecDeque<U>> for ArchivedVec<T> {
     fn eq(&self, other: &VecDeque<U>) -> bool {
         self.len() == other.len() && self.iter().eq(other.iter())
@@ -84,8 +94,13 @@ impl<T: PartialOrd> PartialOrd<VecDeque<T>> for ArchivedVec<T> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        access_unchecked, alloc::collections::VecDeque, api::test::deserialize,
-        rancor::Error, to_bytes, vec::ArchivedVec, Archived,
+        access_unchecked,
+        alloc::collections::VecDeque,
+        api::test::{assert_canonical, deserialize},
+        rancor::Error,
+        to_bytes,
+        vec::ArchivedVec,
+        Archived,
     };
 
     #[test]
@@ -114,4 +129,21 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn canonical_vecdeque() {
+        // The same elements, once contiguous and once wrapped around the end
+        // of the ring buffer
+        let a = (0..8).collect::<VecDeque<i32>>();
+        let mut b = VecDeque::with_capacity(8);
+        for x in (0..4).rev() {
+            b.push_front(x);
+        }
+        for x in 4..8 {
+            b.push_back(x);
+        }
+        assert_eq!(a, b);
+
+        assert_canonical(&a, &b);
+    }
 }
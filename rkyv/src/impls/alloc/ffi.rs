@@ -7,7 +7,8 @@ use crate::{
     ffi::{ArchivedCString, CStringResolver},
     ser::Writer,
     traits::LayoutRaw,
-    Archive, Deserialize, DeserializeUnsized, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, DeserializeUnsized, Place,
+    Serialize,
 };
 
 // CString
@@ -54,6 +55,14 @@ where
     }
 }
 
+impl<D> DeserializeInPlace<CString, D> for ArchivedCString
+where
+    D: Fallible + ?Sized,
+    D::Error: Source,
+    CStr: DeserializeUnsized<CStr, D>,
+{
+}
+
 impl PartialEq<CString> for ArchivedCString {
     #[inline]
     fn eq(&self, other: &CString) -> bool {
@@ -7,10 +7,11 @@ use crate::{
     alloc::{alloc::alloc, boxed::Box, sync},
     de::{Metadata, Pooling, PoolingExt as _, SharedPointer},
     rc::{ArcFlavor, ArchivedRc, ArchivedRcWeak, RcResolver, RcWeakResolver},
+    rel_ptr::Offset,
     ser::{Sharing, Writer},
     traits::{ArchivePointee, LayoutRaw},
-    Archive, ArchiveUnsized, Deserialize, DeserializeUnsized, Place, Serialize,
-    SerializeUnsized,
+    Archive, ArchiveUnsized, Deserialize, DeserializeInPlace,
+    DeserializeUnsized, Place, Serialize, SerializeUnsized,
 };
 
 // Arc
@@ -63,8 +64,10 @@ unsafe impl<T: LayoutRaw + Pointee + ?Sized> SharedPointer<T> for sync::Arc<T> {
     }
 }
 
-impl<T, D> Deserialize<sync::Arc<T>, D> for ArchivedRc<T::Archived, ArcFlavor>
+impl<T, O, D> Deserialize<sync::Arc<T>, D>
+    for ArchivedRc<T::Archived, ArcFlavor, O>
 where
+    O: Offset,
     T: ArchiveUnsized + LayoutRaw + Pointee + ?Sized + 'static,
     T::Archived: DeserializeUnsized<T, D>,
     T::Metadata: Into<Metadata>,
@@ -85,8 +88,22 @@ where
     }
 }
 
-impl<T, U> PartialEq<sync::Arc<U>> for ArchivedRc<T, ArcFlavor>
+impl<T, O, D> DeserializeInPlace<sync::Arc<T>, D>
+    for ArchivedRc<T::Archived, ArcFlavor, O>
 where
+    O: Offset,
+    T: ArchiveUnsized + LayoutRaw + Pointee + ?Sized + 'static,
+    T::Archived: DeserializeUnsized<T, D>,
+    T::Metadata: Into<Metadata>,
+    Metadata: Into<T::Metadata>,
+    D: Fallible + Pooling + ?Sized,
+    D::Error: Source,
+{
+}
+
+impl<T, O, U> PartialEq<sync::Arc<U>> for ArchivedRc<T, ArcFlavor, O>
+where
+    O: Offset,
     T: ArchivePointee + PartialEq<U> + ?Sized,
     U: ?Sized,
 {
@@ -154,3 +171,15 @@ where
         })
     }
 }
+
+impl<T, D> DeserializeInPlace<sync::Weak<T>, D>
+    for ArchivedRcWeak<T::Archived, ArcFlavor>
+where
+    T: ArchiveUnsized + LayoutRaw + Pointee + 'static,
+    T::Archived: DeserializeUnsized<T, D>,
+    T::Metadata: Into<Metadata>,
+    Metadata: Into<T::Metadata>,
+    D: Fallible + Pooling + ?Sized,
+    D::Error: Source,
+{
+}
@@ -10,10 +10,11 @@ use crate::{
     alloc::{alloc::alloc, boxed::Box, rc},
     de::{Metadata, Pooling, PoolingExt as _, SharedPointer},
     rc::{ArchivedRc, ArchivedRcWeak, RcFlavor, RcResolver, RcWeakResolver},
+    rel_ptr::Offset,
     ser::{Sharing, Writer},
     traits::{ArchivePointee, LayoutRaw},
-    Archive, ArchiveUnsized, Deserialize, DeserializeUnsized, Place, Serialize,
-    SerializeUnsized,
+    Archive, ArchiveUnsized, Deserialize, DeserializeInPlace,
+    DeserializeUnsized, Place, Serialize, SerializeUnsized,
 };
 
 // Rc
@@ -66,8 +67,9 @@ unsafe impl<T: LayoutRaw + Pointee + ?Sized> SharedPointer<T> for rc::Rc<T> {
     }
 }
 
-impl<T, D> Deserialize<rc::Rc<T>, D> for ArchivedRc<T::Archived, RcFlavor>
+impl<T, O, D> Deserialize<rc::Rc<T>, D> for ArchivedRc<T::Archived, RcFlavor, O>
 where
+    O: Offset,
     T: ArchiveUnsized + LayoutRaw + Pointee + ?Sized + 'static,
     T::Archived: DeserializeUnsized<T, D>,
     T::Metadata: Into<Metadata>,
@@ -85,8 +87,22 @@ where
     }
 }
 
-impl<T, U> PartialEq<rc::Rc<U>> for ArchivedRc<T, RcFlavor>
+impl<T, O, D> DeserializeInPlace<rc::Rc<T>, D>
+    for ArchivedRc<T::Archived, RcFlavor, O>
 where
+    O: Offset,
+    T: ArchiveUnsized + LayoutRaw + Pointee + ?Sized + 'static,
+    T::Archived: DeserializeUnsized<T, D>,
+    T::Metadata: Into<Metadata>,
+    Metadata: Into<T::Metadata>,
+    D: Fallible + Pooling + ?Sized,
+    D::Error: Source,
+{
+}
+
+impl<T, O, U> PartialEq<rc::Rc<U>> for ArchivedRc<T, RcFlavor, O>
+where
+    O: Offset,
     T: ArchivePointee + PartialEq<U> + ?Sized,
     U: ?Sized,
 {
@@ -152,6 +168,18 @@ where
     }
 }
 
+impl<T, D> DeserializeInPlace<rc::Weak<T>, D>
+    for ArchivedRcWeak<T::Archived, RcFlavor>
+where
+    T: ArchiveUnsized + LayoutRaw + Pointee + 'static,
+    T::Archived: DeserializeUnsized<T, D>,
+    T::Metadata: Into<Metadata>,
+    Metadata: Into<T::Metadata>,
+    D: Fallible + Pooling + ?Sized,
+    D::Error: Source,
+{
+}
+
 #[cfg(test)]
 mod tests {
     use munge::munge;
@@ -4,9 +4,10 @@ use rancor::{Fallible, Source};
 
 use crate::{
     alloc::string::{String, ToString},
+    rel_ptr::Offset,
     string::{ArchivedString, StringResolver},
-    Archive, Deserialize, DeserializeUnsized, Place, Serialize,
-    SerializeUnsized,
+    Archive, Deserialize, DeserializeInPlace, DeserializeUnsized, Place,
+    Serialize, SerializeUnsized,
 };
 
 impl Archive for String {
@@ -32,8 +33,10 @@ where
     }
 }
 
-impl<D: Fallible + ?Sized> Deserialize<String, D> for ArchivedString
+impl<O, D> Deserialize<String, D> for ArchivedString<O>
 where
+    O: Offset,
+    D: Fallible + ?Sized,
     str: DeserializeUnsized<str, D>,
 {
     fn deserialize(&self, _: &mut D) -> Result<String, D::Error> {
@@ -41,30 +44,47 @@ where
     }
 }
 
-impl PartialEq<String> for ArchivedString {
+impl<O, D> DeserializeInPlace<String, D> for ArchivedString<O>
+where
+    O: Offset,
+    D: Fallible + ?Sized,
+    str: DeserializeUnsized<str, D>,
+{
+    fn deserialize_into(
+        &self,
+        target: &mut String,
+        _: &mut D,
+    ) -> Result<(), D::Error> {
+        target.clear();
+        target.push_str(self.as_str());
+        Ok(())
+    }
+}
+
+impl<O: Offset> PartialEq<String> for ArchivedString<O> {
     #[inline]
     fn eq(&self, other: &String) -> bool {
         PartialEq::eq(self.as_str(), other.as_str())
     }
 }
 
-impl PartialEq<ArchivedString> for String {
+impl<O: Offset> PartialEq<ArchivedString<O>> for String {
     #[inline]
-    fn eq(&self, other: &ArchivedString) -> bool {
+    fn eq(&self, other: &ArchivedString<O>) -> bool {
         PartialEq::eq(other.as_str(), self.as_str())
     }
 }
 
-impl PartialOrd<String> for ArchivedString {
+impl<O: Offset> PartialOrd<String> for ArchivedString<O> {
     #[inline]
     fn partial_cmp(&self, other: &String) -> Option<Ordering> {
         self.as_str().partial_cmp(other.as_str())
     }
 }
 
-impl PartialOrd<ArchivedString> for String {
+impl<O: Offset> PartialOrd<ArchivedString<O>> for String {
     #[inline]
-    fn partial_cmp(&self, other: &ArchivedString) -> Option<Ordering> {
+    fn partial_cmp(&self, other: &ArchivedString<O>) -> Option<Ordering> {
         self.as_str().partial_cmp(other.as_str())
     }
 }
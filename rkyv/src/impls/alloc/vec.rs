@@ -2,10 +2,12 @@ use rancor::{Fallible, ResultExt as _, Source};
 
 use crate::{
     alloc::{alloc::alloc, boxed::Box, vec::Vec},
+    rel_ptr::Offset,
     ser::{Allocator, Writer},
     traits::LayoutRaw,
     vec::{ArchivedVec, VecResolver},
-    Archive, Deserialize, DeserializeUnsized, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, DeserializeUnsized, Place,
+    Serialize,
 };
 
 impl<T: Archive> Archive for Vec<T> {
@@ -31,9 +33,10 @@ impl<T: Serialize<S>, S: Fallible + Allocator + Writer + ?Sized> Serialize<S>
     }
 }
 
-impl<T, D> Deserialize<Vec<T>, D> for ArchivedVec<T::Archived>
+impl<T, O, D> Deserialize<Vec<T>, D> for ArchivedVec<T::Archived, O>
 where
     T: Archive,
+    O: Offset,
     [T::Archived]: DeserializeUnsized<[T], D>,
     D: Fallible + ?Sized,
     D::Error: Source,
@@ -54,13 +57,45 @@ where
     }
 }
 
-impl<T: PartialEq<U>, U> PartialEq<Vec<U>> for ArchivedVec<T> {
+impl<T, O, D> DeserializeInPlace<Vec<T>, D> for ArchivedVec<T::Archived, O>
+where
+    T: Archive,
+    O: Offset,
+    T::Archived: DeserializeInPlace<T, D>,
+    [T::Archived]: DeserializeUnsized<[T], D>,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_into(
+        &self,
+        target: &mut Vec<T>,
+        deserializer: &mut D,
+    ) -> Result<(), D::Error> {
+        let values = self.as_slice();
+        target.truncate(values.len());
+        target.reserve(values.len() - target.len());
+        let (reused, new) = values.split_at(target.len());
+        for (value, target) in reused.iter().zip(target.iter_mut()) {
+            value.deserialize_into(target, deserializer)?;
+        }
+        for value in new {
+            target.push(value.deserialize(deserializer)?);
+        }
+        Ok(())
+    }
+}
+
+impl<T: PartialEq<U>, U, O: Offset> PartialEq<Vec<U>> for ArchivedVec<T, O> {
     fn eq(&self, other: &Vec<U>) -> bool {
         self.as_slice().eq(other.as_slice())
     }
 }
 
-impl<T: PartialOrd<U>, U> PartialOrd<Vec<U>> for ArchivedVec<T> {
+impl<T, U, O> PartialOrd<Vec<U>> for ArchivedVec<T, O>
+where
+    T: PartialOrd<U>,
+    O: Offset,
+{
     fn partial_cmp(&self, other: &Vec<U>) -> Option<::core::cmp::Ordering> {
         crate::impls::lexicographical_partial_ord(
             self.as_slice(),
@@ -72,8 +107,12 @@ impl<T: PartialOrd<U>, U> PartialOrd<Vec<U>> for ArchivedVec<T> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        alloc::{vec, vec::Vec},
-        api::test::roundtrip,
+        alloc::{
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        api::test::{deserialize_into, roundtrip, to_archived},
     };
 
     #[test]
@@ -102,4 +141,27 @@ mod tests {
         roundtrip(&Err::<(), _>(Vec::<i32>::new()));
         roundtrip(&Err::<(), _>(vec![1, 2, 3, 4]));
     }
+
+    #[test]
+    fn deserialize_vec_in_place() {
+        let value = vec!["a string which is not inline".to_string(); 3];
+        to_archived(&value, |archived| {
+            // Longer targets are truncated and their capacity is kept
+            let mut target = (0..5)
+                .map(|_| String::with_capacity(64))
+                .collect::<Vec<_>>();
+            target.reserve(10);
+            let capacity = target.capacity();
+            let element = target[1].as_ptr();
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+            assert_eq!(target.capacity(), capacity);
+            assert_eq!(target[1].as_ptr(), element);
+
+            // Shorter targets are extended
+            let mut target = vec!["x".to_string()];
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+        });
+    }
 }
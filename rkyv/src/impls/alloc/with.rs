@@ -3,30 +3,53 @@ use core::{marker::PhantomData, ops::ControlFlow};
 use ptr_meta::Pointee;
 use rancor::{Fallible, Source};
 
+#[cfg(target_has_atomic = "ptr")]
+use crate::{alloc::sync::Arc, rc::ArcFlavor};
 use crate::{
     alloc::{
         borrow::Cow,
         boxed::Box,
         collections::{BTreeMap, BTreeSet},
         rc::Rc,
+        string::String,
         vec::Vec,
     },
+    api::high::{to_bytes, to_bytes_canonical, HighSerializer},
+    boxed::{ArchivedBox, BoxResolver},
+    checksum::{ArchivedChecksummed, ChecksumSerializer, ChecksummedResolver},
     collections::{
         btree_map::{ArchivedBTreeMap, BTreeMapResolver},
+        roaring::{ArchivedRoaringBitmap, RoaringBitmapResolver},
+        segmented_vec::{ArchivedSegmentedVec, SegmentedVecResolver},
         util::{Entry, EntryAdapter},
     },
+    columnar::{
+        ArchiveColumns, ArchivedColumnar, DeserializeColumns, SerializeColumns,
+    },
+    compression::{ArchivedCompressed, Codec, CompressedResolver},
+    de::{Metadata, Pooling},
+    ext_ref::{ArchivedExtRef, ExtRefResolver, ExternalWriter},
     impls::core::with::RefWrapper,
     niche::option_box::{ArchivedOptionBox, OptionBoxResolver},
-    ser::{Allocator, Writer},
+    primitive::ArchivedI64,
+    rc::{ArchivedRc, RcFlavor, RcResolver},
+    rel_ptr::{signed_offset, Offset},
+    ser::{
+        allocator::ArenaHandle,
+        sharing::{Internable, InterningExt as _},
+        Allocator, Interning, Sharing, Writer,
+    },
     string::{ArchivedString, StringResolver},
     traits::LayoutRaw,
+    util::AlignedVec,
     vec::{ArchivedVec, VecResolver},
     with::{
-        ArchiveWith, AsOwned, AsVec, DeserializeWith, Map, MapKV, Niche,
-        SerializeWith, Unshare,
+        ArchiveWith, AsBitmap, AsOwned, AsVec, Checksummed, Columnar,
+        Compressed, DeserializeWith, External, Intern, Map, MapKV,
+        NarrowOffset, Niche, Segmented, SerializeWith, Unshare, WideOffset,
     },
     Archive, ArchiveUnsized, ArchivedMetadata, Deserialize, DeserializeUnsized,
-    Place, Serialize, SerializeUnsized,
+    Place, Portable, Serialize, SerializeUnsized,
 };
 
 // Implementation for `MapKV`
@@ -169,6 +192,13 @@ where
             fn serialize(&self, s: &mut S) -> Result<Self::Resolver, S::Error> {
                 A::serialize_with(self.0, s)
             }
+
+            fn check_resolver(
+                resolver: &Self::Resolver,
+                pos: usize,
+            ) -> Result<(), S::Error> {
+                A::check_resolver(resolver, pos)
+            }
         }
 
         let iter = field
@@ -428,310 +458,1788 @@ where
     }
 }
 
-// Niche
+// Columnar
 
-impl<T> ArchiveWith<Option<Box<T>>> for Niche
-where
-    T: ArchiveUnsized + ?Sized,
-    ArchivedMetadata<T>: Default,
-{
-    type Archived = ArchivedOptionBox<T::Archived>;
-    type Resolver = OptionBoxResolver;
+impl<T: ArchiveColumns> ArchiveWith<Vec<T>> for Columnar {
+    type Archived = ArchivedColumnar<T::Columns>;
+    type Resolver = T::ColumnsResolver;
 
     fn resolve_with(
-        field: &Option<Box<T>>,
+        field: &Vec<T>,
         resolver: Self::Resolver,
         out: Place<Self::Archived>,
     ) {
-        ArchivedOptionBox::resolve_from_option(field.as_deref(), resolver, out);
+        let columns = ArchivedColumnar::emplace_len(field.len(), out);
+        T::resolve_columns(field.len(), resolver, columns);
     }
 }
 
-impl<T, S> SerializeWith<Option<Box<T>>, S> for Niche
+impl<T, S> SerializeWith<Vec<T>, S> for Columnar
 where
-    T: SerializeUnsized<S> + ?Sized,
-    S: Fallible + Writer + ?Sized,
-    ArchivedMetadata<T>: Default,
+    T: SerializeColumns<S>,
+    S: Fallible + ?Sized,
 {
     fn serialize_with(
-        field: &Option<Box<T>>,
+        field: &Vec<T>,
         serializer: &mut S,
     ) -> Result<Self::Resolver, S::Error> {
-        ArchivedOptionBox::serialize_from_option(field.as_deref(), serializer)
+        T::serialize_columns(field.as_slice(), serializer)
     }
 }
 
-impl<T, D> DeserializeWith<ArchivedOptionBox<T::Archived>, Option<Box<T>>, D>
-    for Niche
+impl<T, D> DeserializeWith<ArchivedColumnar<T::Columns>, Vec<T>, D> for Columnar
 where
-    T: ArchiveUnsized + LayoutRaw + Pointee + ?Sized,
-    T::Archived: DeserializeUnsized<T, D>,
+    T: ArchiveColumns,
+    T::Columns: DeserializeColumns<T, D>,
     D: Fallible + ?Sized,
-    D::Error: Source,
 {
     fn deserialize_with(
-        field: &ArchivedOptionBox<T::Archived>,
+        field: &ArchivedColumnar<T::Columns>,
         deserializer: &mut D,
-    ) -> Result<Option<Box<T>>, D::Error> {
-        if let Some(value) = field.as_ref() {
-            Ok(Some(value.deserialize(deserializer)?))
-        } else {
-            Ok(None)
+    ) -> Result<Vec<T>, D::Error> {
+        let mut result = Vec::with_capacity(field.len());
+        for i in 0..field.len() {
+            result.push(field.columns().deserialize_row(i, deserializer)?);
         }
+        Ok(result)
     }
 }
 
-// Unshare
+// Segmented
 
-#[cfg(target_has_atomic = "ptr")]
-impl<T: Archive> ArchiveWith<crate::alloc::sync::Arc<T>> for Unshare {
-    type Archived = T::Archived;
-    type Resolver = T::Resolver;
+impl<T: Archive, const SEGMENT_LEN: usize> ArchiveWith<Vec<T>>
+    for Segmented<SEGMENT_LEN>
+{
+    type Archived = ArchivedSegmentedVec<T::Archived>;
+    type Resolver = SegmentedVecResolver;
 
     fn resolve_with(
-        x: &crate::alloc::sync::Arc<T>,
+        field: &Vec<T>,
         resolver: Self::Resolver,
         out: Place<Self::Archived>,
     ) {
-        x.as_ref().resolve(resolver, out)
+        ArchivedSegmentedVec::resolve_from_len(field.len(), resolver, out);
     }
 }
 
-#[cfg(target_has_atomic = "ptr")]
-impl<T, S> SerializeWith<crate::alloc::sync::Arc<T>, S> for Unshare
+impl<T, S, const SEGMENT_LEN: usize> SerializeWith<Vec<T>, S>
+    for Segmented<SEGMENT_LEN>
 where
     T: Serialize<S>,
-    S: Fallible + ?Sized,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
 {
     fn serialize_with(
-        x: &crate::alloc::sync::Arc<T>,
-        s: &mut S,
+        field: &Vec<T>,
+        serializer: &mut S,
     ) -> Result<Self::Resolver, S::Error> {
-        x.as_ref().serialize(s)
+        let segment_len = if SEGMENT_LEN == 0 {
+            ArchivedSegmentedVec::<T::Archived>::max_segment_len()
+        } else {
+            SEGMENT_LEN
+        };
+        ArchivedSegmentedVec::serialize_from_slice(
+            field.as_slice(),
+            segment_len,
+            serializer,
+        )
     }
 }
 
-#[cfg(target_has_atomic = "ptr")]
-impl<A, T, D> DeserializeWith<A, crate::alloc::sync::Arc<T>, D> for Unshare
+impl<T, D, const SEGMENT_LEN: usize>
+    DeserializeWith<ArchivedSegmentedVec<T::Archived>, Vec<T>, D>
+    for Segmented<SEGMENT_LEN>
 where
-    A: Deserialize<T, D>,
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
     D: Fallible + ?Sized,
 {
     fn deserialize_with(
-        x: &A,
-        d: &mut D,
-    ) -> Result<crate::alloc::sync::Arc<T>, D::Error> {
-        Ok(crate::alloc::sync::Arc::new(A::deserialize(x, d)?))
+        field: &ArchivedSegmentedVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        let mut result = Vec::with_capacity(field.len() as usize);
+        for value in field.iter() {
+            result.push(value.deserialize(deserializer)?);
+        }
+        Ok(result)
     }
 }
 
-impl<T: Archive> ArchiveWith<Rc<T>> for Unshare {
-    type Archived = T::Archived;
-    type Resolver = T::Resolver;
+// Checksummed
+
+type SubtreeSerializer<'a, E> = HighSerializer<AlignedVec, ArenaHandle<'a>, E>;
+
+impl<T: Archive> ArchiveWith<T> for Checksummed {
+    type Archived = ArchivedChecksummed<T::Archived>;
+    type Resolver = ChecksummedResolver;
 
     fn resolve_with(
-        x: &Rc<T>,
+        _: &T,
         resolver: Self::Resolver,
         out: Place<Self::Archived>,
     ) {
-        x.as_ref().resolve(resolver, out)
+        ArchivedChecksummed::resolve_from_resolver(resolver, out);
     }
 }
 
-impl<T: Serialize<S>, S: Fallible + ?Sized> SerializeWith<Rc<T>, S>
-    for Unshare
+/// Serializes `field` to a separate buffer, canonically if `serializer` is
+/// canonical.
+fn serialize_subtree<T, S>(
+    field: &T,
+    serializer: &S,
+) -> Result<AlignedVec, S::Error>
+where
+    T: for<'a> Serialize<SubtreeSerializer<'a, S::Error>>,
+    S: Fallible + Writer + ?Sized,
+    S::Error: Source,
+{
+    if serializer.is_canonical() {
+        to_bytes_canonical::<S::Error>(field)
+    } else {
+        to_bytes::<S::Error>(field)
+    }
+}
+
+impl<T, S> SerializeWith<T, S> for Checksummed
+where
+    T: Archive + for<'a> Serialize<ChecksumSerializer<'a, S>>,
+    S: Fallible + Writer + ?Sized,
+    S::Error: Source,
 {
     fn serialize_with(
-        x: &Rc<T>,
-        s: &mut S,
+        field: &T,
+        serializer: &mut S,
     ) -> Result<Self::Resolver, S::Error> {
-        x.as_ref().serialize(s)
+        ArchivedChecksummed::<T::Archived>::serialize_from_value(
+            field, serializer,
+        )
     }
 }
 
-impl<A, T, D> DeserializeWith<A, Rc<T>, D> for Unshare
+impl<T, D> DeserializeWith<ArchivedChecksummed<T::Archived>, T, D>
+    for Checksummed
 where
-    A: Deserialize<T, D>,
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
     D: Fallible + ?Sized,
+    D::Error: Source,
 {
-    fn deserialize_with(x: &A, d: &mut D) -> Result<Rc<T>, D::Error> {
-        Ok(Rc::new(A::deserialize(x, d)?))
+    fn deserialize_with(
+        field: &ArchivedChecksummed<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<T, D::Error> {
+        field.get_verified()?.deserialize(deserializer)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use core::mem::size_of;
+// Compressed
 
-    use crate::{
-        alloc::{
-            borrow::Cow,
-            boxed::Box,
-            collections::{BTreeMap, BTreeSet},
-            string::{String, ToString},
-        },
-        api::test::{roundtrip, roundtrip_with, to_archived},
-        niche::niching::Null,
-        with::{
-            AsOwned, AsVec, DefaultNiche, InlineAsBox, Map, MapKV, Niche,
-            NicheInto,
-        },
-        Archive, Deserialize, Serialize,
-    };
+impl<T: Archive, C> ArchiveWith<T> for Compressed<C> {
+    type Archived = ArchivedCompressed<T::Archived, C>;
+    type Resolver = CompressedResolver;
 
-    #[derive(Debug, Archive, Deserialize, Serialize, PartialEq)]
-    #[rkyv(crate, compare(PartialEq), derive(Debug))]
-    struct Test {
-        value: Option<Box<u128>>,
+    fn resolve_with(
+        _: &T,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedCompressed::resolve_from_resolver(resolver, out);
     }
+}
 
-    #[test]
-    fn roundtrip_niche_none() {
-        roundtrip(&Test { value: None });
+impl<T, C, S> SerializeWith<T, S> for Compressed<C>
+where
+    T: Archive + for<'a> Serialize<SubtreeSerializer<'a, S::Error>>,
+    C: Codec,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &T,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let bytes = serialize_subtree(field, serializer)?;
+        ArchivedCompressed::<T::Archived, C>::serialize_from_bytes(
+            &bytes, serializer,
+        )
     }
+}
 
-    #[test]
-    fn roundtrip_niche_some() {
-        roundtrip(&Test {
-            value: Some(Box::new(128)),
-        });
+#[cfg(feature = "bytecheck")]
+impl<T, C, D> DeserializeWith<ArchivedCompressed<T::Archived, C>, T, D>
+    for Compressed<C>
+where
+    T: Archive,
+    T::Archived: for<'a> bytecheck::CheckBytes<
+            crate::api::high::HighValidator<'a, D::Error>,
+        > + Deserialize<T, crate::api::high::HighDeserializer<D::Error>>,
+    C: Codec,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        field: &ArchivedCompressed<T::Archived, C>,
+        _: &mut D,
+    ) -> Result<T, D::Error> {
+        // The decompressed archive is freed after deserializing, so it gets a
+        // fresh deserializer instead of sharing pointers by address.
+        let mut buffer = AlignedVec::<16>::new();
+        let archived = field.decompress_and_access(&mut buffer)?;
+        crate::api::high::deserialize(archived)
     }
+}
 
-    #[test]
-    fn ambiguous_niched_archived_box() {
-        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
-        #[rkyv(crate, compare(PartialEq), derive(Debug))]
-        struct HasNiche {
-            #[rkyv(with = Niche)]
-            inner: Option<Box<[u32]>>,
-        }
-
-        roundtrip(&HasNiche {
-            inner: Some(Box::<[u32]>::from([])),
-        });
-        roundtrip(&HasNiche { inner: None });
-    }
+// Intern
 
-    #[test]
-    fn with_as_owned() {
-        #[derive(Archive, Serialize, Deserialize)]
-        #[rkyv(crate)]
-        struct Test<'a> {
-            #[rkyv(with = AsOwned)]
-            a: Cow<'a, u32>,
-            #[rkyv(with = AsOwned)]
-            b: Cow<'a, [u32]>,
-            #[rkyv(with = AsOwned)]
-            c: Cow<'a, str>,
-        }
+impl ArchiveWith<String> for Intern {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
 
-        let value = Test {
-            a: Cow::Borrowed(&100),
-            b: Cow::Borrowed(&[1, 2, 3, 4, 5, 6]),
-            c: Cow::Borrowed("hello world"),
-        };
-        to_archived(&value, |archived| {
-            assert_eq!(archived.a, 100);
-            assert_eq!(archived.b, [1, 2, 3, 4, 5, 6]);
-            assert_eq!(archived.c, "hello world");
-        });
+    fn resolve_with(
+        field: &String,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedString::resolve_from_str(field.as_str(), resolver, out);
     }
+}
 
-    #[test]
-    fn with_as_map() {
-        #[derive(Archive, Serialize, Deserialize)]
-        #[rkyv(crate)]
-        struct Test<'a> {
-            #[rkyv(with = Map<InlineAsBox>)]
-            a: Option<&'a str>,
-            #[rkyv(with = Map<InlineAsBox>)]
-            b: Option<&'a str>,
-        }
+impl<S> SerializeWith<String, S> for Intern
+where
+    S: Fallible + Interning + ?Sized,
+    S::Error: Source,
+    str: SerializeUnsized<S>,
+{
+    fn serialize_with(
+        field: &String,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str_interned(field.as_str(), serializer)
+    }
+}
 
-        let value = Test {
-            a: Some("foo"),
-            b: None,
-        };
+impl<T: ArchiveUnsized + ?Sized> ArchiveWith<Box<T>> for Intern {
+    type Archived = ArchivedBox<T::Archived>;
+    type Resolver = BoxResolver;
 
-        to_archived(&value, |archived| {
-            assert!(archived.a.is_some());
-            assert!(archived.b.is_none());
-        });
+    fn resolve_with(
+        field: &Box<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedBox::resolve_from_ref(field.as_ref(), resolver, out);
     }
+}
 
-    #[test]
-    fn with_as_mapkv() {
-        #[derive(Archive, Serialize, Deserialize)]
-        #[rkyv(crate)]
-        struct Test<'a> {
-            #[rkyv(with = MapKV<InlineAsBox, InlineAsBox>)]
-            a: BTreeMap<&'a str, &'a str>,
-        }
-
-        let mut a = BTreeMap::new();
-        a.insert("foo", "bar");
-        a.insert("woo", "roo");
+impl<T, S> SerializeWith<Box<T>, S> for Intern
+where
+    T: Internable + SerializeUnsized<S> + ?Sized,
+    S: Fallible + Interning + ?Sized,
+{
+    fn serialize_with(
+        field: &Box<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let pos = serializer.serialize_interned(field.as_ref())?;
+        Ok(BoxResolver::from_pos(pos))
+    }
+}
 
-        let value = Test { a };
+impl<T: Archive> ArchiveWith<Vec<T>> for Intern {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
 
-        to_archived(&value, |archived| {
-            assert_eq!(archived.a.len(), 2);
-            assert!(archived.a.contains_key("foo"));
-            assert_eq!(**archived.a.get("woo").unwrap(), *"roo");
-        });
+    fn resolve_with(
+        field: &Vec<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedVec::resolve_from_slice(field.as_slice(), resolver, out);
     }
+}
 
-    #[test]
-    fn with_as_vec() {
-        #[derive(Archive, Serialize, Deserialize)]
+impl<T, S> SerializeWith<Vec<T>, S> for Intern
+where
+    T: Archive,
+    [T]: Internable + SerializeUnsized<S>,
+    S: Fallible + Interning + ?Sized,
+{
+    fn serialize_with(
+        field: &Vec<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let pos = serializer.serialize_interned(field.as_slice())?;
+        Ok(VecResolver::from_pos(pos))
+    }
+}
+
+impl<A, T, D> DeserializeWith<A, T, D> for Intern
+where
+    A: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &A,
+        deserializer: &mut D,
+    ) -> Result<T, D::Error> {
+        field.deserialize(deserializer)
+    }
+}
+
+// External
+
+impl<T: Archive> ArchiveWith<T> for External {
+    type Archived = ArchivedExtRef<T::Archived>;
+    type Resolver = ExtRefResolver;
+
+    fn resolve_with(
+        _: &T,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedExtRef::resolve_from_resolver(resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<T, S> for External
+where
+    T: Archive + for<'a> Serialize<SubtreeSerializer<'a, S::Error>>,
+    S: Fallible + ExternalWriter + ?Sized,
+{
+    fn serialize_with(
+        field: &T,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        serializer.serialize_external(field)
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+impl<T, D> DeserializeWith<ArchivedExtRef<T::Archived>, T, D> for External
+where
+    T: Archive,
+    T::Archived: for<'a> bytecheck::CheckBytes<
+            crate::api::high::HighValidator<'a, D::Error>,
+        > + Deserialize<T, crate::api::high::HighDeserializer<D::Error>>,
+    D: Fallible + crate::ext_ref::ExtRefContext + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        field: &ArchivedExtRef<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<T, D::Error> {
+        // The external archive may be validated separately from the archive
+        // containing the reference, so the value is validated again here.
+        let bytes = deserializer.external_archive(field.archive_id())?;
+        let archived = crate::api::high::access_pos::<T::Archived, D::Error>(
+            bytes,
+            field.pos(),
+        )?;
+        crate::api::high::deserialize(archived)
+    }
+}
+
+// WideOffset and NarrowOffset
+
+fn check_offset_range<O, E>(from: usize, to: usize) -> Result<(), E>
+where
+    O: Offset,
+    E: Source,
+{
+    O::from_isize::<E>(signed_offset(from, to)?)?;
+    Ok(())
+}
+
+macro_rules! impl_with_offset {
+    ($wrapper:ty, $offset:ty $(, $param:ident)?) => {
+        impl<T, $($param: Offset + Portable)?> ArchiveWith<Box<T>>
+            for $wrapper
+        where
+            T: ArchiveUnsized + ?Sized,
+        {
+            type Archived = ArchivedBox<T::Archived, $offset>;
+            type Resolver = BoxResolver;
+
+            fn resolve_with(
+                field: &Box<T>,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                ArchivedBox::resolve_from_ref(field.as_ref(), resolver, out);
+            }
+        }
+
+        impl<T, S, $($param: Offset + Portable)?> SerializeWith<Box<T>, S>
+            for $wrapper
+        where
+            T: SerializeUnsized<S> + ?Sized,
+            S: Fallible + Writer + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize_with(
+                field: &Box<T>,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                let pos = field.as_ref().serialize_unsized(serializer)?;
+                // Values serialized later only push the data farther away
+                check_offset_range::<$offset, S::Error>(
+                    serializer.pos(),
+                    pos,
+                )?;
+                Ok(BoxResolver::from_pos(pos))
+            }
+
+            fn check_resolver(
+                resolver: &Self::Resolver,
+                pos: usize,
+            ) -> Result<(), S::Error> {
+                check_offset_range::<$offset, S::Error>(pos, resolver.pos())
+            }
+        }
+
+        impl<T, D, $($param: Offset + Portable)?>
+            DeserializeWith<ArchivedBox<T::Archived, $offset>, Box<T>, D>
+            for $wrapper
+        where
+            T: ArchiveUnsized + LayoutRaw + ?Sized,
+            T::Archived: DeserializeUnsized<T, D>,
+            D: Fallible + ?Sized,
+            D::Error: Source,
+        {
+            fn deserialize_with(
+                field: &ArchivedBox<T::Archived, $offset>,
+                deserializer: &mut D,
+            ) -> Result<Box<T>, D::Error> {
+                field.deserialize(deserializer)
+            }
+        }
+
+        impl<T: Archive, $($param: Offset + Portable)?> ArchiveWith<Vec<T>>
+            for $wrapper
+        {
+            type Archived = ArchivedVec<T::Archived, $offset>;
+            type Resolver = VecResolver;
+
+            fn resolve_with(
+                field: &Vec<T>,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                ArchivedVec::resolve_from_slice(field, resolver, out);
+            }
+        }
+
+        impl<T, S, $($param: Offset + Portable)?> SerializeWith<Vec<T>, S>
+            for $wrapper
+        where
+            T: Serialize<S>,
+            S: Fallible + Allocator + Writer + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize_with(
+                field: &Vec<T>,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                let resolver =
+                    ArchivedVec::serialize_from_slice(field, serializer)?;
+                // Values serialized later only push the data farther away
+                check_offset_range::<$offset, S::Error>(
+                    serializer.pos(),
+                    resolver.pos(),
+                )?;
+                Ok(resolver)
+            }
+
+            fn check_resolver(
+                resolver: &Self::Resolver,
+                pos: usize,
+            ) -> Result<(), S::Error> {
+                check_offset_range::<$offset, S::Error>(pos, resolver.pos())
+            }
+        }
+
+        impl<T, D, $($param: Offset + Portable)?>
+            DeserializeWith<ArchivedVec<T::Archived, $offset>, Vec<T>, D>
+            for $wrapper
+        where
+            T: Archive,
+            [T::Archived]: DeserializeUnsized<[T], D>,
+            D: Fallible + ?Sized,
+            D::Error: Source,
+        {
+            fn deserialize_with(
+                field: &ArchivedVec<T::Archived, $offset>,
+                deserializer: &mut D,
+            ) -> Result<Vec<T>, D::Error> {
+                field.deserialize(deserializer)
+            }
+        }
+
+        impl<$($param: Offset + Portable)?> ArchiveWith<String>
+            for $wrapper
+        {
+            type Archived = ArchivedString<$offset>;
+            type Resolver = StringResolver;
+
+            fn resolve_with(
+                field: &String,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                ArchivedString::resolve_from_str(field, resolver, out);
+            }
+        }
+
+        impl<S, $($param: Offset + Portable)?> SerializeWith<String, S>
+            for $wrapper
+        where
+            S: Fallible + Writer + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize_with(
+                field: &String,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                let resolver =
+                    ArchivedString::serialize_from_str(field, serializer)?;
+                if let Some(pos) = resolver.out_of_line_pos() {
+                    // Values serialized later only push the data farther away
+                    check_offset_range::<$offset, S::Error>(
+                        serializer.pos(),
+                        pos,
+                    )?;
+                }
+                Ok(resolver)
+            }
+
+            fn check_resolver(
+                resolver: &Self::Resolver,
+                pos: usize,
+            ) -> Result<(), S::Error> {
+                match resolver.out_of_line_pos() {
+                    Some(to) => {
+                        check_offset_range::<$offset, S::Error>(pos, to)
+                    }
+                    None => Ok(()),
+                }
+            }
+        }
+
+        impl<D, $($param: Offset + Portable)?>
+            DeserializeWith<ArchivedString<$offset>, String, D>
+            for $wrapper
+        where
+            D: Fallible + ?Sized,
+        {
+            fn deserialize_with(
+                field: &ArchivedString<$offset>,
+                deserializer: &mut D,
+            ) -> Result<String, D::Error> {
+                field.deserialize(deserializer)
+            }
+        }
+    };
+}
+
+macro_rules! impl_shared_with_offset {
+    (
+        $pointer:ident,
+        $flavor:ty,
+        $wrapper:ty,
+        $offset:ty
+        $(, $param:ident)?
+    ) => {
+        impl<T, $($param: Offset + Portable)?> ArchiveWith<$pointer<T>>
+            for $wrapper
+        where
+            T: ArchiveUnsized + ?Sized,
+        {
+            type Archived = ArchivedRc<T::Archived, $flavor, $offset>;
+            type Resolver = RcResolver;
+
+            fn resolve_with(
+                field: &$pointer<T>,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                ArchivedRc::resolve_from_ref(field.as_ref(), resolver, out);
+            }
+        }
+
+        impl<T, S, $($param: Offset + Portable)?> SerializeWith<$pointer<T>, S>
+            for $wrapper
+        where
+            T: SerializeUnsized<S> + ?Sized + 'static,
+            S: Fallible + Writer + Sharing + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize_with(
+                field: &$pointer<T>,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                let resolver =
+                    ArchivedRc::<T::Archived, $flavor>::serialize_from_ref(
+                        field.as_ref(),
+                        serializer,
+                    )?;
+                // Values serialized later only push the data farther away
+                check_offset_range::<$offset, S::Error>(
+                    serializer.pos(),
+                    resolver.pos(),
+                )?;
+                Ok(resolver)
+            }
+
+            fn check_resolver(
+                resolver: &Self::Resolver,
+                pos: usize,
+            ) -> Result<(), S::Error> {
+                check_offset_range::<$offset, S::Error>(pos, resolver.pos())
+            }
+        }
+
+        impl<T, D, $($param: Offset + Portable)?>
+            DeserializeWith<
+                ArchivedRc<T::Archived, $flavor, $offset>,
+                $pointer<T>,
+                D,
+            > for $wrapper
+        where
+            T: ArchiveUnsized + LayoutRaw + Pointee + ?Sized + 'static,
+            T::Archived: DeserializeUnsized<T, D>,
+            T::Metadata: Into<Metadata>,
+            Metadata: Into<T::Metadata>,
+            D: Fallible + Pooling + ?Sized,
+            D::Error: Source,
+        {
+            fn deserialize_with(
+                field: &ArchivedRc<T::Archived, $flavor, $offset>,
+                deserializer: &mut D,
+            ) -> Result<$pointer<T>, D::Error> {
+                field.deserialize(deserializer)
+            }
+        }
+    };
+}
+
+impl_with_offset!(WideOffset, ArchivedI64);
+impl_with_offset!(NarrowOffset<O>, O, O);
+impl_shared_with_offset!(Rc, RcFlavor, WideOffset, ArchivedI64);
+impl_shared_with_offset!(Rc, RcFlavor, NarrowOffset<O>, O, O);
+#[cfg(target_has_atomic = "ptr")]
+impl_shared_with_offset!(Arc, ArcFlavor, WideOffset, ArchivedI64);
+#[cfg(target_has_atomic = "ptr")]
+impl_shared_with_offset!(Arc, ArcFlavor, NarrowOffset<O>, O, O);
+
+// AsBitmap
+
+impl ArchiveWith<BTreeSet<u32>> for AsBitmap {
+    type Archived = ArchivedRoaringBitmap;
+    type Resolver = RoaringBitmapResolver;
+
+    fn resolve_with(
+        _: &BTreeSet<u32>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedRoaringBitmap::resolve_from_resolver(resolver, out);
+    }
+}
+
+impl<S> SerializeWith<BTreeSet<u32>, S> for AsBitmap
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &BTreeSet<u32>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedRoaringBitmap::serialize_from_sorted_iter(
+            field.iter().copied(),
+            serializer,
+        )
+    }
+}
+
+impl<D> DeserializeWith<ArchivedRoaringBitmap, BTreeSet<u32>, D> for AsBitmap
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedRoaringBitmap,
+        _: &mut D,
+    ) -> Result<BTreeSet<u32>, D::Error> {
+        Ok(field.iter().collect())
+    }
+}
+
+// Niche
+
+impl<T> ArchiveWith<Option<Box<T>>> for Niche
+where
+    T: ArchiveUnsized + ?Sized,
+    ArchivedMetadata<T>: Default,
+{
+    type Archived = ArchivedOptionBox<T::Archived>;
+    type Resolver = OptionBoxResolver;
+
+    fn resolve_with(
+        field: &Option<Box<T>>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedOptionBox::resolve_from_option(field.as_deref(), resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Option<Box<T>>, S> for Niche
+where
+    T: SerializeUnsized<S> + ?Sized,
+    S: Fallible + Writer + ?Sized,
+    ArchivedMetadata<T>: Default,
+{
+    fn serialize_with(
+        field: &Option<Box<T>>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedOptionBox::serialize_from_option(field.as_deref(), serializer)
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedOptionBox<T::Archived>, Option<Box<T>>, D>
+    for Niche
+where
+    T: ArchiveUnsized + LayoutRaw + Pointee + ?Sized,
+    T::Archived: DeserializeUnsized<T, D>,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        field: &ArchivedOptionBox<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Option<Box<T>>, D::Error> {
+        if let Some(value) = field.as_ref() {
+            Ok(Some(value.deserialize(deserializer)?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+// Unshare
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T: Archive> ArchiveWith<crate::alloc::sync::Arc<T>> for Unshare {
+    type Archived = T::Archived;
+    type Resolver = T::Resolver;
+
+    fn resolve_with(
+        x: &crate::alloc::sync::Arc<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        x.as_ref().resolve(resolver, out)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T, S> SerializeWith<crate::alloc::sync::Arc<T>, S> for Unshare
+where
+    T: Serialize<S>,
+    S: Fallible + ?Sized,
+{
+    fn serialize_with(
+        x: &crate::alloc::sync::Arc<T>,
+        s: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        x.as_ref().serialize(s)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<A, T, D> DeserializeWith<A, crate::alloc::sync::Arc<T>, D> for Unshare
+where
+    A: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        x: &A,
+        d: &mut D,
+    ) -> Result<crate::alloc::sync::Arc<T>, D::Error> {
+        Ok(crate::alloc::sync::Arc::new(A::deserialize(x, d)?))
+    }
+}
+
+impl<T: Archive> ArchiveWith<Rc<T>> for Unshare {
+    type Archived = T::Archived;
+    type Resolver = T::Resolver;
+
+    fn resolve_with(
+        x: &Rc<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        x.as_ref().resolve(resolver, out)
+    }
+}
+
+impl<T: Serialize<S>, S: Fallible + ?Sized> SerializeWith<Rc<T>, S>
+    for Unshare
+{
+    fn serialize_with(
+        x: &Rc<T>,
+        s: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        x.as_ref().serialize(s)
+    }
+}
+
+impl<A, T, D> DeserializeWith<A, Rc<T>, D> for Unshare
+where
+    A: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(x: &A, d: &mut D) -> Result<Rc<T>, D::Error> {
+        Ok(Rc::new(A::deserialize(x, d)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::size_of;
+
+    use crate::{
+        alloc::{
+            borrow::Cow,
+            boxed::Box,
+            collections::{BTreeMap, BTreeSet},
+            string::{String, ToString},
+            vec::Vec,
+        },
+        api::test::{deserialize, roundtrip, roundtrip_with, to_archived},
+        collections::roaring::ContainerKind,
+        niche::niching::Null,
+        with::{
+            AsBitmap, AsOwned, AsVec, Checksummed, Columnar, DefaultNiche,
+            InlineAsBox, Intern, Map, MapKV, NarrowOffset, Niche, NicheInto,
+            Segmented, WideOffset,
+        },
+        Archive, Deserialize, Serialize,
+    };
+
+    #[derive(Debug, Archive, Deserialize, Serialize, PartialEq)]
+    #[rkyv(crate, compare(PartialEq), derive(Debug))]
+    struct Test {
+        value: Option<Box<u128>>,
+    }
+
+    #[test]
+    fn roundtrip_niche_none() {
+        roundtrip(&Test { value: None });
+    }
+
+    #[test]
+    fn roundtrip_niche_some() {
+        roundtrip(&Test {
+            value: Some(Box::new(128)),
+        });
+    }
+
+    #[test]
+    fn ambiguous_niched_archived_box() {
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[rkyv(crate, compare(PartialEq), derive(Debug))]
+        struct HasNiche {
+            #[rkyv(with = Niche)]
+            inner: Option<Box<[u32]>>,
+        }
+
+        roundtrip(&HasNiche {
+            inner: Some(Box::<[u32]>::from([])),
+        });
+        roundtrip(&HasNiche { inner: None });
+    }
+
+    #[test]
+    fn with_as_owned() {
+        #[derive(Archive, Serialize, Deserialize)]
+        #[rkyv(crate)]
+        struct Test<'a> {
+            #[rkyv(with = AsOwned)]
+            a: Cow<'a, u32>,
+            #[rkyv(with = AsOwned)]
+            b: Cow<'a, [u32]>,
+            #[rkyv(with = AsOwned)]
+            c: Cow<'a, str>,
+        }
+
+        let value = Test {
+            a: Cow::Borrowed(&100),
+            b: Cow::Borrowed(&[1, 2, 3, 4, 5, 6]),
+            c: Cow::Borrowed("hello world"),
+        };
+        to_archived(&value, |archived| {
+            assert_eq!(archived.a, 100);
+            assert_eq!(archived.b, [1, 2, 3, 4, 5, 6]);
+            assert_eq!(archived.c, "hello world");
+        });
+    }
+
+    #[test]
+    fn with_as_map() {
+        #[derive(Archive, Serialize, Deserialize)]
+        #[rkyv(crate)]
+        struct Test<'a> {
+            #[rkyv(with = Map<InlineAsBox>)]
+            a: Option<&'a str>,
+            #[rkyv(with = Map<InlineAsBox>)]
+            b: Option<&'a str>,
+        }
+
+        let value = Test {
+            a: Some("foo"),
+            b: None,
+        };
+
+        to_archived(&value, |archived| {
+            assert!(archived.a.is_some());
+            assert!(archived.b.is_none());
+        });
+    }
+
+    #[test]
+    fn with_as_mapkv() {
+        #[derive(Archive, Serialize, Deserialize)]
+        #[rkyv(crate)]
+        struct Test<'a> {
+            #[rkyv(with = MapKV<InlineAsBox, InlineAsBox>)]
+            a: BTreeMap<&'a str, &'a str>,
+        }
+
+        let mut a = BTreeMap::new();
+        a.insert("foo", "bar");
+        a.insert("woo", "roo");
+
+        let value = Test { a };
+
+        to_archived(&value, |archived| {
+            assert_eq!(archived.a.len(), 2);
+            assert!(archived.a.contains_key("foo"));
+            assert_eq!(**archived.a.get("woo").unwrap(), *"roo");
+        });
+    }
+
+    #[test]
+    fn with_as_vec() {
+        #[derive(Archive, Serialize, Deserialize)]
+        #[rkyv(crate)]
+        struct Test {
+            #[rkyv(with = AsVec)]
+            a: BTreeMap<String, String>,
+            #[rkyv(with = AsVec)]
+            b: BTreeSet<String>,
+            #[rkyv(with = AsVec)]
+            c: BTreeMap<String, String>,
+        }
+
+        let mut a = BTreeMap::new();
+        a.insert("foo".to_string(), "hello".to_string());
+        a.insert("bar".to_string(), "world".to_string());
+        a.insert("baz".to_string(), "bat".to_string());
+
+        let mut b = BTreeSet::new();
+        b.insert("foo".to_string());
+        b.insert("hello world!".to_string());
+        b.insert("bar".to_string());
+        b.insert("fizzbuzz".to_string());
+
+        let c = BTreeMap::new();
+
+        let value = Test { a, b, c };
+
+        to_archived(&value, |archived| {
+            assert_eq!(archived.a.len(), 3);
+            assert!(archived
+                .a
+                .iter()
+                .find(|&e| e.key == "foo" && e.value == "hello")
+                .is_some());
+            assert!(archived
+                .a
+                .iter()
+                .find(|&e| e.key == "bar" && e.value == "world")
+                .is_some());
+            assert!(archived
+                .a
+                .iter()
+                .find(|&e| e.key == "baz" && e.value == "bat")
+                .is_some());
+
+            assert_eq!(archived.b.len(), 4);
+            assert!(archived.b.iter().find(|&e| e == "foo").is_some());
+            assert!(archived.b.iter().find(|&e| e == "hello world!").is_some());
+            assert!(archived.b.iter().find(|&e| e == "bar").is_some());
+            assert!(archived.b.iter().find(|&e| e == "fizzbuzz").is_some());
+        });
+    }
+
+    #[test]
+    fn with_columnar() {
+        use crate::alloc::vec;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, columnar)]
+        struct Point {
+            x: f32,
+            y: f32,
+            #[rkyv(with = AsOwned)]
+            label: Cow<'static, str>,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, columnar)]
+        struct Pair<T>(u8, T);
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = Columnar)]
+            points: Vec<Point>,
+            #[rkyv(with = Columnar)]
+            pairs: Vec<Pair<String>>,
+        }
+
+        let value = Test {
+            points: (0..10)
+                .map(|i| Point {
+                    x: i as f32,
+                    y: -(i as f32),
+                    label: Cow::Owned(i.to_string()),
+                })
+                .collect(),
+            pairs: Vec::new(),
+        };
+        roundtrip_with(&value, |value, archived| {
+            let points = &archived.points;
+            assert_eq!(points.len(), value.points.len());
+            assert_eq!(
+                points.columns().x.as_slice(),
+                value.points.iter().map(|p| p.x).collect::<Vec<_>>(),
+            );
+            assert_eq!(points.columns().label[3], "3");
+
+            let row = points.get(5).unwrap();
+            assert_eq!(*row.x, 5.0);
+            assert_eq!(*row.y, -5.0);
+            assert_eq!(row.label, "5");
+            assert!(points.get(10).is_none());
+            assert_eq!(points.rows().count(), 10);
+
+            assert!(archived.pairs.is_empty());
+            assert!(archived.pairs.rows().next().is_none());
+        });
+
+        let value = Test {
+            points: Vec::new(),
+            pairs: vec![Pair(1, "a".to_string()), Pair(2, "b".to_string())],
+        };
+        roundtrip_with(&value, |_, archived| {
+            let row = archived.pairs.get(1).unwrap();
+            assert_eq!((*row.0, row.1.as_str()), (2, "b"));
+        });
+    }
+
+    #[test]
+    fn with_segmented() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = Segmented<3>)]
+            strings: Vec<String>,
+            #[rkyv(with = Segmented<4>)]
+            exact: Vec<u32>,
+            #[rkyv(with = Segmented)]
+            empty: Vec<u32>,
+        }
+
+        let value = Test {
+            strings: (0..10).map(|i| i.to_string()).collect(),
+            exact: (0..8).collect(),
+            empty: Vec::new(),
+        };
+
+        roundtrip_with(&value, |value, archived| {
+            let lens = archived
+                .strings
+                .segments()
+                .map(|s| s.len())
+                .collect::<Vec<_>>();
+            assert_eq!(lens, [3, 3, 3, 1]);
+            assert_eq!(archived.strings.len(), 10);
+            assert!(archived.strings.iter().eq(value.strings.iter()));
+            assert_eq!(archived.strings.get(7).unwrap(), "7");
+            assert!(archived.strings.get(10).is_none());
+
+            assert_eq!(archived.exact.segments().count(), 2);
+            assert_eq!(archived.exact.get(4).unwrap().to_native(), 4);
+
+            assert!(archived.empty.is_empty());
+            assert!(archived.empty.head().is_none());
+        });
+    }
+
+    #[test]
+    fn with_segmented_long_chain() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = Segmented<1>)]
+            values: Vec<u8>,
+        }
+
+        // Validating each segment must not recurse into the next one.
+        let value = Test {
+            values: (0..100_000).map(|i| i as u8).collect(),
+        };
+        roundtrip_with(&value, |value, archived| {
+            assert_eq!(archived.values.segments().count(), 100_000);
+            assert!(archived.values.iter().eq(value.values.iter()));
+        });
+    }
+
+    #[cfg(feature = "pointer_width_16")]
+    #[test]
+    fn with_segmented_out_of_pointer_range() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = Segmented)]
+            values: Vec<u32>,
+        }
+
+        // 40 KiB of elements can't be reached with a single 16-bit offset.
+        let value = Test {
+            values: (0..10_240).collect(),
+        };
+        roundtrip_with(&value, |value, archived| {
+            assert!(archived.values.segments().count() > 1);
+            assert_eq!(archived.values.len(), 10_240);
+            assert!(archived.values.iter().eq(value.values.iter()));
+        });
+    }
+
+    #[test]
+    fn with_checksummed() {
+        use rancor::Failure;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = Checksummed)]
+            strings: Vec<String>,
+            #[rkyv(with = Checksummed)]
+            values: Vec<u64>,
+        }
+
+        let value = Test {
+            strings: (0..10).map(|i| i.to_string()).collect(),
+            values: (0..10).collect(),
+        };
+
+        roundtrip_with(&value, |value, archived| {
+            archived.strings.verify::<Failure>().unwrap();
+            let strings = archived.strings.get_verified::<Failure>().unwrap();
+            assert!(strings.iter().eq(value.strings.iter()));
+            assert_eq!(archived.values.get()[7], 7);
+        });
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn with_checksummed_detects_corruption() {
+        use rancor::Failure;
+
+        #[derive(Archive, Serialize)]
         #[rkyv(crate)]
         struct Test {
-            #[rkyv(with = AsVec)]
-            a: BTreeMap<String, String>,
-            #[rkyv(with = AsVec)]
-            b: BTreeSet<String>,
-            #[rkyv(with = AsVec)]
-            c: BTreeMap<String, String>,
+            #[rkyv(with = Checksummed)]
+            strings: Vec<String>,
+            #[rkyv(with = Checksummed)]
+            values: Vec<u64>,
         }
 
-        let mut a = BTreeMap::new();
-        a.insert("foo".to_string(), "hello".to_string());
-        a.insert("bar".to_string(), "world".to_string());
-        a.insert("baz".to_string(), "bat".to_string());
+        let value = Test {
+            strings: (0..10).map(|i| i.to_string()).collect(),
+            values: (0..10).collect(),
+        };
 
-        let mut b = BTreeSet::new();
-        b.insert("foo".to_string());
-        b.insert("hello world!".to_string());
-        b.insert("bar".to_string());
-        b.insert("fizzbuzz".to_string());
+        let mut bytes = crate::to_bytes::<Failure>(&value).unwrap();
+        let archived = crate::access::<ArchivedTest, Failure>(&bytes).unwrap();
+        let values = archived.values.as_bytes();
+        let offset = values.as_ptr() as usize - bytes.as_ptr() as usize;
+        let data =
+            archived.values.get().as_ptr() as usize - bytes.as_ptr() as usize;
+        assert!(data >= offset && data < offset + values.len());
+
+        // Flip the least significant bit of the first value
+        let lsb = if cfg!(feature = "big_endian") {
+            data + core::mem::size_of::<crate::Archived<u64>>() - 1
+        } else {
+            data
+        };
+        bytes[lsb] ^= 1;
+        let archived = crate::access::<ArchivedTest, Failure>(&bytes).unwrap();
+        assert_eq!(archived.values.get()[0], 1);
+        assert!(archived.values.verify::<Failure>().is_err());
+        assert!(archived.strings.verify::<Failure>().is_ok());
+    }
 
-        let c = BTreeMap::new();
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn with_checksummed_shared_pointers() {
+        use rancor::Failure;
 
-        let value = Test { a, b, c };
+        use crate::alloc::{rc::Rc, vec};
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Test {
+            outside: Rc<String>,
+            #[rkyv(with = Checksummed)]
+            inside: Vec<Rc<String>>,
+        }
+
+        let shared = Rc::new("shared".repeat(10));
+        let value = Test {
+            outside: shared.clone(),
+            inside: vec![shared.clone(), shared],
+        };
+
+        let bytes = crate::to_bytes::<Failure>(&value).unwrap();
+        let archived = crate::access::<ArchivedTest, Failure>(&bytes).unwrap();
+        let inside = archived.inside.get_verified::<Failure>().unwrap();
+        let subtree = archived.inside.as_bytes().as_ptr_range();
+
+        // Shared pointers are still shared within the subtree, but the value
+        // which was serialized before it is serialized again inside of it.
+        assert!(core::ptr::eq(&*inside[0], &*inside[1]));
+        assert!(subtree.contains(&inside[0].as_ptr()));
+        assert!(!subtree.contains(&archived.outside.as_ptr()));
+        assert_eq!(inside[0].as_str(), archived.outside.as_str());
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn with_checksummed_rejects_pointers_outside_subtree() {
+        use rancor::Failure;
+
+        use crate::{
+            checksum::Crc32c,
+            primitive::{ArchivedU32, ArchivedUsize, FixedUsize},
+            Archived, RawRelPtr,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Test {
+            #[rkyv(with = Checksummed)]
+            values: Vec<u64>,
+        }
+
+        let value = Test {
+            values: (0..10).collect(),
+        };
+
+        let mut bytes = crate::to_bytes::<Failure>(&value).unwrap();
+        let archived = crate::access::<ArchivedTest, Failure>(&bytes).unwrap();
+        let base = bytes.as_ptr() as usize;
+        let field = &archived.values as *const _ as usize - base;
+        let root = archived.values.get() as *const _ as usize - base;
+        let root_len = size_of::<Archived<Vec<u64>>>();
+
+        // Shrink the subtree to just the archived vec so that its elements are
+        // outside of it, and update the checksum to match.
+        let len = ArchivedUsize::from_native(root_len as FixedUsize);
+        let checksum = ArchivedU32::from_native(Crc32c::checksum(
+            &bytes[root..root + root_len],
+        ));
+        let len_pos = field + size_of::<RawRelPtr>();
+        let checksum_pos = len_pos + size_of::<ArchivedUsize>();
+        unsafe {
+            let ptr = bytes.as_mut_ptr();
+            ptr.add(len_pos)
+                .cast::<ArchivedUsize>()
+                .write_unaligned(len);
+            ptr.add(checksum_pos)
+                .cast::<ArchivedU32>()
+                .write_unaligned(checksum);
+        }
+
+        let archived =
+            unsafe { crate::access_unchecked::<ArchivedTest>(&bytes) };
+        assert!(archived.values.verify::<Failure>().is_ok());
+        assert!(crate::access::<ArchivedTest, Failure>(&bytes).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn with_checksummed_canonical() {
+        use std::collections::HashMap;
+
+        use crate::api::test::assert_canonical;
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Test {
+            #[rkyv(with = Checksummed)]
+            map: HashMap<String, u32>,
+        }
+
+        let a = Test {
+            map: (0..100).map(|i| (i.to_string(), i)).collect(),
+        };
+        let b = Test {
+            map: (0..100).rev().map(|i| (i.to_string(), i)).collect(),
+        };
+
+        assert_canonical(&a, &b);
+    }
+
+    #[cfg(all(feature = "bytecheck", feature = "lz4_flex-0_11"))]
+    #[test]
+    fn with_compressed() {
+        use rancor::Panic;
+
+        use crate::{
+            compression::Lz4, util::AlignedVec, with::Compressed, Archived,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = Compressed<Lz4>)]
+            bytes: Vec<u8>,
+            #[rkyv(with = Compressed<Lz4>)]
+            text: String,
+            #[rkyv(with = Compressed<Lz4>)]
+            strings: Vec<String>,
+        }
+
+        let value = Test {
+            bytes: [1, 2, 3, 4].repeat(256),
+            text: "hello world ".repeat(100),
+            strings: (0..100).map(|i| i.to_string()).collect(),
+        };
+
+        roundtrip_with(&value, |value, archived| {
+            assert!(
+                archived.bytes.compressed_bytes().len() < value.bytes.len()
+            );
+
+            let mut buffer = AlignedVec::<16>::new();
+            let text: &Archived<String> = archived
+                .text
+                .decompress_and_access::<Panic, 16>(&mut buffer)
+                .unwrap();
+            assert_eq!(text, &value.text);
+
+            let strings: &Archived<Vec<String>> = archived
+                .strings
+                .decompress_and_access::<Panic, 16>(&mut buffer)
+                .unwrap();
+            assert_eq!(strings[42], "42");
+
+            let bytes = archived.bytes.decompress::<Panic>().unwrap();
+            assert_eq!(bytes.len(), archived.bytes.uncompressed_len());
+        });
+    }
+
+    #[test]
+    fn with_intern() {
+        use rancor::Failure;
+
+        use crate::alloc::vec;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = Map<Intern>)]
+            strings: Vec<String>,
+            #[rkyv(with = Map<Intern>)]
+            bytes: Vec<Vec<u8>>,
+            #[rkyv(with = Intern)]
+            boxed: Box<str>,
+            #[rkyv(with = Intern)]
+            short: String,
+            #[rkyv(with = Map<Intern>)]
+            empty: Vec<Vec<u8>>,
+        }
+
+        let long = "a string which is too long to be stored inline";
+        let value = Test {
+            strings: vec![long.to_string(); 8],
+            bytes: vec![vec![1, 2, 3, 4]; 4],
+            boxed: long.into(),
+            short: "short".to_string(),
+            empty: vec![Vec::new(); 2],
+        };
+
+        let bytes = crate::to_bytes::<Failure>(&value).unwrap();
+        #[cfg(feature = "bytecheck")]
+        let archived = crate::access::<ArchivedTest, Failure>(&bytes).unwrap();
+        #[cfg(not(feature = "bytecheck"))]
+        let archived =
+            unsafe { crate::access_unchecked::<ArchivedTest>(&bytes) };
+
+        let ptr = archived.strings[0].as_ptr();
+        assert!(archived.strings.iter().all(|s| s.as_ptr() == ptr));
+        assert_eq!(archived.boxed.as_ptr(), ptr);
+        let ptr = archived.bytes[0].as_ptr();
+        assert!(archived.bytes.iter().all(|b| b.as_ptr() == ptr));
+
+        let deserialized = crate::deserialize::<Test, Failure>(archived);
+        assert_eq!(deserialized.unwrap(), value);
+
+        let duplicated = crate::to_bytes::<Failure>(&value.strings).unwrap();
+        assert!(bytes.len() < duplicated.len());
+
+        // Mutable access can't allow multiple pointers to the same bytes
+        #[cfg(feature = "bytecheck")]
+        {
+            let mut bytes = bytes;
+            assert!(
+                crate::access_mut::<ArchivedTest, Failure>(&mut bytes).is_err()
+            );
+        }
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn with_intern_mismatched_alias() {
+        use core::mem::size_of;
+
+        use rancor::Failure;
+
+        use crate::{alloc::vec, primitive::ArchivedUsize, vec::ArchivedVec};
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Test {
+            #[rkyv(with = Map<Intern>)]
+            bytes: Vec<Vec<u8>>,
+        }
+
+        let value = Test {
+            bytes: vec![vec![1, 2, 3, 4]; 2],
+        };
+        let mut bytes = crate::to_bytes::<Failure>(&value).unwrap();
+        let archived = crate::access::<ArchivedTest, Failure>(&bytes).unwrap();
+        assert_eq!(archived.bytes[0].as_ptr(), archived.bytes[1].as_ptr());
+
+        // Shorten the second vec so that it only aliases part of the first one
+        let len_pos = (&archived.bytes[1] as *const ArchivedVec<u8>)
+            .cast::<u8>() as usize
+            - bytes.as_ptr() as usize
+            + size_of::<ArchivedVec<u8>>()
+            - size_of::<ArchivedUsize>();
+        let len = ArchivedUsize::from_native(3);
+        let len_bytes = unsafe {
+            core::slice::from_raw_parts(
+                (&len as *const ArchivedUsize).cast::<u8>(),
+                size_of::<ArchivedUsize>(),
+            )
+        };
+        bytes[len_pos..len_pos + len_bytes.len()].copy_from_slice(len_bytes);
+        assert!(crate::access::<ArchivedTest, Failure>(&bytes).is_err());
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn with_intern_alias_checked_as_other_type() {
+        use rancor::Failure;
+
+        use crate::alloc::vec;
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Test {
+            #[rkyv(with = Intern)]
+            a: Vec<u8>,
+            #[rkyv(with = Intern)]
+            b: Vec<u8>,
+        }
+
+        // Has the same layout as `ArchivedTest`, but `b` must be valid UTF-8
+        #[allow(dead_code)]
+        #[derive(Archive)]
+        #[rkyv(crate)]
+        struct Other {
+            a: Vec<u8>,
+            b: Box<str>,
+        }
+
+        let value = Test {
+            a: vec![b'a'; 40],
+            b: vec![b'a'; 40],
+        };
+        let bytes = crate::to_bytes::<Failure>(&value).unwrap();
+        let other = crate::access::<ArchivedOther, Failure>(&bytes).unwrap();
+        assert_eq!(other.a.as_ptr(), other.b.as_ptr());
+        assert_eq!(&*other.b, "a".repeat(40));
+
+        let value = Test {
+            a: vec![0xff; 40],
+            b: vec![0xff; 40],
+        };
+        let bytes = crate::to_bytes::<Failure>(&value).unwrap();
+        assert!(crate::access::<ArchivedTest, Failure>(&bytes).is_ok());
+        assert!(crate::access::<ArchivedOther, Failure>(&bytes).is_err());
+    }
+
+    #[test]
+    fn with_offsets() {
+        use core::ptr;
+
+        use crate::{
+            alloc::rc::Rc, primitive::ArchivedI16, string::ArchivedString,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = WideOffset)]
+            wide_vec: Vec<u32>,
+            #[rkyv(with = WideOffset)]
+            wide_box: Box<[u8]>,
+            #[rkyv(with = NarrowOffset)]
+            narrow_string: String,
+            #[rkyv(with = NarrowOffset<i8>)]
+            narrow_vec: Vec<u8>,
+            #[rkyv(with = NarrowOffset<i8>)]
+            inline_string: String,
+            #[rkyv(with = WideOffset)]
+            wide_rc: Rc<u32>,
+            #[rkyv(with = NarrowOffset)]
+            narrow_rc: Rc<u32>,
+            rc: Rc<u32>,
+        }
+
+        let shared = Rc::new(42);
+        let value = Test {
+            wide_vec: (0..100).collect(),
+            wide_box: Box::new([1, 2, 3]),
+            narrow_string: "a string that doesn't fit inline".to_string(),
+            narrow_vec: Vec::from([4, 5, 6]),
+            inline_string: "inline".to_string(),
+            wide_rc: shared.clone(),
+            narrow_rc: shared.clone(),
+            rc: shared,
+        };
+
+        roundtrip_with(&value, |value, archived| {
+            assert_eq!(archived.wide_vec, value.wide_vec);
+            assert_eq!(archived.wide_box, value.wide_box);
+            let narrow_string: &ArchivedString<ArchivedI16> =
+                &archived.narrow_string;
+            assert_eq!(narrow_string, &value.narrow_string);
+            assert_eq!(archived.narrow_vec, value.narrow_vec);
+            assert_eq!(archived.inline_string, "inline");
+            assert_eq!(*archived.wide_rc, 42);
+            assert!(ptr::eq(archived.wide_rc.get(), archived.rc.get()));
+            assert!(ptr::eq(archived.narrow_rc.get(), archived.rc.get()));
+        });
 
         to_archived(&value, |archived| {
-            assert_eq!(archived.a.len(), 3);
-            assert!(archived
-                .a
-                .iter()
-                .find(|&e| e.key == "foo" && e.value == "hello")
-                .is_some());
-            assert!(archived
-                .a
-                .iter()
-                .find(|&e| e.key == "bar" && e.value == "world")
-                .is_some());
-            assert!(archived
+            let deserialized = deserialize::<Test>(&*archived);
+            assert!(Rc::ptr_eq(&deserialized.wide_rc, &deserialized.rc));
+            assert!(Rc::ptr_eq(&deserialized.narrow_rc, &deserialized.rc));
+        });
+    }
+
+    #[test]
+    fn with_narrow_offset_pointee_out_of_range() {
+        use rancor::Failure;
+
+        use crate::to_bytes;
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Test {
+            // Too long for the end of the vec to be in range of its start
+            #[rkyv(with = NarrowOffset<i8>)]
+            bytes: Vec<u8>,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct TestString {
+            #[rkyv(with = NarrowOffset<i8>)]
+            string: String,
+        }
+
+        let value = Test {
+            bytes: Vec::from([0; 200]),
+        };
+        assert!(to_bytes::<Failure>(&value).is_err());
+
+        let value = TestString {
+            string: "x".repeat(200),
+        };
+        assert!(to_bytes::<Failure>(&value).is_err());
+    }
+
+    #[test]
+    fn with_narrow_offset_pushed_out_of_range() {
+        use rancor::Failure;
+
+        use crate::to_bytes;
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Test {
+            #[rkyv(with = NarrowOffset<i8>)]
+            bytes: Vec<u8>,
+            // Serialized after `bytes`, pushing it out of range
+            more_bytes: Vec<u8>,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        enum TestEnum {
+            A(#[rkyv(with = NarrowOffset<i8>)] Vec<u8>, Vec<u8>),
+        }
+
+        let value = Test {
+            bytes: Vec::from([0; 8]),
+            more_bytes: Vec::from([0; 64]),
+        };
+        assert!(to_bytes::<Failure>(&value).is_ok());
+
+        let value = Test {
+            bytes: Vec::from([0; 8]),
+            more_bytes: Vec::from([0; 200]),
+        };
+        assert!(to_bytes::<Failure>(&value).is_err());
+
+        let value = TestEnum::A(Vec::from([0; 8]), Vec::from([0; 200]));
+        assert!(to_bytes::<Failure>(&value).is_err());
+    }
+
+    #[test]
+    fn with_narrow_offset_pushed_out_of_range_by_container() {
+        use rancor::Failure;
+
+        use crate::to_bytes;
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Test {
+            #[rkyv(with = NarrowOffset<i8>)]
+            bytes: Vec<u8>,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Outer {
+            inner: Test,
+            // Serialized after `inner.bytes`, but before `inner` is written
+            more_bytes: Vec<u8>,
+        }
+
+        let elements = |len: usize| {
+            (0..len)
+                .map(|i| Test {
+                    bytes: Vec::from([i as u8; 4]),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let bytes = to_bytes::<Failure>(&elements(4)).unwrap();
+        let archived = unsafe {
+            crate::access_unchecked::<crate::Archived<Vec<Test>>>(&bytes)
+        };
+        assert!(archived
+            .iter()
+            .enumerate()
+            .all(|(i, element)| element.bytes == [i as u8; 4]));
+
+        // Every element is written after the bytes of all of the elements, so
+        // the first elements can't reach their bytes.
+        assert!(to_bytes::<Failure>(&elements(300)).is_err());
+
+        let value = Outer {
+            inner: Test {
+                bytes: Vec::from([0; 8]),
+            },
+            more_bytes: Vec::from([0; 200]),
+        };
+        assert!(to_bytes::<Failure>(&value).is_err());
+        assert!(to_bytes::<Failure>(&Some(value)).is_err());
+    }
+
+    #[test]
+    fn with_as_bitmap() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = AsBitmap)]
+            a: BTreeSet<u32>,
+            #[rkyv(with = AsBitmap)]
+            b: BTreeSet<u32>,
+        }
+
+        // Sparse values become an array container, dense values a bitmap
+        // container, and long runs a run container.
+        let a = (0..100u32)
+            .map(|i| i * 7)
+            .chain((1 << 16..2 << 16).filter(|i| i % 3 != 0))
+            .chain((3 << 16) + 10..(3 << 16) + 50_000)
+            .collect::<BTreeSet<_>>();
+        let b = (0..1000u32)
+            .map(|i| i * 5)
+            .chain((1 << 16..2 << 16).filter(|i| i % 2 == 0))
+            .chain(5 << 16..(5 << 16) + 10)
+            .collect::<BTreeSet<_>>();
+
+        roundtrip_with(&Test { a, b }, |value, archived| {
+            let kinds = archived
                 .a
+                .containers()
                 .iter()
-                .find(|&e| e.key == "baz" && e.value == "bat")
-                .is_some());
+                .map(|c| c.kind())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                kinds,
+                [
+                    ContainerKind::Array,
+                    ContainerKind::Bitmap,
+                    ContainerKind::Run
+                ],
+            );
 
-            assert_eq!(archived.b.len(), 4);
-            assert!(archived.b.iter().find(|&e| e == "foo").is_some());
-            assert!(archived.b.iter().find(|&e| e == "hello world!").is_some());
-            assert!(archived.b.iter().find(|&e| e == "bar").is_some());
-            assert!(archived.b.iter().find(|&e| e == "fizzbuzz").is_some());
+            assert_eq!(archived.a.len(), value.a.len() as u64);
+            assert!(archived.a.iter().eq(value.a.iter().copied()));
+            for i in [0, 7, 8, 693, 1 << 16, (1 << 16) + 1, (3 << 16) + 10] {
+                assert_eq!(archived.a.contains(i), value.a.contains(&i));
+                assert_eq!(
+                    archived.a.rank(i),
+                    value.a.range(..=i).count() as u64,
+                );
+            }
+            assert!(!archived.a.contains(u32::MAX));
+
+            let intersection = archived
+                .a
+                .intersection(&archived.b)
+                .collect::<BTreeSet<_>>();
+            assert_eq!(
+                intersection,
+                value.a.intersection(&value.b).copied().collect(),
+            );
+            let union = archived.a.union(&archived.b).collect::<BTreeSet<_>>();
+            assert_eq!(union, value.a.union(&value.b).copied().collect());
         });
     }
 
@@ -1,7 +1,7 @@
 use core::{
     alloc::{Layout, LayoutError},
     marker::{PhantomData, PhantomPinned},
-    mem::{ManuallyDrop, MaybeUninit},
+    mem::{offset_of, size_of, ManuallyDrop, MaybeUninit},
     ptr::{self, addr_of_mut},
     str,
 };
@@ -14,8 +14,8 @@ use crate::{
     ser::{Allocator, Writer, WriterExt as _},
     traits::{ArchivePointee, CopyOptimization, LayoutRaw, NoUndef},
     tuple::*,
-    Archive, ArchiveUnsized, ArchivedMetadata, Deserialize, DeserializeUnsized,
-    Place, Portable, Serialize, SerializeUnsized,
+    Archive, ArchiveUnsized, ArchivedMetadata, Deserialize, DeserializeInPlace,
+    DeserializeUnsized, Place, Portable, Serialize, SerializeUnsized,
 };
 
 mod ffi;
@@ -74,7 +74,8 @@ where
 {
     fn serialize_unsized(&self, serializer: &mut S) -> Result<usize, S::Error> {
         let resolver = self.serialize(serializer)?;
-        serializer.align_for::<T::Archived>()?;
+        let pos = serializer.align_for::<T::Archived>()?;
+        T::check_resolver(&resolver, pos)?;
         unsafe { serializer.resolve_aligned(self, resolver) }
     }
 }
@@ -148,6 +149,19 @@ macro_rules! impl_tuple {
                     $(self.$index.serialize(serializer)?,)*
                 ))
             }
+
+            fn check_resolver(
+                resolver: &Self::Resolver,
+                pos: usize,
+            ) -> Result<(), S::Error> {
+                $(
+                    $type::check_resolver(
+                        &resolver.$index,
+                        pos + offset_of!(<Self as Archive>::Archived, $index),
+                    )?;
+                )*
+                Ok(())
+            }
         }
 
         impl<$($type,)* D> Deserialize<($($type,)*), D>
@@ -166,6 +180,28 @@ macro_rules! impl_tuple {
                 ))
             }
         }
+
+        impl<$($type,)* D> DeserializeInPlace<($($type,)*), D>
+            for $name<$($type::Archived,)*>
+        where
+            D: Fallible + ?Sized,
+            $($type: Archive,)*
+            $($type::Archived: DeserializeInPlace<$type, D>,)*
+        {
+            fn deserialize_into(
+                &self,
+                target: &mut ($($type,)*),
+                deserializer: &mut D,
+            ) -> Result<(), D::Error> {
+                $(
+                    self.$index.deserialize_into(
+                        &mut target.$index,
+                        deserializer,
+                    )?;
+                )*
+                Ok(())
+            }
+        }
     };
 }
 
@@ -236,6 +272,16 @@ where
         }
         unsafe { Ok(result.assume_init()) }
     }
+
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        for (i, resolver) in resolver.iter().enumerate() {
+            T::check_resolver(resolver, pos + i * size_of::<T::Archived>())?;
+        }
+        Ok(())
+    }
 }
 
 impl<T, D, const N: usize> Deserialize<[T; N], D> for [T::Archived; N]
@@ -256,6 +302,24 @@ where
     }
 }
 
+impl<T, D, const N: usize> DeserializeInPlace<[T; N], D> for [T::Archived; N]
+where
+    T: Archive,
+    T::Archived: DeserializeInPlace<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_into(
+        &self,
+        target: &mut [T; N],
+        deserializer: &mut D,
+    ) -> Result<(), D::Error> {
+        for (value, target) in self.iter().zip(target.iter_mut()) {
+            value.deserialize_into(target, deserializer)?;
+        }
+        Ok(())
+    }
+}
+
 // Slices
 
 // SAFETY: `[T]` is a `T` slice and so is portable as long as `T` is also
@@ -315,6 +379,7 @@ where
 
                     for (value, resolver) in self.iter().zip(resolvers.drain())
                     {
+                        T::check_resolver(&resolver, serializer.pos())?;
                         unsafe {
                             serializer.resolve_aligned(value, resolver)?;
                         }
@@ -452,6 +517,11 @@ impl<T: ?Sized, D: Fallible + ?Sized> Deserialize<PhantomData<T>, D>
     }
 }
 
+impl<T: ?Sized, D: Fallible + ?Sized> DeserializeInPlace<PhantomData<T>, D>
+    for PhantomData<T>
+{
+}
+
 // PhantomPinned
 
 // SAFETY: `PhantomPinned` always a size of 0 and align of 1, and so has a
@@ -481,6 +551,11 @@ impl<D: Fallible + ?Sized> Deserialize<PhantomPinned, D> for PhantomPinned {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<PhantomPinned, D>
+    for PhantomPinned
+{
+}
+
 // `ManuallyDrop`
 
 // SAFETY: `ManuallyDrop<T>` is guaranteed to have the same layout and bit
@@ -508,6 +583,13 @@ impl<T: Serialize<S>, S: Fallible + ?Sized> Serialize<S> for ManuallyDrop<T> {
     ) -> Result<Self::Resolver, S::Error> {
         T::serialize(self, serializer)
     }
+
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        T::check_resolver(resolver, pos)
+    }
 }
 
 impl<T, D> Deserialize<ManuallyDrop<T>, D> for ManuallyDrop<T::Archived>
@@ -524,6 +606,14 @@ where
     }
 }
 
+impl<T, D> DeserializeInPlace<ManuallyDrop<T>, D> for ManuallyDrop<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+}
+
 // `MaybeUninit`
 
 // SAFETY: `MaybeUninit` is guaranteed to have the same layout as `T`, and `T`
@@ -12,7 +12,7 @@ use crate::{
         ArchivedSocketAddrV4, ArchivedSocketAddrV6,
     },
     traits::NoUndef,
-    Archive, Deserialize, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 // Ipv4Addr
@@ -39,6 +39,11 @@ impl<D: Fallible + ?Sized> Deserialize<Ipv4Addr, D> for ArchivedIpv4Addr {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<Ipv4Addr, D>
+    for ArchivedIpv4Addr
+{
+}
+
 impl PartialEq<Ipv4Addr> for ArchivedIpv4Addr {
     #[inline]
     fn eq(&self, other: &Ipv4Addr) -> bool {
@@ -91,6 +96,11 @@ impl<D: Fallible + ?Sized> Deserialize<Ipv6Addr, D> for ArchivedIpv6Addr {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<Ipv6Addr, D>
+    for ArchivedIpv6Addr
+{
+}
+
 impl PartialEq<Ipv6Addr> for ArchivedIpv6Addr {
     #[inline]
     fn eq(&self, other: &Ipv6Addr) -> bool {
@@ -188,6 +198,8 @@ impl<D: Fallible + ?Sized> Deserialize<IpAddr, D> for ArchivedIpAddr {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<IpAddr, D> for ArchivedIpAddr {}
+
 impl PartialEq<IpAddr> for ArchivedIpAddr {
     #[inline]
     fn eq(&self, other: &IpAddr) -> bool {
@@ -262,6 +274,11 @@ where
     }
 }
 
+impl<D> DeserializeInPlace<SocketAddrV4, D> for ArchivedSocketAddrV4 where
+    D: Fallible + ?Sized
+{
+}
+
 impl PartialEq<SocketAddrV4> for ArchivedSocketAddrV4 {
     #[inline]
     fn eq(&self, other: &SocketAddrV4) -> bool {
@@ -328,6 +345,11 @@ impl<D: Fallible + ?Sized> Deserialize<SocketAddrV6, D>
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<SocketAddrV6, D>
+    for ArchivedSocketAddrV6
+{
+}
+
 impl PartialEq<SocketAddrV6> for ArchivedSocketAddrV6 {
     #[inline]
     fn eq(&self, other: &SocketAddrV6) -> bool {
@@ -437,6 +459,11 @@ impl<D: Fallible + ?Sized> Deserialize<SocketAddr, D> for ArchivedSocketAddr {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<SocketAddr, D>
+    for ArchivedSocketAddr
+{
+}
+
 impl PartialEq<SocketAddr> for ArchivedSocketAddr {
     #[inline]
     fn eq(&self, other: &SocketAddr) -> bool {
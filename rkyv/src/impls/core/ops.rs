@@ -15,7 +15,7 @@ use crate::{
         ArchivedRangeInclusive, ArchivedRangeTo, ArchivedRangeToInclusive,
     },
     traits::{CopyOptimization, NoUndef},
-    Archive, Deserialize, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 // RangeFull
@@ -43,6 +43,11 @@ impl<D: Fallible + ?Sized> Deserialize<RangeFull, D> for ArchivedRangeFull {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<RangeFull, D>
+    for ArchivedRangeFull
+{
+}
+
 impl PartialEq<RangeFull> for ArchivedRangeFull {
     fn eq(&self, _: &RangeFull) -> bool {
         true
@@ -88,6 +93,14 @@ where
     }
 }
 
+impl<T, D> DeserializeInPlace<Range<T>, D> for ArchivedRange<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+}
+
 impl<T, U: PartialEq<T>> PartialEq<Range<T>> for ArchivedRange<U> {
     fn eq(&self, other: &Range<T>) -> bool {
         self.start.eq(&other.start) && self.end.eq(&other.end)
@@ -137,6 +150,15 @@ where
     }
 }
 
+impl<T, D> DeserializeInPlace<RangeInclusive<T>, D>
+    for ArchivedRangeInclusive<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+}
+
 impl<T, U> PartialEq<RangeInclusive<T>> for ArchivedRangeInclusive<U>
 where
     U: PartialEq<T>,
@@ -185,6 +207,15 @@ where
     }
 }
 
+impl<T, D> DeserializeInPlace<RangeFrom<T>, D>
+    for ArchivedRangeFrom<T::Archived>
+where
+    T: Archive,
+    D: Fallible + ?Sized,
+    T::Archived: Deserialize<T, D>,
+{
+}
+
 impl<T, U: PartialEq<T>> PartialEq<RangeFrom<T>> for ArchivedRangeFrom<U> {
     fn eq(&self, other: &RangeFrom<T>) -> bool {
         self.start.eq(&other.start)
@@ -230,6 +261,14 @@ where
     }
 }
 
+impl<T, D> DeserializeInPlace<RangeTo<T>, D> for ArchivedRangeTo<T::Archived>
+where
+    T: Archive,
+    D: Fallible + ?Sized,
+    T::Archived: Deserialize<T, D>,
+{
+}
+
 impl<T, U: PartialEq<T>> PartialEq<RangeTo<T>> for ArchivedRangeTo<U> {
     fn eq(&self, other: &RangeTo<T>) -> bool {
         self.end.eq(&other.end)
@@ -280,6 +319,15 @@ where
     }
 }
 
+impl<T, D> DeserializeInPlace<RangeToInclusive<T>, D>
+    for ArchivedRangeToInclusive<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+}
+
 impl<T, U> PartialEq<RangeToInclusive<T>> for ArchivedRangeToInclusive<U>
 where
     U: PartialEq<T>,
@@ -402,6 +450,14 @@ where
     }
 }
 
+impl<T, D> DeserializeInPlace<Bound<T>, D> for ArchivedBound<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+}
+
 impl<T, U> PartialEq<Bound<T>> for ArchivedBound<U>
 where
     U: PartialEq<T>,
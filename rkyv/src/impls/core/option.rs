@@ -1,11 +1,11 @@
-use core::hint::unreachable_unchecked;
+use core::{hint::unreachable_unchecked, mem::offset_of};
 
 use munge::munge;
 use rancor::Fallible;
 
 use crate::{
-    option::ArchivedOption, traits::NoUndef, Archive, Deserialize, Place,
-    Serialize,
+    option::ArchivedOption, traits::NoUndef, Archive, Deserialize,
+    DeserializeInPlace, Place, Serialize,
 };
 
 #[allow(dead_code)]
@@ -69,6 +69,19 @@ impl<T: Serialize<S>, S: Fallible + ?Sized> Serialize<S> for Option<T> {
             .map(|value| value.serialize(serializer))
             .transpose()
     }
+
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        match resolver {
+            None => Ok(()),
+            Some(resolver) => T::check_resolver(
+                resolver,
+                pos + offset_of!(ArchivedOptionVariantSome<T::Archived>, 1),
+            ),
+        }
+    }
 }
 
 impl<T, D> Deserialize<Option<T>, D> for ArchivedOption<T::Archived>
@@ -87,6 +100,30 @@ where
     }
 }
 
+impl<T, D> DeserializeInPlace<Option<T>, D> for ArchivedOption<T::Archived>
+where
+    T: Archive,
+    T::Archived: DeserializeInPlace<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_into(
+        &self,
+        target: &mut Option<T>,
+        deserializer: &mut D,
+    ) -> Result<(), D::Error> {
+        match (self, target) {
+            (ArchivedOption::Some(value), Some(target)) => {
+                value.deserialize_into(target, deserializer)?;
+            }
+            (ArchivedOption::Some(value), target) => {
+                *target = Some(value.deserialize(deserializer)?);
+            }
+            (ArchivedOption::None, target) => *target = None,
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::api::test::roundtrip;
@@ -15,7 +15,7 @@ use crate::{
         ArchivedU128, ArchivedU16, ArchivedU32, ArchivedU64, ArchivedUsize,
     },
     traits::{CopyOptimization, NoUndef},
-    Archive, Deserialize, Place, Portable, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Place, Portable, Serialize,
 };
 
 macro_rules! unsafe_impl_primitive {
@@ -144,6 +144,8 @@ macro_rules! impl_archive_self_primitive {
                 Ok(*self)
             }
         }
+
+        impl<D: Fallible + ?Sized> DeserializeInPlace<$type, D> for $type {}
     };
 }
 
@@ -200,6 +202,8 @@ macro_rules! impl_multibyte_primitive {
                 Ok(self.to_native())
             }
         }
+
+        impl<D: Fallible + ?Sized> DeserializeInPlace<$type, D> for $archived {}
     };
 }
 
@@ -283,6 +287,8 @@ impl<D: Fallible + ?Sized> Deserialize<usize, D> for ArchivedUsize {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<usize, D> for ArchivedUsize {}
+
 // isize
 
 impl Archive for isize {
@@ -314,6 +320,8 @@ impl<D: Fallible + ?Sized> Deserialize<isize, D> for ArchivedIsize {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<isize, D> for ArchivedIsize {}
+
 // NonZeroUsize
 
 impl Archive for NonZeroUsize {
@@ -350,6 +358,11 @@ where
     }
 }
 
+impl<D> DeserializeInPlace<NonZeroUsize, D> for ArchivedNonZeroUsize where
+    D: Fallible + ?Sized
+{
+}
+
 // NonZeroIsize
 
 impl Archive for NonZeroIsize {
@@ -386,6 +399,11 @@ where
     }
 }
 
+impl<D> DeserializeInPlace<NonZeroIsize, D> for ArchivedNonZeroIsize where
+    D: Fallible + ?Sized
+{
+}
+
 #[cfg(test)]
 mod tests {
     use core::num::{
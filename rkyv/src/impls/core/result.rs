@@ -1,11 +1,11 @@
-use core::hint::unreachable_unchecked;
+use core::{hint::unreachable_unchecked, mem::offset_of};
 
 use munge::munge;
 use rancor::Fallible;
 
 use crate::{
-    result::ArchivedResult, traits::NoUndef, Archive, Deserialize, Place,
-    Serialize,
+    result::ArchivedResult, traits::NoUndef, Archive, Deserialize,
+    DeserializeInPlace, Place, Serialize,
 };
 
 #[allow(dead_code)]
@@ -75,6 +75,22 @@ where
             Err(value) => Err(value.serialize(serializer)?),
         })
     }
+
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        match resolver {
+            Ok(resolver) => T::check_resolver(
+                resolver,
+                pos + offset_of!(ArchivedResultVariantOk<T::Archived>, 1),
+            ),
+            Err(resolver) => U::check_resolver(
+                resolver,
+                pos + offset_of!(ArchivedResultVariantErr<U::Archived>, 1),
+            ),
+        }
+    }
 }
 
 impl<T, U, D> Deserialize<Result<T, U>, D>
@@ -99,6 +115,35 @@ where
     }
 }
 
+impl<T, U, D> DeserializeInPlace<Result<T, U>, D>
+    for ArchivedResult<T::Archived, U::Archived>
+where
+    T: Archive,
+    U: Archive,
+    D: Fallible + ?Sized,
+    T::Archived: DeserializeInPlace<T, D>,
+    U::Archived: DeserializeInPlace<U, D>,
+{
+    fn deserialize_into(
+        &self,
+        target: &mut Result<T, U>,
+        deserializer: &mut D,
+    ) -> Result<(), D::Error> {
+        match (self, target) {
+            (ArchivedResult::Ok(value), Ok(target)) => {
+                value.deserialize_into(target, deserializer)?;
+            }
+            (ArchivedResult::Err(err), Err(target)) => {
+                err.deserialize_into(target, deserializer)?;
+            }
+            (archived, target) => {
+                *target = archived.deserialize(deserializer)?
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::api::test::roundtrip;
@@ -108,4 +153,28 @@ mod tests {
         roundtrip(&Result::<i32, u32>::Ok(12345i32));
         roundtrip(&Result::<i32, u32>::Err(12345u32));
     }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn deserialize_result_in_place() {
+        use crate::{
+            alloc::{string::ToString, vec, vec::Vec},
+            api::test::{deserialize_into, to_archived},
+        };
+
+        let value = Ok::<_, Vec<u32>>(vec!["a".to_string(), "b".to_string()]);
+        to_archived(&value, |archived| {
+            // Targets with the same variant keep their contents
+            let mut target = Ok(Vec::with_capacity(16));
+            let ptr = target.as_ref().unwrap().as_ptr();
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+            assert_eq!(target.as_ref().unwrap().as_ptr(), ptr);
+
+            // Targets with a different variant are replaced
+            let mut target = Err(vec![1, 2, 3]);
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+        });
+    }
 }
@@ -2,7 +2,10 @@ use core::time::Duration;
 
 use rancor::Fallible;
 
-use crate::{time::ArchivedDuration, Archive, Deserialize, Place, Serialize};
+use crate::{
+    time::ArchivedDuration, Archive, Deserialize, DeserializeInPlace, Place,
+    Serialize,
+};
 
 impl Archive for Duration {
     type Archived = ArchivedDuration;
@@ -32,6 +35,11 @@ impl<D: Fallible + ?Sized> Deserialize<Duration, D> for ArchivedDuration {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<Duration, D>
+    for ArchivedDuration
+{
+}
+
 impl PartialEq<Duration> for ArchivedDuration {
     #[inline]
     fn eq(&self, other: &Duration) -> bool {
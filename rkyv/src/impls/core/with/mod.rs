@@ -12,6 +12,7 @@ use core::{
     hash::{Hash, Hasher},
     hint::unreachable_unchecked,
     marker::PhantomData,
+    mem::offset_of,
     num::{
         NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8,
         NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64,
@@ -43,7 +44,8 @@ use crate::{
         ArchiveWith, AsBox, DeserializeWith, Identity, Inline, InlineAsBox,
         Map, MapNiche, Niche, NicheInto, SerializeWith, Skip, Unsafe,
     },
-    Archive, ArchiveUnsized, Deserialize, Place, Serialize, SerializeUnsized,
+    Archive, ArchiveUnsized, Deserialize, DeserializeInPlace, Place, Serialize,
+    SerializeUnsized,
 };
 
 // Wrapper for O so that we have an Archive and Serialize implementation
@@ -68,6 +70,13 @@ where
     fn serialize(&self, s: &mut S) -> Result<Self::Resolver, S::Error> {
         A::serialize_with(self.0, s)
     }
+
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        A::check_resolver(resolver, pos)
+    }
 }
 
 impl<A, O: Hash> Hash for RefWrapper<'_, A, O> {
@@ -214,6 +223,24 @@ where
             .map(|value| A::serialize_with(value, s))
             .transpose()
     }
+
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        match resolver {
+            None => Ok(()),
+            Some(resolver) => A::check_resolver(
+                resolver,
+                pos + offset_of!(
+                    ArchivedOptionVariantSome<
+                        <A as ArchiveWith<O>>::Archived,
+                    >,
+                    1
+                ),
+            ),
+        }
+    }
 }
 
 impl<A, O, D>
@@ -459,6 +486,14 @@ where
     }
 }
 
+impl<T, N, D> DeserializeInPlace<Option<T>, D> for NichedOption<T::Archived, N>
+where
+    T: Archive<Archived: Deserialize<T, D>>,
+    N: Niching<T::Archived> + ?Sized,
+    D: Fallible + ?Sized,
+{
+}
+
 // MapNiche
 
 impl<T, W, N> ArchiveWith<Option<T>> for MapNiche<W, N>
@@ -594,6 +629,13 @@ impl<F: Serialize<S>, S: Fallible + ?Sized> SerializeWith<&F, S> for Inline {
     ) -> Result<Self::Resolver, S::Error> {
         field.serialize(serializer)
     }
+
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        F::check_resolver(resolver, pos)
+    }
 }
 
 // Unsafe
@@ -623,6 +665,13 @@ where
     ) -> Result<Self::Resolver, S::Error> {
         unsafe { (*field.get()).serialize(serializer) }
     }
+
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        F::check_resolver(resolver, pos)
+    }
 }
 
 impl<F, D> DeserializeWith<F::Archived, UnsafeCell<F>, D> for Unsafe
@@ -664,6 +713,13 @@ where
     ) -> Result<Self::Resolver, S::Error> {
         unsafe { (*field.as_ptr()).serialize(serializer) }
     }
+
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        F::check_resolver(resolver, pos)
+    }
 }
 
 impl<F, D> DeserializeWith<F::Archived, Cell<F>, D> for Unsafe
@@ -723,6 +779,13 @@ impl<F: Serialize<S>, S: Fallible + ?Sized> SerializeWith<F, S> for Identity {
     ) -> Result<Self::Resolver, S::Error> {
         field.serialize(serializer)
     }
+
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        F::check_resolver(resolver, pos)
+    }
 }
 
 impl<F, T, D> DeserializeWith<F, T, D> for Identity
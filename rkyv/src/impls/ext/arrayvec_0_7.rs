@@ -4,7 +4,7 @@ use rancor::Fallible;
 use crate::{
     ser::{Allocator, Writer},
     vec::{ArchivedVec, VecResolver},
-    Archive, Archived, Deserialize, Place, Serialize,
+    Archive, Archived, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl<T, const CAP: usize> Archive for ArrayVec<T, CAP>
@@ -51,6 +51,15 @@ where
     }
 }
 
+impl<T, D, const CAP: usize> DeserializeInPlace<ArrayVec<T, CAP>, D>
+    for ArchivedVec<Archived<T>>
+where
+    T: Archive,
+    Archived<T>: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+}
+
 impl<T, U, const CAP: usize> PartialEq<ArrayVec<U, CAP>> for ArchivedVec<T>
 where
     T: PartialEq<U>,
@@ -4,7 +4,7 @@ use rancor::Fallible;
 use crate::{
     ser::{Allocator, Writer},
     vec::{ArchivedVec, VecResolver},
-    Archive, Archived, Deserialize, Place, Serialize,
+    Archive, Archived, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl Archive for Bytes {
@@ -34,6 +34,11 @@ impl<D: Fallible + ?Sized> Deserialize<Bytes, D> for ArchivedVec<Archived<u8>> {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<Bytes, D>
+    for ArchivedVec<Archived<u8>>
+{
+}
+
 impl<T: Archive> PartialEq<Bytes> for ArchivedVec<T>
 where
     Bytes: PartialEq<[T]>,
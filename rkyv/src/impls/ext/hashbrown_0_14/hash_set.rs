@@ -9,7 +9,7 @@ use rancor::{Fallible, Source};
 use crate::{
     collections::swiss_table::set::{ArchivedHashSet, HashSetResolver},
     ser::{Allocator, Writer},
-    Archive, Deserialize, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl<K, S> Archive for HashSet<K, S>
@@ -68,6 +68,16 @@ where
     }
 }
 
+impl<K, D, S> DeserializeInPlace<HashSet<K, S>, D>
+    for ArchivedHashSet<K::Archived>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D> + Hash + Eq,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+}
+
 impl<K: Hash + Eq + Borrow<AK>, AK: Hash + Eq, S: BuildHasher>
     PartialEq<HashSet<K, S>> for ArchivedHashSet<AK>
 {
@@ -94,7 +104,9 @@ mod tests {
 
     use super::HashSet;
     use crate::{
-        alloc::string::String, api::test::roundtrip_with, hash::FxHasher64,
+        alloc::string::{String, ToString},
+        api::test::{assert_canonical, roundtrip_with},
+        hash::FxHasher64,
     };
 
     #[test]
@@ -114,4 +126,17 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn canonical_hash_set() {
+        let hasher = BuildHasherDefault::<FxHasher64>::default();
+        let mut a = HashSet::with_hasher(hasher.clone());
+        let mut b = HashSet::with_capacity_and_hasher(1000, hasher);
+        for i in 0..200 {
+            a.insert(i.to_string());
+            b.insert((199 - i).to_string());
+        }
+
+        assert_canonical(&a, &b);
+    }
 }
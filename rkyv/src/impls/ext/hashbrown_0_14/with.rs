@@ -3,15 +3,18 @@ use core::{
     marker::PhantomData,
 };
 
-use hashbrown_0_14::HashMap;
+use hashbrown_0_14::{HashMap, HashSet};
 use rancor::{Fallible, Source};
 
 use crate::{
-    collections::swiss_table::{ArchivedHashMap, HashMapResolver},
+    collections::swiss_table::{
+        ArchivedHashMap, ArchivedKeyedHashMap, ArchivedKeyedHashSet,
+        HashMapResolver, KeyedHashMapResolver, KeyedHashSetResolver,
+    },
     impls::core::with::RefWrapper,
     ser::{Allocator, Writer},
-    with::{ArchiveWith, DeserializeWith, MapKV, SerializeWith},
-    Place,
+    with::{ArchiveWith, DeserializeWith, Keyed, MapKV, SerializeWith},
+    Archive, Deserialize, Place,
 };
 
 impl<A, B, K, V, H> ArchiveWith<HashMap<K, V, H>> for MapKV<A, B>
@@ -98,6 +101,146 @@ where
     }
 }
 
+impl<K, V, H, R, S> ArchiveWith<HashMap<K, V, S>> for Keyed<H, R>
+where
+    K: Archive,
+    V: Archive,
+{
+    type Archived = ArchivedKeyedHashMap<K::Archived, V::Archived, H>;
+    type Resolver = KeyedHashMapResolver;
+
+    fn resolve_with(
+        field: &HashMap<K, V, S>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedKeyedHashMap::resolve_from_len(
+            field.len(),
+            (7, 8),
+            resolver,
+            out,
+        )
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V, H, R, S, RS> SerializeWith<HashMap<K, V, RS>, S> for Keyed<H, R>
+where
+    K: crate::Serialize<S> + Hash + Eq,
+    V: crate::Serialize<S>,
+    H: crate::hash::KeyedHasher,
+    R: crate::hash::SeedSource,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &HashMap<K, V, RS>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        <Self::Archived>::serialize_from_iter::<_, _, _, K, V, _>(
+            field.iter(),
+            R::seed(serializer.is_canonical()),
+            (7, 8),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, H, R, D, S>
+    DeserializeWith<
+        ArchivedKeyedHashMap<K::Archived, V::Archived, H>,
+        HashMap<K, V, S>,
+        D,
+    > for Keyed<H, R>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+    fn deserialize_with(
+        field: &ArchivedKeyedHashMap<K::Archived, V::Archived, H>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V, S>, D::Error> {
+        let mut result =
+            HashMap::with_capacity_and_hasher(field.len(), S::default());
+        for (k, v) in field.iter() {
+            result.insert(
+                k.deserialize(deserializer)?,
+                v.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+impl<K, H, R, S> ArchiveWith<HashSet<K, S>> for Keyed<H, R>
+where
+    K: Archive,
+{
+    type Archived = ArchivedKeyedHashSet<K::Archived, H>;
+    type Resolver = KeyedHashSetResolver;
+
+    fn resolve_with(
+        field: &HashSet<K, S>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedKeyedHashSet::resolve_from_len(
+            field.len(),
+            (7, 8),
+            resolver,
+            out,
+        )
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, H, R, S, RS> SerializeWith<HashSet<K, RS>, S> for Keyed<H, R>
+where
+    K: crate::Serialize<S> + Hash + Eq,
+    H: crate::hash::KeyedHasher,
+    R: crate::hash::SeedSource,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &HashSet<K, RS>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        <Self::Archived>::serialize_from_iter::<_, K, _>(
+            field.iter(),
+            R::seed(serializer.is_canonical()),
+            (7, 8),
+            serializer,
+        )
+    }
+}
+
+impl<K, H, R, D, S>
+    DeserializeWith<ArchivedKeyedHashSet<K::Archived, H>, HashSet<K, S>, D>
+    for Keyed<H, R>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+    fn deserialize_with(
+        field: &ArchivedKeyedHashSet<K::Archived, H>,
+        deserializer: &mut D,
+    ) -> Result<HashSet<K, S>, D::Error> {
+        let mut result =
+            HashSet::with_capacity_and_hasher(field.len(), S::default());
+        for k in field.iter() {
+            result.insert(k.deserialize(deserializer)?);
+        }
+        Ok(result)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use core::hash::BuildHasherDefault;
@@ -3,13 +3,13 @@ use core::{
     hash::{BuildHasher, Hash},
 };
 
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use rancor::{Fallible, Source};
 
 use crate::{
     collections::swiss_table::map::{ArchivedHashMap, HashMapResolver},
     ser::{Allocator, Writer},
-    Archive, Deserialize, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl<K, V: Archive, S> Archive for HashMap<K, V, S>
@@ -74,6 +74,50 @@ where
     }
 }
 
+impl<K, V, D, S> DeserializeInPlace<HashMap<K, V, S>, D>
+    for ArchivedHashMap<K::Archived, V::Archived>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D> + Hash + Eq,
+    V: Archive,
+    V::Archived: DeserializeInPlace<V, D>,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+    fn deserialize_into(
+        &self,
+        target: &mut HashMap<K, V, S>,
+        deserializer: &mut D,
+    ) -> Result<(), D::Error> {
+        // Deserialize into the values of keys which are already in the target
+        // and insert the rest, so the target keeps its storage and its
+        // existing keys and values.
+        for (k, v) in self.iter() {
+            let key = k.deserialize(deserializer)?;
+            match target.get_mut(&key) {
+                Some(value) => v.deserialize_into(value, deserializer)?,
+                None => {
+                    let value = v.deserialize(deserializer)?;
+                    target.insert(key, value);
+                }
+            }
+        }
+
+        // Every key in the archive is in the target now, so any extra entries
+        // are for keys which aren't in the archive.
+        if target.len() > self.len() {
+            let mut keys =
+                HashSet::with_capacity_and_hasher(self.len(), S::default());
+            for k in self.keys() {
+                keys.insert(k.deserialize(deserializer)?);
+            }
+            target.retain(|key, _| keys.contains(key));
+        }
+
+        Ok(())
+    }
+}
+
 impl<K, V, AK, AV, S> PartialEq<HashMap<K, V, S>> for ArchivedHashMap<AK, AV>
 where
     K: Hash + Eq + Borrow<AK>,
@@ -94,11 +138,19 @@ where
 
 #[cfg(test)]
 mod tests {
-    use core::hash::BuildHasherDefault;
+    use core::{hash::BuildHasherDefault, ptr};
 
     use super::HashMap;
     use crate::{
-        alloc::string::String, api::test::roundtrip_with, hash::FxHasher64,
+        alloc::{
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        api::test::{
+            assert_canonical, deserialize_into, roundtrip_with, to_archived,
+        },
+        hash::FxHasher64,
     };
 
     #[test]
@@ -119,4 +171,48 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn canonical_hash_map() {
+        let hasher = BuildHasherDefault::<FxHasher64>::default();
+        let mut a = HashMap::with_hasher(hasher.clone());
+        let mut b = HashMap::with_capacity_and_hasher(1000, hasher);
+        for i in 0..200 {
+            a.insert(i.to_string(), i);
+            b.insert((199 - i).to_string(), 199 - i);
+        }
+
+        assert_canonical(&a, &b);
+    }
+
+    #[test]
+    fn deserialize_hash_map_in_place() {
+        let hasher = BuildHasherDefault::<FxHasher64>::default();
+        let mut value = HashMap::with_hasher(hasher.clone());
+        value.insert(String::from("foo"), vec![1, 2, 3]);
+        value.insert(String::from("bar"), vec![4, 5]);
+
+        to_archived(&value, |archived| {
+            let mut target = HashMap::with_capacity_and_hasher(32, hasher);
+            target.insert(String::from("foo"), Vec::with_capacity(16));
+            target.insert(String::from("baz"), vec![6]);
+            let capacity = target.capacity();
+
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+            assert_eq!(target.capacity(), capacity);
+
+            // With the same keys, the entries stay where they are
+            let entry: *const Vec<i32> = &target["foo"];
+            let key = target.keys().find(|k| *k == "foo").unwrap().as_ptr();
+            for _ in 0..3 {
+                deserialize_into(&*archived, &mut target);
+                assert_eq!(target, value);
+                assert_eq!(target.capacity(), capacity);
+                assert!(ptr::eq(&target["foo"], entry));
+                let k = target.keys().find(|k| *k == "foo").unwrap();
+                assert_eq!(k.as_ptr(), key);
+            }
+        });
+    }
 }
@@ -6,7 +6,7 @@ use rancor::{Fallible, Source};
 use crate::{
     collections::swiss_table::{ArchivedIndexMap, IndexMapResolver},
     ser::{Allocator, Writer},
-    Archive, Deserialize, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl<K: Archive, V: Archive, S> Archive for IndexMap<K, V, S> {
@@ -66,6 +66,45 @@ where
     }
 }
 
+impl<K, V, D, S> DeserializeInPlace<IndexMap<K, V, S>, D>
+    for ArchivedIndexMap<K::Archived, V::Archived>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    V: Archive,
+    V::Archived: DeserializeInPlace<V, D>,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+    fn deserialize_into(
+        &self,
+        target: &mut IndexMap<K, V, S>,
+        deserializer: &mut D,
+    ) -> Result<(), D::Error> {
+        // Deserialize into the values of keys which are already in the target
+        // and insert the rest, then move each entry to its index in the
+        // archive. The first `self.len()` entries are then the entries of the
+        // archive in order, and the rest are for keys which aren't in it.
+        for (i, (k, v)) in self.iter().enumerate() {
+            let key = k.deserialize(deserializer)?;
+            let index = match target.get_full_mut(&key) {
+                Some((index, _, value)) => {
+                    v.deserialize_into(value, deserializer)?;
+                    index
+                }
+                None => {
+                    let value = v.deserialize(deserializer)?;
+                    target.insert_full(key, value).0
+                }
+            };
+            target.swap_indices(i, index);
+        }
+        target.truncate(self.len());
+
+        Ok(())
+    }
+}
+
 impl<UK, K, UV, V, S> PartialEq<IndexMap<UK, UV, S>> for ArchivedIndexMap<K, V>
 where
     K: PartialEq<UK>,
@@ -81,12 +120,20 @@ where
 
 #[cfg(test)]
 mod tests {
-    use core::hash::BuildHasherDefault;
+    use core::{hash::BuildHasherDefault, ptr};
 
     use indexmap_2::IndexMap;
 
     use crate::{
-        alloc::string::String, api::test::roundtrip_with, hash::FxHasher64,
+        alloc::{
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        api::test::{
+            assert_canonical, deserialize_into, roundtrip_with, to_archived,
+        },
+        hash::FxHasher64,
     };
 
     #[test]
@@ -107,4 +154,55 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn canonical_index_map() {
+        // Index maps keep their order, so equal maps have the same order but
+        // may differ in capacity and removal history.
+        let hasher = BuildHasherDefault::<FxHasher64>::default();
+        let mut a = IndexMap::with_hasher(hasher.clone());
+        let mut b = IndexMap::with_capacity_and_hasher(1000, hasher);
+        for i in 0..200 {
+            a.insert(i.to_string(), i);
+            b.insert(i.to_string(), i);
+        }
+        b.insert("extra".to_string(), 0);
+        b.shift_remove("extra");
+
+        assert_canonical(&a, &b);
+    }
+
+    #[test]
+    fn deserialize_index_map_in_place() {
+        let hasher = BuildHasherDefault::<FxHasher64>::default();
+        let mut value = IndexMap::with_hasher(hasher.clone());
+        value.insert(String::from("foo"), vec![1, 2, 3]);
+        value.insert(String::from("bar"), vec![4, 5]);
+        value.insert(String::from("bat"), vec![]);
+
+        to_archived(&value, |archived| {
+            let mut target = IndexMap::with_capacity_and_hasher(32, hasher);
+            target.insert(String::from("baz"), vec![6]);
+            target.insert(String::from("bar"), Vec::with_capacity(16));
+            target.insert(String::from("foo"), vec![7]);
+            let capacity = target.capacity();
+            let bar = target["bar"].as_ptr();
+
+            deserialize_into(&*archived, &mut target);
+            assert!(target.iter().eq(value.iter()));
+            assert_eq!(target.capacity(), capacity);
+            assert_eq!(target["bar"].as_ptr(), bar);
+
+            // With the same keys, the entries stay where they are
+            let entry: *const Vec<i32> = &target["foo"];
+            let key = target.get_index(0).unwrap().0.as_ptr();
+            for _ in 0..3 {
+                deserialize_into(&*archived, &mut target);
+                assert!(target.iter().eq(value.iter()));
+                assert_eq!(target.capacity(), capacity);
+                assert!(ptr::eq(&target["foo"], entry));
+                assert_eq!(target.get_index(0).unwrap().0.as_ptr(), key);
+            }
+        });
+    }
 }
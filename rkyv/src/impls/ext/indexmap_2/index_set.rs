@@ -6,7 +6,7 @@ use rancor::{Fallible, Source};
 use crate::{
     collections::swiss_table::{ArchivedIndexSet, IndexSetResolver},
     ser::{Allocator, Writer},
-    Archive, Deserialize, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl<K: Archive, S> Archive for IndexSet<K, S> {
@@ -56,6 +56,16 @@ where
     }
 }
 
+impl<K, D, S> DeserializeInPlace<IndexSet<K, S>, D>
+    for ArchivedIndexSet<K::Archived>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+}
+
 impl<UK, K: PartialEq<UK>, S: BuildHasher> PartialEq<IndexSet<UK, S>>
     for ArchivedIndexSet<K>
 {
@@ -71,7 +81,9 @@ mod tests {
     use indexmap_2::IndexSet;
 
     use crate::{
-        alloc::string::String, api::test::roundtrip_with, hash::FxHasher64,
+        alloc::string::{String, ToString},
+        api::test::{assert_canonical, roundtrip_with},
+        hash::FxHasher64,
     };
 
     #[test]
@@ -91,4 +103,21 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn canonical_index_set() {
+        // Index maps keep their order, so equal maps have the same order but
+        // may differ in capacity and removal history.
+        let hasher = BuildHasherDefault::<FxHasher64>::default();
+        let mut a = IndexSet::with_hasher(hasher.clone());
+        let mut b = IndexSet::with_capacity_and_hasher(1000, hasher);
+        for i in 0..200 {
+            a.insert(i.to_string());
+            b.insert(i.to_string());
+        }
+        b.insert("extra".to_string());
+        b.shift_remove("extra");
+
+        assert_canonical(&a, &b);
+    }
 }
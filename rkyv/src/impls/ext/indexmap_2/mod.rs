@@ -1,2 +1,3 @@
 mod index_map;
 mod index_set;
+mod with;
@@ -0,0 +1,121 @@
+use core::hash::{BuildHasher, Hash};
+
+use indexmap_2::IndexMap;
+use rancor::Fallible;
+
+use crate::{
+    collections::swiss_table::{ArchivedKeyedIndexMap, KeyedIndexMapResolver},
+    with::{ArchiveWith, DeserializeWith, Keyed},
+    Archive, Deserialize, Place,
+};
+
+impl<K, V, H, R, S> ArchiveWith<IndexMap<K, V, S>> for Keyed<H, R>
+where
+    K: Archive,
+    V: Archive,
+{
+    type Archived = ArchivedKeyedIndexMap<K::Archived, V::Archived, H>;
+    type Resolver = KeyedIndexMapResolver;
+
+    fn resolve_with(
+        field: &IndexMap<K, V, S>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedKeyedIndexMap::resolve_from_len(
+            field.len(),
+            (7, 8),
+            resolver,
+            out,
+        )
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V, H, R, S, RS> crate::with::SerializeWith<IndexMap<K, V, RS>, S>
+    for Keyed<H, R>
+where
+    K: crate::Serialize<S> + Hash + Eq,
+    V: crate::Serialize<S>,
+    H: crate::hash::KeyedHasher,
+    R: crate::hash::SeedSource,
+    S: Fallible + crate::ser::Allocator + crate::ser::Writer + ?Sized,
+    S::Error: rancor::Source,
+{
+    fn serialize_with(
+        field: &IndexMap<K, V, RS>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        <Self::Archived>::serialize_from_iter::<_, _, _, K, V, _>(
+            field.iter(),
+            R::seed(crate::ser::Writer::is_canonical(serializer)),
+            (7, 8),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, H, R, D, S>
+    DeserializeWith<
+        ArchivedKeyedIndexMap<K::Archived, V::Archived, H>,
+        IndexMap<K, V, S>,
+        D,
+    > for Keyed<H, R>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+    fn deserialize_with(
+        field: &ArchivedKeyedIndexMap<K::Archived, V::Archived, H>,
+        deserializer: &mut D,
+    ) -> Result<IndexMap<K, V, S>, D::Error> {
+        let mut result =
+            IndexMap::with_capacity_and_hasher(field.len(), S::default());
+        for (k, v) in field.iter() {
+            result.insert(
+                k.deserialize(deserializer)?,
+                v.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use indexmap_2::IndexMap;
+
+    use crate::{
+        api::test::roundtrip_with, with::Keyed, Archive, Deserialize, Serialize,
+    };
+
+    #[test]
+    fn roundtrip_index_map_keyed() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = Keyed)]
+            inner: IndexMap<String, u32>,
+        }
+
+        let mut inner = IndexMap::new();
+        inner.insert(String::from("foo"), 10);
+        inner.insert(String::from("bar"), 20);
+        inner.insert(String::from("baz"), 40);
+        inner.insert(String::from("bat"), 80);
+
+        roundtrip_with(&Test { inner }, |a, b| {
+            assert_eq!(a.inner.len(), b.inner.len());
+            for (i, (k, v)) in a.inner.iter().enumerate() {
+                let (index, ak, av) = b.inner.get_full(k.as_str()).unwrap();
+                assert_eq!(index, i);
+                assert_eq!(k, ak);
+                assert_eq!(v, av);
+            }
+        });
+    }
+}
@@ -4,7 +4,7 @@ use smallvec_1::{Array, SmallVec};
 use crate::{
     ser::{Allocator, Writer},
     vec::{ArchivedVec, VecResolver},
-    Archive, Archived, Deserialize, Place, Serialize,
+    Archive, Archived, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl<A: Array> Archive for SmallVec<A>
@@ -52,6 +52,15 @@ where
     }
 }
 
+impl<A, D> DeserializeInPlace<SmallVec<A>, D> for ArchivedVec<Archived<A::Item>>
+where
+    A: Array,
+    A::Item: Archive,
+    Archived<A::Item>: Deserialize<A::Item, D>,
+    D: Fallible + ?Sized,
+{
+}
+
 impl<A, U> PartialEq<SmallVec<A>> for ArchivedVec<U>
 where
     A: Array,
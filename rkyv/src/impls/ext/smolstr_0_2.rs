@@ -4,7 +4,7 @@ use smol_str_0_2::SmolStr;
 use crate::{
     ser::{Allocator, Writer},
     string::{ArchivedString, StringResolver},
-    Archive, Deserialize, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl Archive for SmolStr {
@@ -36,6 +36,8 @@ impl<D: Fallible + ?Sized> Deserialize<SmolStr, D> for ArchivedString {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<SmolStr, D> for ArchivedString {}
+
 impl PartialEq<SmolStr> for ArchivedString {
     fn eq(&self, other: &SmolStr) -> bool {
         other.as_str() == self.as_str()
@@ -4,7 +4,7 @@ use thin_vec_0_2::ThinVec;
 use crate::{
     ser::{Allocator, Writer},
     vec::{ArchivedVec, VecResolver},
-    Archive, Archived, Deserialize, Place, Serialize,
+    Archive, Archived, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl<T> Archive for ThinVec<T>
@@ -50,6 +50,14 @@ where
     }
 }
 
+impl<T, D: Fallible + ?Sized> DeserializeInPlace<ThinVec<T>, D>
+    for ArchivedVec<Archived<T>>
+where
+    T: Archive,
+    Archived<T>: Deserialize<T, D>,
+{
+}
+
 impl<T, U> PartialEq<ThinVec<U>> for ArchivedVec<T>
 where
     T: PartialEq<U>,
@@ -6,7 +6,7 @@ use tinyvec_1::{Array, ArrayVec, SliceVec};
 use crate::{
     ser::{Allocator, Writer},
     vec::{ArchivedVec, VecResolver},
-    Archive, Archived, Deserialize, Place, Serialize,
+    Archive, Archived, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 // ArrayVec
@@ -56,6 +56,15 @@ where
     }
 }
 
+impl<A, D> DeserializeInPlace<ArrayVec<A>, D> for ArchivedVec<Archived<A::Item>>
+where
+    A: Array,
+    A::Item: Archive,
+    Archived<A::Item>: Deserialize<A::Item, D>,
+    D: Fallible + ?Sized,
+{
+}
+
 // SliceVec
 
 impl<'s, T: Archive> Archive for SliceVec<'s, T> {
@@ -132,6 +141,15 @@ where
     }
 }
 
+impl<A, D> DeserializeInPlace<TinyVec<A>, D> for ArchivedVec<Archived<A::Item>>
+where
+    A: Array,
+    A::Item: Archive,
+    Archived<A::Item>: Deserialize<A::Item, D>,
+    D: Fallible + ?Sized,
+{
+}
+
 impl<T, A> PartialEq<ArrayVec<A>> for ArchivedVec<T>
 where
     A: Array,
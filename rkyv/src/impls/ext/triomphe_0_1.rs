@@ -11,8 +11,8 @@ use crate::{
     de::{Metadata, Pooling, PoolingExt, SharedPointer},
     rc::{ArchivedRc, Flavor, RcResolver},
     ser::{Sharing, Writer},
-    Archive, ArchiveUnsized, Deserialize, DeserializeUnsized, Place, Serialize,
-    SerializeUnsized,
+    Archive, ArchiveUnsized, Deserialize, DeserializeInPlace,
+    DeserializeUnsized, Place, Serialize, SerializeUnsized,
 };
 
 pub struct TriompheArcFlavor;
@@ -81,6 +81,18 @@ where
     }
 }
 
+impl<T, D> DeserializeInPlace<Arc<T>, D>
+    for ArchivedRc<T::Archived, TriompheArcFlavor>
+where
+    T: ArchiveUnsized + 'static,
+    T::Metadata: Into<Metadata>,
+    Metadata: Into<T::Metadata>,
+    T::Archived: DeserializeUnsized<T, D>,
+    D: Pooling + Fallible + ?Sized,
+    D::Error: Source,
+{
+}
+
 #[cfg(test)]
 mod tests {
     use triomphe_0_1::Arc;
@@ -2,7 +2,8 @@ use rancor::Fallible;
 use uuid_1::Uuid;
 
 use crate::{
-    traits::CopyOptimization, Archive, Deserialize, Place, Portable, Serialize,
+    traits::CopyOptimization, Archive, Deserialize, DeserializeInPlace, Place,
+    Portable, Serialize,
 };
 
 // SAFETY: `Uuid` has the same ABI has `Bytes`, and so is `Portable` when
@@ -37,6 +38,8 @@ impl<D: Fallible + ?Sized> Deserialize<Uuid, D> for Uuid {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeInPlace<Uuid, D> for Uuid {}
+
 #[cfg(test)]
 mod tests {
     use super::Uuid;
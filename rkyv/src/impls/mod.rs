@@ -708,9 +708,9 @@ mod alloc_tests {
             vec,
             vec::Vec,
         },
-        api::test::{roundtrip, to_archived},
+        api::test::{deserialize_into, roundtrip, to_archived},
         ser::Writer,
-        Archive, Deserialize, Serialize,
+        Archive, Deserialize, DeserializeInPlace, Serialize,
     };
 
     #[test]
@@ -804,4 +804,151 @@ mod alloc_tests {
             }),
         });
     }
+
+    #[test]
+    fn derive_deserialize_in_place() {
+        #[derive(
+            Archive,
+            Serialize,
+            Deserialize,
+            DeserializeInPlace,
+            Debug,
+            PartialEq,
+        )]
+        #[rkyv(crate)]
+        struct Record {
+            name: String,
+            values: Vec<u32>,
+            extra: Option<Box<u64>>,
+            pair: (u8, String),
+            #[rkyv(with = crate::with::AsBox)]
+            boxed: u32,
+        }
+
+        #[derive(
+            Archive,
+            Serialize,
+            Deserialize,
+            DeserializeInPlace,
+            Debug,
+            PartialEq,
+        )]
+        #[rkyv(crate)]
+        enum Message {
+            Empty,
+            Text(String),
+            Record { record: Record, count: u64 },
+        }
+
+        let record = Record {
+            name: "a name which is not inline".to_string(),
+            values: vec![1, 2, 3],
+            extra: Some(Box::new(8)),
+            pair: (4, "five".to_string()),
+            boxed: 6,
+        };
+        let value = Message::Record { record, count: 7 };
+
+        to_archived(&value, |archived| {
+            // Matching variants are deserialized in place
+            let mut target = Message::Record {
+                record: Record {
+                    name: String::with_capacity(64),
+                    values: Vec::with_capacity(64),
+                    extra: Some(Box::new(0)),
+                    pair: (0, String::new()),
+                    boxed: 0,
+                },
+                count: 0,
+            };
+            let Message::Record { ref record, .. } = target else {
+                unreachable!()
+            };
+            let name = record.name.as_ptr();
+            let values = record.values.as_ptr();
+            let extra: *const u64 = &**record.extra.as_ref().unwrap();
+
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+            let Message::Record { ref record, .. } = target else {
+                unreachable!()
+            };
+            assert_eq!(record.name.as_ptr(), name);
+            assert_eq!(record.values.as_ptr(), values);
+            assert!(core::ptr::eq(&**record.extra.as_ref().unwrap(), extra));
+
+            // Other variants are replaced
+            let mut target = Message::Text("text".to_string());
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+        });
+
+        to_archived(&Message::Empty, |archived| {
+            let mut target = Message::Text("text".to_string());
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, Message::Empty);
+        });
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn derive_deserialize_in_place_default_impls() {
+        use core::time::Duration;
+        use std::{
+            collections::{BTreeSet, HashSet, VecDeque},
+            rc::Rc,
+            sync::Arc,
+        };
+
+        // Fields which don't reuse their storage still implement
+        // `DeserializeInPlace` by replacing the target.
+        #[derive(
+            Archive,
+            Serialize,
+            Deserialize,
+            DeserializeInPlace,
+            Debug,
+            PartialEq,
+        )]
+        #[rkyv(crate)]
+        struct Record {
+            timeout: Duration,
+            result: Result<u32, String>,
+            tags: HashSet<String>,
+            ordered: BTreeSet<u32>,
+            queue: VecDeque<u32>,
+            shared: Rc<String>,
+            atomic: Arc<[u8]>,
+            text: Box<str>,
+            range: core::ops::Range<u32>,
+        }
+
+        let value = Record {
+            timeout: Duration::from_millis(1500),
+            result: Err("an error".to_string()),
+            tags: ["a", "b"].iter().map(|s| s.to_string()).collect(),
+            ordered: [3, 1, 2].into_iter().collect(),
+            queue: [4, 5, 6].into_iter().collect(),
+            shared: Rc::new("shared".to_string()),
+            atomic: Arc::from(&[7u8, 8, 9][..]),
+            text: "text".into(),
+            range: 1..10,
+        };
+
+        to_archived(&value, |archived| {
+            let mut target = Record {
+                timeout: Duration::ZERO,
+                result: Ok(0),
+                tags: HashSet::new(),
+                ordered: BTreeSet::new(),
+                queue: VecDeque::new(),
+                shared: Rc::new(String::new()),
+                atomic: Arc::from(&[][..]),
+                text: "".into(),
+                range: 0..0,
+            };
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+        });
+    }
 }
@@ -1,7 +1,8 @@
 use rancor::Fallible;
 
 use crate::{
-    rend::*, traits::CopyOptimization, Archive, Deserialize, Place, Serialize,
+    rend::*, traits::CopyOptimization, Archive, Deserialize,
+    DeserializeInPlace, Place, Serialize,
 };
 
 macro_rules! impl_rend_primitive {
@@ -30,6 +31,8 @@ macro_rules! impl_rend_primitive {
                 Ok(*self)
             }
         }
+
+        impl<D: Fallible + ?Sized> DeserializeInPlace<$type, D> for $type {}
     };
 }
 
@@ -2,14 +2,14 @@ use core::{
     borrow::Borrow,
     hash::{BuildHasher, Hash},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use rancor::{Fallible, Source};
 
 use crate::{
     collections::swiss_table::map::{ArchivedHashMap, HashMapResolver},
     ser::{Allocator, Writer},
-    Archive, Deserialize, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl<K, V: Archive, S> Archive for HashMap<K, V, S>
@@ -74,6 +74,50 @@ where
     }
 }
 
+impl<K, V, D, S> DeserializeInPlace<HashMap<K, V, S>, D>
+    for ArchivedHashMap<K::Archived, V::Archived>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D> + Hash + Eq,
+    V: Archive,
+    V::Archived: DeserializeInPlace<V, D>,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+    fn deserialize_into(
+        &self,
+        target: &mut HashMap<K, V, S>,
+        deserializer: &mut D,
+    ) -> Result<(), D::Error> {
+        // Deserialize into the values of keys which are already in the target
+        // and insert the rest, so the target keeps its storage and its
+        // existing keys and values.
+        for (k, v) in self.iter() {
+            let key = k.deserialize(deserializer)?;
+            match target.get_mut(&key) {
+                Some(value) => v.deserialize_into(value, deserializer)?,
+                None => {
+                    let value = v.deserialize(deserializer)?;
+                    target.insert(key, value);
+                }
+            }
+        }
+
+        // Every key in the archive is in the target now, so any extra entries
+        // are for keys which aren't in the archive.
+        if target.len() > self.len() {
+            let mut keys =
+                HashSet::with_capacity_and_hasher(self.len(), S::default());
+            for k in self.keys() {
+                keys.insert(k.deserialize(deserializer)?);
+            }
+            target.retain(|key, _| keys.contains(key));
+        }
+
+        Ok(())
+    }
+}
+
 impl<K, V, AK, AV, S> PartialEq<HashMap<K, V, S>> for ArchivedHashMap<AK, AV>
 where
     K: Hash + Eq + Borrow<AK>,
@@ -104,13 +148,16 @@ where
 
 #[cfg(test)]
 mod tests {
-    use core::{fmt::Debug, hash::BuildHasher};
+    use core::{fmt::Debug, hash::BuildHasher, ptr};
     use std::collections::HashMap;
 
     use ahash::RandomState;
 
     use crate::{
-        api::test::{roundtrip, roundtrip_with, to_archived},
+        api::test::{
+            assert_canonical, deserialize_into, roundtrip, roundtrip_with,
+            to_archived,
+        },
         collections::swiss_table::ArchivedHashMap,
         string::ArchivedString,
         Archive, Archived, Deserialize, Serialize,
@@ -298,4 +345,115 @@ mod tests {
         // This .unwrap() fails!
         let _decoded = access::<Archived<MyHashMap>, Panic>(&encoded).unwrap();
     }
+
+    #[test]
+    fn canonical_hash_map() {
+        let a = (0..200)
+            .map(|i| (i.to_string(), vec![i; i % 4]))
+            .collect::<HashMap<_, _>>();
+        let b = (0..200)
+            .rev()
+            .map(|i| (i.to_string(), vec![i; i % 4]))
+            .collect::<HashMap<_, _, RandomState>>()
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        assert_canonical(&a, &b);
+    }
+
+    #[test]
+    fn canonical_hash_map_colliding_hashes() {
+        use rancor::{Fallible, Source};
+
+        use crate::{
+            collections::swiss_table::HashMapResolver,
+            primitive::ArchivedU32,
+            ser::{Allocator, Writer},
+            Place,
+        };
+
+        type Map = ArchivedHashMap<ArchivedString, ArchivedU32>;
+
+        // Hashes every key to the same value.
+        struct Colliding(Vec<(String, u32)>);
+
+        impl Archive for Colliding {
+            type Archived = Map;
+            type Resolver = HashMapResolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                ArchivedHashMap::resolve_from_len(
+                    self.0.len(),
+                    (7, 8),
+                    resolver,
+                    out,
+                );
+            }
+        }
+
+        impl<S> Serialize<S> for Colliding
+        where
+            S: Fallible + Allocator + Writer + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                Map::serialize_from_iter_hashed::<_, _, _, String, u32, _, _>(
+                    self.0.iter().map(|(k, v)| (k, v)),
+                    |_| 0,
+                    (7, 8),
+                    serializer,
+                )
+            }
+        }
+
+        // Long keys which only differ after a shared prefix
+        let prefix = "x".repeat(100);
+        let entries = (0..50)
+            .map(|i| (i.to_string(), i))
+            .chain((0..50).map(|i| (format!("{prefix}{i}"), 50 + i)))
+            .collect::<Vec<_>>();
+        let a = Colliding(entries.clone());
+        let b = Colliding(entries.into_iter().rev().collect());
+
+        assert_canonical(&a, &b);
+    }
+
+    #[test]
+    fn deserialize_hash_map_in_place() {
+        let mut value = HashMap::new();
+        value.insert("foo".to_string(), vec![1, 2, 3]);
+        value.insert("bar".to_string(), vec![4, 5]);
+
+        to_archived(&value, |archived| {
+            let mut target = HashMap::<_, Vec<i32>>::with_capacity(32);
+            target.insert("foo".to_string(), Vec::with_capacity(16));
+            target.insert("baz".to_string(), vec![6]);
+            let capacity = target.capacity();
+            let foo = target["foo"].as_ptr();
+
+            deserialize_into(&*archived, &mut target);
+            assert_eq!(target, value);
+            assert_eq!(target.capacity(), capacity);
+            assert_eq!(target["foo"].as_ptr(), foo);
+
+            // With the same keys, the entries stay where they are
+            let entry: *const Vec<i32> = &target["foo"];
+            let key = target.keys().find(|k| *k == "foo").unwrap().as_ptr();
+            for _ in 0..3 {
+                deserialize_into(&*archived, &mut target);
+                assert_eq!(target, value);
+                assert_eq!(target.capacity(), capacity);
+                assert!(ptr::eq(&target["foo"], entry));
+                let k = target.keys().find(|k| *k == "foo").unwrap();
+                assert_eq!(k.as_ptr(), key);
+            }
+        });
+    }
 }
@@ -9,7 +9,7 @@ use rancor::{Fallible, Source};
 use crate::{
     collections::swiss_table::set::{ArchivedHashSet, HashSetResolver},
     ser::{Allocator, Writer},
-    Archive, Deserialize, Place, Serialize,
+    Archive, Deserialize, DeserializeInPlace, Place, Serialize,
 };
 
 impl<K, S> Archive for HashSet<K, S>
@@ -68,6 +68,16 @@ where
     }
 }
 
+impl<K, D, S> DeserializeInPlace<HashSet<K, S>, D>
+    for ArchivedHashSet<K::Archived>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D> + Hash + Eq,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+}
+
 impl<K: Hash + Eq + Borrow<AK>, AK: Hash + Eq, S: BuildHasher>
     PartialEq<HashSet<K, S>> for ArchivedHashSet<AK>
 {
@@ -92,7 +102,7 @@ impl<K: Hash + Eq + Borrow<AK>, AK: Hash + Eq, S: BuildHasher>
 mod tests {
     use std::collections::HashSet;
 
-    use crate::api::test::{roundtrip, roundtrip_with};
+    use crate::api::test::{assert_canonical, roundtrip, roundtrip_with};
 
     #[test]
     fn roundtrip_hash_set() {
@@ -122,4 +132,15 @@ mod tests {
         value.insert(());
         roundtrip(&value);
     }
+
+    #[test]
+    fn canonical_hash_set() {
+        let a = (0..200).map(|i| i.to_string()).collect::<HashSet<_>>();
+        let b = (0..200)
+            .rev()
+            .map(|i| i.to_string())
+            .collect::<HashSet<_>>();
+
+        assert_canonical(&a, &b);
+    }
 }
@@ -15,11 +15,14 @@ use rancor::{Fallible, OptionExt, ResultExt, Source};
 
 use crate::{
     collections::{
-        swiss_table::{ArchivedHashMap, HashMapResolver},
+        swiss_table::{
+            ArchivedHashMap, ArchivedKeyedHashMap, ArchivedKeyedHashSet,
+            HashMapResolver, KeyedHashMapResolver, KeyedHashSetResolver,
+        },
         util::{Entry, EntryAdapter},
     },
     ffi::{ArchivedCString, CStringResolver},
-    hash::FxHasher64,
+    hash::{FxHasher64, KeyedHasher, SeedSource},
     impls::core::with::RefWrapper,
     ser::{Allocator, Writer},
     string::{ArchivedString, StringResolver},
@@ -27,7 +30,7 @@ use crate::{
     vec::{ArchivedVec, VecResolver},
     with::{
         ArchiveWith, AsOwned, AsString, AsUnixTime, AsVec, DeserializeWith,
-        Lock, MapKV, SerializeWith,
+        Keyed, Lock, MapKV, SerializeWith,
     },
     Archive, Deserialize, Place, Serialize, SerializeUnsized,
 };
@@ -117,6 +120,146 @@ where
     }
 }
 
+// Keyed
+
+impl<K, V, H, R, S> ArchiveWith<HashMap<K, V, S>> for Keyed<H, R>
+where
+    K: Archive,
+    V: Archive,
+{
+    type Archived = ArchivedKeyedHashMap<K::Archived, V::Archived, H>;
+    type Resolver = KeyedHashMapResolver;
+
+    fn resolve_with(
+        field: &HashMap<K, V, S>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedKeyedHashMap::resolve_from_len(
+            field.len(),
+            (7, 8),
+            resolver,
+            out,
+        )
+    }
+}
+
+impl<K, V, H, R, S, RS> SerializeWith<HashMap<K, V, RS>, S> for Keyed<H, R>
+where
+    K: Serialize<S> + Hash + Eq,
+    V: Serialize<S>,
+    H: KeyedHasher,
+    R: SeedSource,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &HashMap<K, V, RS>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        <Self::Archived>::serialize_from_iter::<_, _, _, K, V, _>(
+            field.iter(),
+            R::seed(serializer.is_canonical()),
+            (7, 8),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, H, R, D, S>
+    DeserializeWith<
+        ArchivedKeyedHashMap<K::Archived, V::Archived, H>,
+        HashMap<K, V, S>,
+        D,
+    > for Keyed<H, R>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+    fn deserialize_with(
+        field: &ArchivedKeyedHashMap<K::Archived, V::Archived, H>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V, S>, D::Error> {
+        let mut result =
+            HashMap::with_capacity_and_hasher(field.len(), S::default());
+        for (k, v) in field.iter() {
+            result.insert(
+                k.deserialize(deserializer)?,
+                v.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+impl<K, H, R, S> ArchiveWith<HashSet<K, S>> for Keyed<H, R>
+where
+    K: Archive,
+{
+    type Archived = ArchivedKeyedHashSet<K::Archived, H>;
+    type Resolver = KeyedHashSetResolver;
+
+    fn resolve_with(
+        field: &HashSet<K, S>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedKeyedHashSet::resolve_from_len(
+            field.len(),
+            (7, 8),
+            resolver,
+            out,
+        )
+    }
+}
+
+impl<K, H, R, S, RS> SerializeWith<HashSet<K, RS>, S> for Keyed<H, R>
+where
+    K: Serialize<S> + Hash + Eq,
+    H: KeyedHasher,
+    R: SeedSource,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &HashSet<K, RS>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        <Self::Archived>::serialize_from_iter::<_, K, _>(
+            field.iter(),
+            R::seed(serializer.is_canonical()),
+            (7, 8),
+            serializer,
+        )
+    }
+}
+
+impl<K, H, R, D, S>
+    DeserializeWith<ArchivedKeyedHashSet<K::Archived, H>, HashSet<K, S>, D>
+    for Keyed<H, R>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+    fn deserialize_with(
+        field: &ArchivedKeyedHashSet<K::Archived, H>,
+        deserializer: &mut D,
+    ) -> Result<HashSet<K, S>, D::Error> {
+        let mut result =
+            HashSet::with_capacity_and_hasher(field.len(), S::default());
+        for k in field.iter() {
+            result.insert(k.deserialize(deserializer)?);
+        }
+        Ok(result)
+    }
+}
+
 // AsString
 
 #[derive(Debug)]
@@ -553,7 +696,7 @@ where
 #[cfg(test)]
 mod tests {
     use std::{
-        collections::BTreeMap,
+        collections::{BTreeMap, HashSet},
         ffi::OsString,
         path::PathBuf,
         sync::{Mutex, RwLock},
@@ -562,7 +705,8 @@ mod tests {
     use crate::{
         alloc::collections::HashMap,
         api::test::{roundtrip_with, to_archived},
-        with::{AsString, InlineAsBox, Lock, MapKV},
+        hash::{DefaultSeed, FixedSeed, HashSeed, SipHasher13},
+        with::{AsString, InlineAsBox, Keyed, Lock, MapKV},
         Archive, Deserialize, Serialize,
     };
 
@@ -613,6 +757,90 @@ mod tests {
         });
     }
 
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(crate, derive(Debug))]
+    struct KeyedTest {
+        #[rkyv(with = Keyed)]
+        inner: HashMap<String, u32>,
+    }
+
+    fn keyed_test_value() -> KeyedTest {
+        let mut inner = HashMap::new();
+        for i in 0..100 {
+            inner.insert(i.to_string(), i);
+        }
+        KeyedTest { inner }
+    }
+
+    #[test]
+    fn roundtrip_hash_map_keyed() {
+        roundtrip_with(&keyed_test_value(), |a, b| {
+            assert_eq!(a.inner.len(), b.inner.len());
+            for (key, value) in a.inner.iter() {
+                assert_eq!(b.inner[key.as_str()], *value);
+            }
+            assert!(!b.inner.contains_key("100"));
+        });
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn hash_map_keyed_wrong_seed() {
+        use core::mem::size_of;
+
+        use rancor::Failure;
+
+        use crate::{access, to_bytes};
+
+        let mut bytes = to_bytes::<Failure>(&keyed_test_value()).unwrap();
+        access::<ArchivedKeyedTest, Failure>(&bytes).unwrap();
+
+        // The seed is the first field of the root object.
+        let root = bytes.len() - size_of::<ArchivedKeyedTest>();
+        bytes[root] ^= 0xff;
+        assert!(access::<ArchivedKeyedTest, Failure>(&bytes).is_err());
+    }
+
+    #[test]
+    fn roundtrip_hash_set_keyed() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = Keyed<SipHasher13, FixedSeed<1, 2>>)]
+            inner: HashSet<String>,
+        }
+
+        let value = Test {
+            inner: (0..100).map(|i| i.to_string()).collect(),
+        };
+        roundtrip_with(&value, |a, b| {
+            assert_eq!(b.inner.seed(), HashSeed::new(1, 2));
+            assert_eq!(a.inner.len(), b.inner.len());
+            for key in a.inner.iter() {
+                assert!(b.inner.contains(key.as_str()));
+            }
+            assert!(!b.inner.contains("100"));
+        });
+    }
+
+    #[test]
+    fn hash_map_keyed_canonical() {
+        use rancor::Panic;
+
+        use crate::{access_unchecked, api::high::to_bytes_canonical};
+
+        let value = keyed_test_value();
+        let bytes = to_bytes_canonical::<Panic>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedKeyedTest>(&bytes) };
+        assert_eq!(archived.inner.seed(), DefaultSeed::CANONICAL);
+
+        let copy = KeyedTest {
+            inner: value.inner.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+        };
+        let copy_bytes = to_bytes_canonical::<Panic>(&copy).unwrap();
+        assert_eq!(bytes.as_slice(), copy_bytes.as_slice());
+    }
+
     #[test]
     fn with_btree_map_mapkv() {
         #[derive(Archive, Serialize, Deserialize)]
@@ -147,11 +147,16 @@
 //! - [`hashbrown-0_14`](https://docs.rs/hashbrown/0.14)
 //! - [`hashbrown-0_15`](https://docs.rs/hashbrown/0.15)
 //! - [`indexmap-2`](https://docs.rs/indexmap/2)
+//! - [`lz4_flex-0_11`](https://docs.rs/lz4_flex/0.11)
+//! - [`memmap2-0_9`](https://docs.rs/memmap2/0.9)
+//! - [`rayon-1`](https://docs.rs/rayon/1)
 //! - [`smallvec-1`](https://docs.rs/smallvec/1)
 //! - [`smol_str-0_2`](https://docs.rs/smol_str/0.2)
 //! - [`smol_str-0_3`](https://docs.rs/smol_str/0.3)
 //! - [`thin-vec-0_2`](https://docs.rs/thin-vec/0.2)
 //! - [`tinyvec-1`](https://docs.rs/tinyvec/1)
+//! - [`tokio-1`](https://docs.rs/tokio/1)
+//! - [`tokio-util-0_7`](https://docs.rs/tokio-util/0.7)
 //! - [`triomphe-0_1`](https://docs.rs/triomphe/0.1)
 //! - [`uuid-1`](https://docs.rs/uuid/1)
 //!
@@ -217,16 +222,33 @@ mod alias;
 mod _macros;
 pub mod api;
 pub mod boxed;
+#[cfg(feature = "alloc")]
+pub mod bundle;
+pub mod checksum;
+#[cfg(feature = "tokio-util-0_7")]
+pub mod codec;
 pub mod collections;
+pub mod columnar;
+#[cfg(feature = "alloc")]
+pub mod compression;
 pub mod de;
+pub mod embed;
+#[cfg(feature = "alloc")]
+pub mod ext_ref;
 pub mod ffi;
 mod fmt;
 pub mod hash;
 mod impls;
+#[cfg(all(feature = "memmap2-0_9", feature = "bytecheck"))]
+pub mod mmap;
+pub mod multi_root;
 pub mod net;
 pub mod niche;
+pub mod offset;
 pub mod ops;
 pub mod option;
+#[cfg(all(feature = "bytecheck", feature = "alloc"))]
+pub mod owned;
 pub mod place;
 mod polyfill;
 pub mod primitive;
@@ -236,6 +258,8 @@ pub mod result;
 pub mod seal;
 pub mod ser;
 mod simd;
+#[cfg(feature = "alloc")]
+pub mod stream;
 pub mod string;
 pub mod time;
 pub mod traits;
@@ -253,7 +277,9 @@ pub mod with;
 pub use api::high::{access, access_mut, from_bytes};
 #[cfg(feature = "alloc")]
 #[doc(inline)]
-pub use api::high::{deserialize, from_bytes_unchecked, to_bytes};
+pub use api::high::{
+    archived_size, deserialize, from_bytes_unchecked, to_bytes,
+};
 
 #[doc(inline)]
 pub use crate::{
@@ -261,8 +287,8 @@ pub use crate::{
     api::{access_unchecked, access_unchecked_mut},
     place::Place,
     traits::{
-        Archive, ArchiveUnsized, Deserialize, DeserializeUnsized, Portable,
-        Serialize, SerializeUnsized,
+        Archive, ArchiveUnsized, Deserialize, DeserializeInPlace,
+        DeserializeUnsized, Portable, Serialize, SerializeUnsized,
     },
 };
 
@@ -0,0 +1,376 @@
+//! Archives backed by memory-mapped files.
+//!
+//! A [`MappedArchive`] owns a read-only memory map of an archive file. The
+//! archive is validated once when it is opened, and can then be accessed
+//! through `Deref` without any further checks. [`MappedArchive::open_mut`]
+//! maps a file for writing, so the archive can be updated in place through a
+//! [`Seal`] and flushed back to the file.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{mmap::MappedArchive, rancor::Error};
+//!
+//! let path = std::env::temp_dir().join("rkyv_mapped_archive_example.bin");
+//! let bytes = rkyv::to_bytes::<Error>(&[1u32, 2, 3, 4]).unwrap();
+//! std::fs::write(&path, &bytes).unwrap();
+//!
+//! // SAFETY: No other process modifies the file while it is mapped.
+//! let mut archive =
+//!     unsafe { MappedArchive::<[u32; 4]>::open_mut::<Error>(&path) }.unwrap();
+//! archive.seal()[2] = 10.into();
+//! archive.flush::<Error>().unwrap();
+//! drop(archive);
+//!
+//! // SAFETY: No other process modifies the file while it is mapped.
+//! let archive =
+//!     unsafe { MappedArchive::<[u32; 4]>::open::<Error>(&path) }.unwrap();
+//! assert_eq!(*archive, [1, 2, 10, 4]);
+//! # drop(archive);
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use core::{error::Error, fmt, marker::PhantomData, mem::align_of, ops::Deref};
+use std::{fs::OpenOptions, path::Path};
+
+use bytecheck::CheckBytes;
+use memmap2_0_9::{Mmap, MmapMut};
+use rancor::{fail, ResultExt as _, Source};
+
+use crate::{
+    api::{
+        access_unchecked, access_unchecked_mut,
+        high::{access, access_mut, HighValidator},
+    },
+    seal::Seal,
+    Archive,
+};
+
+#[derive(Debug)]
+struct MisalignedMapping {
+    address: usize,
+    align: usize,
+}
+
+impl fmt::Display for MisalignedMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "memory map at address {:#x} is not aligned to {} bytes",
+            self.address, self.align,
+        )
+    }
+}
+
+impl Error for MisalignedMapping {}
+
+fn check_alignment<T, E: Source>(bytes: &[u8]) -> Result<(), E> {
+    let address = bytes.as_ptr() as usize;
+    if address % align_of::<T>() != 0 {
+        fail!(MisalignedMapping {
+            address,
+            align: align_of::<T>(),
+        });
+    }
+    Ok(())
+}
+
+/// A validated archive of a `T` in a read-only memory-mapped file.
+///
+/// `MappedArchive` dereferences to the archived value. See the
+/// [module docs](crate::mmap) for an example.
+pub struct MappedArchive<T> {
+    map: Mmap,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: Archive> MappedArchive<T> {
+    /// Maps the file at the given path and validates the archive in it.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated by any other process or
+    /// handle while it is mapped.
+    pub unsafe fn open<E>(path: impl AsRef<Path>) -> Result<Self, E>
+    where
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+        E: Source,
+    {
+        let file = OpenOptions::new().read(true).open(path).into_error()?;
+        // SAFETY: The caller has guaranteed that the file will not be
+        // modified while it is mapped.
+        let map = unsafe { Mmap::map(&file) }.into_error()?;
+        Self::from_mmap(map)
+    }
+
+    /// Validates the archive in the given memory map.
+    pub fn from_mmap<E>(map: Mmap) -> Result<Self, E>
+    where
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+        E: Source,
+    {
+        check_alignment::<T::Archived, E>(&map)?;
+        access::<T::Archived, E>(&map)?;
+        Ok(Self {
+            map,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Wraps the given memory map without validating it.
+    ///
+    /// # Safety
+    ///
+    /// The bytes of the memory map must pass validation when passed to
+    /// [`access`] as a `T::Archived`.
+    pub unsafe fn from_mmap_unchecked(map: Mmap) -> Self {
+        Self {
+            map,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Maps the file at the given path for writing and validates the archive
+    /// in it.
+    ///
+    /// The file must be readable and writable. Changes made through the
+    /// returned [`MappedArchiveMut`] are written back to the file.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated by any other process or
+    /// handle while it is mapped.
+    pub unsafe fn open_mut<E>(
+        path: impl AsRef<Path>,
+    ) -> Result<MappedArchiveMut<T>, E>
+    where
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+        E: Source,
+    {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .into_error()?;
+        // SAFETY: The caller has guaranteed that the file will not be
+        // modified while it is mapped.
+        let map = unsafe { MmapMut::map_mut(&file) }.into_error()?;
+        MappedArchiveMut::from_mmap(map)
+    }
+
+    /// Returns the bytes of the archive.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// Consumes the `MappedArchive` and returns the memory map.
+    pub fn into_mmap(self) -> Mmap {
+        self.map
+    }
+}
+
+impl<T: Archive> Deref for MappedArchive<T> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The bytes of the memory map were validated when the
+        // `MappedArchive` was created.
+        unsafe { access_unchecked::<T::Archived>(&self.map) }
+    }
+}
+
+impl<T: Archive> fmt::Debug for MappedArchive<T>
+where
+    T::Archived: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// A validated archive of a `T` in a writable memory-mapped file.
+///
+/// `MappedArchiveMut` dereferences to the archived value, and can be updated in
+/// place with [`seal`](Self::seal). Changes are written back to the file by the
+/// operating system, and [`flush`](Self::flush) waits for them to be written.
+/// See the [module docs](crate::mmap) for an example.
+pub struct MappedArchiveMut<T> {
+    map: MmapMut,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: Archive> MappedArchiveMut<T> {
+    /// Validates the archive in the given writable memory map.
+    pub fn from_mmap<E>(mut map: MmapMut) -> Result<Self, E>
+    where
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+        E: Source,
+    {
+        check_alignment::<T::Archived, E>(&map)?;
+        access_mut::<T::Archived, E>(&mut map)?;
+        Ok(Self {
+            map,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Wraps the given writable memory map without validating it.
+    ///
+    /// # Safety
+    ///
+    /// The bytes of the memory map must pass validation when passed to
+    /// [`access_mut`] as a `T::Archived`.
+    pub unsafe fn from_mmap_unchecked(map: MmapMut) -> Self {
+        Self {
+            map,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a sealed mutable reference to the archived value.
+    pub fn seal(&mut self) -> Seal<'_, T::Archived> {
+        // SAFETY: The bytes of the memory map were validated when the
+        // `MappedArchiveMut` was created, and `Seal` only allows changes which
+        // keep them valid.
+        unsafe { access_unchecked_mut::<T::Archived>(&mut self.map) }
+    }
+
+    /// Flushes changes to the file and waits for them to be written.
+    pub fn flush<E: Source>(&self) -> Result<(), E> {
+        self.map.flush().into_error()
+    }
+
+    /// Starts flushing changes to the file without waiting for them to be
+    /// written.
+    pub fn flush_async<E: Source>(&self) -> Result<(), E> {
+        self.map.flush_async().into_error()
+    }
+
+    /// Returns the bytes of the archive.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// Makes the memory map read-only and returns it as a [`MappedArchive`].
+    pub fn into_read_only<E: Source>(self) -> Result<MappedArchive<T>, E> {
+        let map = self.map.make_read_only().into_error()?;
+        // SAFETY: The bytes of the memory map were validated when the
+        // `MappedArchiveMut` was created, and were only changed through `Seal`.
+        unsafe { Ok(MappedArchive::from_mmap_unchecked(map)) }
+    }
+
+    /// Consumes the `MappedArchiveMut` and returns the memory map.
+    pub fn into_mmap(self) -> MmapMut {
+        self.map
+    }
+}
+
+impl<T: Archive> Deref for MappedArchiveMut<T> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The bytes of the memory map were validated when the
+        // `MappedArchiveMut` was created.
+        unsafe { access_unchecked::<T::Archived>(&self.map) }
+    }
+}
+
+impl<T: Archive> fmt::Debug for MappedArchiveMut<T>
+where
+    T::Archived: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use rancor::{Error, Panic};
+
+    use super::MappedArchive;
+    use crate::{
+        alloc::{string::String, vec::Vec},
+        to_bytes, Archive, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate)]
+    struct Table {
+        names: Vec<String>,
+        counts: [u32; 4],
+    }
+
+    fn temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "rkyv_mmap_{}_{}",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn mapped_archive() {
+        let table = Table {
+            names: vec![String::from("a name which is not inline"); 3],
+            counts: [1, 2, 3, 4],
+        };
+        let path =
+            temp_file("mapped_archive", &to_bytes::<Panic>(&table).unwrap());
+
+        let archive =
+            unsafe { MappedArchive::<Table>::open::<Panic>(&path) }.unwrap();
+        assert_eq!(archive.names.len(), 3);
+        assert_eq!(archive.names[2], "a name which is not inline");
+        assert_eq!(archive.counts, [1, 2, 3, 4]);
+
+        drop(archive);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn mapped_archive_mut() {
+        let table = Table {
+            names: Vec::new(),
+            counts: [1, 2, 3, 4],
+        };
+        let path = temp_file(
+            "mapped_archive_mut",
+            &to_bytes::<Panic>(&table).unwrap(),
+        );
+
+        let mut archive =
+            unsafe { MappedArchive::<Table>::open_mut::<Panic>(&path) }
+                .unwrap();
+        munge::munge!(let ArchivedTable { mut counts, .. } = archive.seal());
+        counts[1] = 20.into();
+        archive.flush::<Panic>().unwrap();
+        let archive = archive.into_read_only::<Panic>().unwrap();
+        assert_eq!(archive.counts, [1, 20, 3, 4]);
+        drop(archive);
+
+        let archive =
+            unsafe { MappedArchive::<Table>::open::<Panic>(&path) }.unwrap();
+        assert_eq!(archive.counts, [1, 20, 3, 4]);
+
+        drop(archive);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn mapped_archive_invalid() {
+        let path = temp_file("mapped_archive_invalid", &[0xff; 16]);
+        assert!(
+            unsafe { MappedArchive::<Table>::open::<Error>(&path) }.is_err()
+        );
+        assert!(unsafe {
+            MappedArchive::<Table>::open_mut::<Error>(path.join("missing"))
+        }
+        .is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,718 @@
+//! Archives with multiple named roots.
+//!
+//! A multi-root archive holds several independent values of different types in
+//! one buffer. The values are written one after another, and the buffer ends
+//! with an [`ArchivedTableOfContents`] which maps the name of each value to its
+//! position. Shared pointers are shared between all of the values.
+//!
+//! Multi-root archives are built with a [`MultiRootBuilder`], and each value
+//! can be accessed on its own with [`access_named`]. Only the table of contents
+//! and the requested value are validated.
+//!
+//! The table of contents records a hash of the name of each root's archived
+//! type (from [`core::any::type_name`]) along with its size and alignment, and
+//! accessing a root as a different type fails. Type names are not guaranteed
+//! to be stable across compiler versions, so an archive written by a program
+//! built with a different compiler may fail to access.
+//!
+//! # Example
+//!
+//! ```
+//! use std::collections::HashMap;
+//!
+//! use rkyv::{
+//!     multi_root::{access_named, MultiRootBuilder},
+//!     rancor::Error,
+//!     Archived,
+//! };
+//!
+//! let index = vec![1u32, 2, 3];
+//! let config = HashMap::from([("verbose".to_string(), true)]);
+//!
+//! let mut builder = MultiRootBuilder::new();
+//! builder.add::<_, Error>("index", &index).unwrap();
+//! builder.add::<_, Error>("config", &config).unwrap();
+//! let bytes = builder.finish::<Error>().unwrap();
+//!
+//! let index = access_named::<Archived<Vec<u32>>, Error>(&bytes, "index");
+//! assert_eq!(index.unwrap(), &[1, 2, 3]);
+//!
+//! let config = access_named::<Archived<HashMap<String, bool>>, Error>(
+//!     &bytes, "config",
+//! )
+//! .unwrap();
+//! assert_eq!(config.get("verbose"), Some(&true));
+//! ```
+
+use core::{
+    any::type_name,
+    error::Error,
+    fmt,
+    mem::{align_of, size_of},
+};
+
+use rancor::{fail, Source};
+
+use crate::{
+    hash::{hash_value, FxHasher64},
+    primitive::{ArchivedU64, ArchivedUsize},
+    string::ArchivedString,
+    vec::ArchivedVec,
+    Portable,
+};
+
+#[cfg(any(feature = "alloc", feature = "bytecheck"))]
+const MAGIC: [u8; 4] = *b"rkym";
+
+/// Returns the hash of the name of `T` which identifies it in a table of
+/// contents.
+fn type_hash<T: ?Sized>() -> u64 {
+    hash_value::<str, FxHasher64>(type_name::<T>())
+}
+
+/// An entry in an [`ArchivedTableOfContents`].
+///
+/// Entries identify their root's archived type with a hash of its name, and
+/// describe its layout with its size and alignment.
+#[derive(Portable)]
+#[rkyv(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(C)]
+pub struct ArchivedRootEntry {
+    name: ArchivedString,
+    pos: ArchivedUsize,
+    size: ArchivedUsize,
+    align: ArchivedUsize,
+    type_hash: ArchivedU64,
+}
+
+impl ArchivedRootEntry {
+    /// Returns the name of the root.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Returns the position of the root in the archive.
+    pub fn pos(&self) -> usize {
+        self.pos.to_native() as usize
+    }
+
+    /// Returns the size of the archived root type.
+    pub fn size(&self) -> usize {
+        self.size.to_native() as usize
+    }
+
+    /// Returns the alignment of the archived root type.
+    pub fn align(&self) -> usize {
+        self.align.to_native() as usize
+    }
+
+    /// Returns the hash of the name of the archived root type.
+    pub fn type_hash(&self) -> u64 {
+        self.type_hash.to_native()
+    }
+}
+
+impl fmt::Debug for ArchivedRootEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedRootEntry")
+            .field("name", &self.name())
+            .field("pos", &self.pos())
+            .field("size", &self.size())
+            .field("align", &self.align())
+            .field("type_hash", &self.type_hash())
+            .finish()
+    }
+}
+
+/// The table of contents of a multi-root archive.
+///
+/// This is the root of a multi-root archive, and can be accessed like any
+/// other root. Its entries are sorted by name.
+#[derive(Portable)]
+#[rkyv(crate)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[repr(C)]
+pub struct ArchivedTableOfContents {
+    magic: [u8; 4],
+    entries: ArchivedVec<ArchivedRootEntry>,
+}
+
+impl ArchivedTableOfContents {
+    /// Returns the entries of the table of contents.
+    pub fn entries(&self) -> &[ArchivedRootEntry] {
+        self.entries.as_slice()
+    }
+
+    /// Returns the names of the roots.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries().iter().map(ArchivedRootEntry::name)
+    }
+
+    /// Returns the entry with the given name, if any.
+    pub fn get(&self, name: &str) -> Option<&ArchivedRootEntry> {
+        let entries = self.entries();
+        entries
+            .binary_search_by(|entry| entry.name().cmp(name))
+            .ok()
+            .map(|index| &entries[index])
+    }
+
+    /// Returns the position of the root with the given name and archived type
+    /// `T`.
+    ///
+    /// Returns an error if the root's archived type has a different name, size,
+    /// or alignment than `T`.
+    pub fn root_pos<T: Portable, E: Source>(
+        &self,
+        name: &str,
+    ) -> Result<usize, E> {
+        let Some(entry) = self.get(name) else {
+            fail!(RootNotFound);
+        };
+        if entry.type_hash() != type_hash::<T>()
+            || entry.size() != size_of::<T>()
+            || entry.align() != align_of::<T>()
+        {
+            fail!(RootTypeMismatch {
+                expected: type_name::<T>(),
+            });
+        }
+        Ok(entry.pos())
+    }
+}
+
+impl fmt::Debug for ArchivedTableOfContents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.entries()).finish()
+    }
+}
+
+#[derive(Debug)]
+struct RootNotFound;
+
+impl fmt::Display for RootNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no root with the given name in the table of contents")
+    }
+}
+
+impl Error for RootNotFound {}
+
+#[derive(Debug)]
+struct RootTypeMismatch {
+    expected: &'static str,
+}
+
+impl fmt::Display for RootTypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "root was not archived with the requested type `{}`",
+            self.expected,
+        )
+    }
+}
+
+impl Error for RootTypeMismatch {}
+
+/// Accesses the root with the given name in a multi-root archive without
+/// validation.
+///
+/// Like [`access_named`], this returns an error if the root was archived with a
+/// different type than `T`.
+///
+/// # Safety
+///
+/// The bytes must pass validation when passed to [`access_named`].
+pub unsafe fn access_named_unchecked<'a, T, E>(
+    bytes: &'a [u8],
+    name: &str,
+) -> Result<&'a T, E>
+where
+    T: Portable,
+    E: Source,
+{
+    // SAFETY: The caller has guaranteed that the bytes are a valid multi-root
+    // archive.
+    let toc =
+        unsafe { crate::access_unchecked::<ArchivedTableOfContents>(bytes) };
+    let pos = toc.root_pos::<T, E>(name)?;
+    // SAFETY: The caller has guaranteed that a valid `T` is located at `pos`.
+    unsafe { Ok(crate::api::access_pos_unchecked::<T>(bytes, pos)) }
+}
+
+#[cfg(feature = "alloc")]
+mod builder {
+    use core::{
+        error::Error,
+        fmt,
+        mem::{align_of, size_of, take},
+    };
+
+    use munge::munge;
+    use rancor::{fail, Fallible, Source};
+
+    use super::{type_hash, ArchivedRootEntry, ArchivedTableOfContents, MAGIC};
+    #[cfg(feature = "bytecheck")]
+    use crate::bundle::check_appended;
+    use crate::{
+        alloc::{string::String, vec::Vec},
+        api::{high::HighSerializer, serialize_using},
+        bundle::append_archive,
+        primitive::{ArchivedU64, ArchivedUsize, FixedUsize},
+        ser::{
+            allocator::{Arena, ArenaHandle},
+            sharing::Share,
+            Allocator, Serializer, Writer,
+        },
+        string::{ArchivedString, StringResolver},
+        util::AlignedVec,
+        vec::{ArchivedVec, VecResolver},
+        Archive, Place, Portable, Serialize,
+    };
+
+    #[derive(Debug)]
+    struct DuplicateRootName {
+        name: String,
+    }
+
+    impl fmt::Display for DuplicateRootName {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "the archive already has a root named \"{}\"", self.name)
+        }
+    }
+
+    impl Error for DuplicateRootName {}
+
+    struct RootEntry {
+        name: String,
+        pos: usize,
+        size: usize,
+        align: usize,
+        type_hash: u64,
+    }
+
+    impl Archive for RootEntry {
+        type Archived = ArchivedRootEntry;
+        type Resolver = StringResolver;
+
+        fn resolve(
+            &self,
+            resolver: Self::Resolver,
+            out: Place<Self::Archived>,
+        ) {
+            munge! {
+                let ArchivedRootEntry { name, pos, size, align, type_hash } =
+                    out;
+            }
+            ArchivedString::resolve_from_str(&self.name, resolver, name);
+            pos.write(ArchivedUsize::from_native(self.pos as FixedUsize));
+            size.write(ArchivedUsize::from_native(self.size as FixedUsize));
+            align.write(ArchivedUsize::from_native(self.align as FixedUsize));
+            type_hash.write(ArchivedU64::from_native(self.type_hash));
+        }
+    }
+
+    impl<S> Serialize<S> for RootEntry
+    where
+        S: Fallible + Writer + ?Sized,
+        S::Error: Source,
+        str: crate::SerializeUnsized<S>,
+    {
+        fn serialize(
+            &self,
+            serializer: &mut S,
+        ) -> Result<Self::Resolver, S::Error> {
+            ArchivedString::serialize_from_str(&self.name, serializer)
+        }
+    }
+
+    struct TableOfContents<'a>(&'a [RootEntry]);
+
+    impl Archive for TableOfContents<'_> {
+        type Archived = ArchivedTableOfContents;
+        type Resolver = VecResolver;
+
+        fn resolve(
+            &self,
+            resolver: Self::Resolver,
+            out: Place<Self::Archived>,
+        ) {
+            munge!(let ArchivedTableOfContents { magic, entries } = out);
+            magic.write(MAGIC);
+            ArchivedVec::resolve_from_slice(self.0, resolver, entries);
+        }
+    }
+
+    impl<S> Serialize<S> for TableOfContents<'_>
+    where
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+        RootEntry: Serialize<S>,
+    {
+        fn serialize(
+            &self,
+            serializer: &mut S,
+        ) -> Result<Self::Resolver, S::Error> {
+            ArchivedVec::serialize_from_slice(self.0, serializer)
+        }
+    }
+
+    /// A builder for archives with multiple named roots.
+    ///
+    /// Values are serialized as they are added, and shared pointers are shared
+    /// between all of them. Call [`finish`](Self::finish) to write the table of
+    /// contents and get the bytes of the archive. See the
+    /// [module docs](crate::multi_root) for an example.
+    pub struct MultiRootBuilder {
+        writer: AlignedVec,
+        arena: Arena,
+        share: Share,
+        entries: Vec<RootEntry>,
+    }
+
+    impl Default for MultiRootBuilder {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl fmt::Debug for MultiRootBuilder {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("MultiRootBuilder")
+                .field("len", &self.writer.len())
+                .field(
+                    "roots",
+                    &self
+                        .entries
+                        .iter()
+                        .map(|entry| entry.name.as_str())
+                        .collect::<Vec<_>>(),
+                )
+                .finish()
+        }
+    }
+
+    impl MultiRootBuilder {
+        /// Creates a new empty `MultiRootBuilder`.
+        pub fn new() -> Self {
+            Self {
+                writer: AlignedVec::new(),
+                arena: Arena::new(),
+                share: Share::new(),
+                entries: Vec::new(),
+            }
+        }
+
+        /// Returns the number of roots which have been added.
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        /// Returns whether no roots have been added.
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+
+        /// Serializes a value and adds it as a root with the given name.
+        ///
+        /// Returns an error if a root with the same name was already added.
+        pub fn add<T, E>(&mut self, name: &str, value: &T) -> Result<(), E>
+        where
+            T: for<'a> Serialize<
+                HighSerializer<AlignedVec, ArenaHandle<'a>, E>,
+            >,
+            E: Source,
+        {
+            self.check_name(name)?;
+
+            let mut serializer = Serializer::new(
+                take(&mut self.writer),
+                self.arena.acquire(),
+                take(&mut self.share),
+            );
+            let result = serialize_using(value, &mut serializer);
+            let (writer, _, share) = serializer.into_raw_parts();
+            self.writer = writer;
+            self.share = share;
+
+            self.push_entry::<T::Archived>(name, result?);
+            Ok(())
+        }
+
+        /// Validates an existing archive and adds it as a root with the given
+        /// name, without reserializing it.
+        ///
+        /// `T` is the archived root type of the archive, which does not need to
+        /// be aligned. Shared pointers in the archive are not shared with the
+        /// other roots. Returns an error if the archive is invalid or a root
+        /// with the same name was already added.
+        #[cfg(feature = "bytecheck")]
+        pub fn add_archive<T, E>(
+            &mut self,
+            name: &str,
+            bytes: &[u8],
+        ) -> Result<(), E>
+        where
+            T: Portable
+                + for<'a> bytecheck::CheckBytes<
+                    crate::api::high::HighValidator<'a, E>,
+                >,
+            E: Source,
+        {
+            self.check_name(name)?;
+            let prev_len = self.writer.len();
+            let pos = append_archive::<T>(&mut self.writer, bytes);
+            check_appended::<T, E>(&mut self.writer, prev_len, bytes.len())?;
+            self.push_entry::<T>(name, pos);
+            Ok(())
+        }
+
+        /// Adds an existing archive as a root with the given name without
+        /// validating it.
+        ///
+        /// Returns an error if a root with the same name was already added.
+        ///
+        /// # Safety
+        ///
+        /// The bytes must pass validation when passed to
+        /// [`access`](crate::access) as a `T`, except that they don't need to
+        /// be aligned.
+        pub unsafe fn add_archive_unchecked<T: Portable, E: Source>(
+            &mut self,
+            name: &str,
+            bytes: &[u8],
+        ) -> Result<(), E> {
+            self.check_name(name)?;
+            let pos = append_archive::<T>(&mut self.writer, bytes);
+            self.push_entry::<T>(name, pos);
+            Ok(())
+        }
+
+        fn check_name<E: Source>(&self, name: &str) -> Result<(), E> {
+            if self.entries.iter().any(|entry| entry.name == name) {
+                fail!(DuplicateRootName { name: name.into() });
+            }
+            Ok(())
+        }
+
+        fn push_entry<T: Portable>(&mut self, name: &str, pos: usize) {
+            self.entries.push(RootEntry {
+                name: name.into(),
+                pos,
+                size: size_of::<T>(),
+                align: align_of::<T>(),
+                type_hash: type_hash::<T>(),
+            });
+        }
+
+        /// Writes the table of contents and returns the bytes of the archive.
+        pub fn finish<E: Source>(mut self) -> Result<AlignedVec, E> {
+            self.entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let mut serializer =
+                Serializer::new(self.writer, self.arena.acquire(), self.share);
+            serialize_using(&TableOfContents(&self.entries), &mut serializer)?;
+            Ok(serializer.into_writer())
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub use self::builder::MultiRootBuilder;
+
+#[cfg(feature = "bytecheck")]
+mod checked {
+    use core::{error::Error, fmt};
+
+    use bytecheck::{rancor::Fallible, Verify};
+    use rancor::{fail, Source};
+
+    use super::{ArchivedTableOfContents, MAGIC};
+
+    #[derive(Debug)]
+    struct InvalidMagic;
+
+    impl fmt::Display for InvalidMagic {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "archive does not end with a table of contents")
+        }
+    }
+
+    impl Error for InvalidMagic {}
+
+    unsafe impl<C> Verify<C> for ArchivedTableOfContents
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            if self.magic != MAGIC {
+                fail!(InvalidMagic);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Accesses the root with the given name in a multi-root archive.
+///
+/// The table of contents and the requested root are validated, but the other
+/// roots are not. Returns an error if there is no root with the given name, or
+/// if it was archived with a different type than `T`.
+///
+/// This is part of the [high-level API](crate::api::high). See the
+/// [module docs](crate::multi_root) for an example.
+#[cfg(all(feature = "bytecheck", feature = "alloc"))]
+pub fn access_named<'a, T, E>(bytes: &'a [u8], name: &str) -> Result<&'a T, E>
+where
+    T: Portable
+        + for<'b> bytecheck::CheckBytes<crate::api::high::HighValidator<'b, E>>,
+    E: Source,
+{
+    let toc = crate::access::<ArchivedTableOfContents, E>(bytes)?;
+    let pos = toc.root_pos::<T, E>(name)?;
+    crate::api::high::access_pos::<T, E>(bytes, pos)
+}
+
+#[cfg(all(test, feature = "bytecheck", feature = "alloc"))]
+mod tests {
+    use rancor::{Error, Panic};
+
+    use super::{
+        access_named, access_named_unchecked, ArchivedTableOfContents,
+        MultiRootBuilder,
+    };
+    use crate::{
+        alloc::{string::String, vec::Vec},
+        rc::ArchivedRc,
+        string::ArchivedString,
+        Archive, Archived, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate)]
+    struct Header {
+        version: u32,
+        title: String,
+    }
+
+    #[test]
+    fn multi_root_roundtrip() {
+        let mut builder = MultiRootBuilder::new();
+        builder
+            .add::<_, Panic>(
+                "header",
+                &Header {
+                    version: 3,
+                    title: String::from("a title which is not inline"),
+                },
+            )
+            .unwrap();
+        builder
+            .add::<_, Panic>("values", &(0..100u64).collect::<Vec<_>>())
+            .unwrap();
+        builder.add::<_, Panic>("flag", &true).unwrap();
+        assert_eq!(builder.len(), 3);
+        let bytes = builder.finish::<Panic>().unwrap();
+
+        let toc =
+            crate::access::<ArchivedTableOfContents, Panic>(&bytes).unwrap();
+        assert_eq!(
+            toc.names().collect::<Vec<_>>(),
+            ["flag", "header", "values"]
+        );
+
+        let header =
+            access_named::<ArchivedHeader, Panic>(&bytes, "header").unwrap();
+        assert_eq!(header.version, 3);
+        assert_eq!(header.title, "a title which is not inline");
+        let values =
+            access_named::<Archived<Vec<u64>>, Panic>(&bytes, "values")
+                .unwrap();
+        assert!(values.iter().copied().eq(0..100));
+        let flag = access_named::<bool, Panic>(&bytes, "flag").unwrap();
+        assert!(*flag);
+    }
+
+    #[test]
+    fn multi_root_errors() {
+        let mut builder = MultiRootBuilder::new();
+        builder.add::<_, Error>("value", &42u32).unwrap();
+        assert!(builder.add::<_, Error>("value", &1u32).is_err());
+        let bytes = builder.finish::<Error>().unwrap();
+
+        assert!(
+            access_named::<Archived<u32>, Error>(&bytes, "missing").is_err()
+        );
+        assert!(access_named::<Archived<u64>, Error>(&bytes, "value").is_err());
+        assert_eq!(
+            *access_named::<Archived<u32>, Error>(&bytes, "value").unwrap(),
+            42
+        );
+
+        // Same size and alignment, and valid for every bit pattern
+        assert!(access_named::<Archived<i32>, Error>(&bytes, "value").is_err());
+        assert!(unsafe {
+            access_named_unchecked::<Archived<i32>, Error>(&bytes, "value")
+        }
+        .is_err());
+        assert!(unsafe {
+            access_named_unchecked::<Archived<u32>, Error>(&bytes, "value")
+        }
+        .is_ok());
+
+        let bytes = crate::to_bytes::<Error>(&42u32).unwrap();
+        assert!(
+            crate::access::<ArchivedTableOfContents, Error>(&bytes).is_err()
+        );
+    }
+
+    #[test]
+    fn multi_root_add_archive() {
+        let bytes = crate::to_bytes::<Error>(&Header {
+            version: 7,
+            title: String::from("an archived title which is not inline"),
+        })
+        .unwrap();
+
+        let mut builder = MultiRootBuilder::new();
+        builder.add::<_, Error>("flag", &false).unwrap();
+        builder
+            .add_archive::<ArchivedHeader, Error>("header", &bytes)
+            .unwrap();
+        assert!(builder
+            .add_archive::<ArchivedHeader, Error>("invalid", &bytes[1..])
+            .is_err());
+        assert_eq!(builder.len(), 2);
+        let bytes = builder.finish::<Error>().unwrap();
+
+        let header =
+            access_named::<ArchivedHeader, Error>(&bytes, "header").unwrap();
+        assert_eq!(header.version, 7);
+        assert_eq!(header.title, "an archived title which is not inline");
+        assert!(!*access_named::<bool, Error>(&bytes, "flag").unwrap());
+    }
+
+    #[test]
+    fn multi_root_shared_pointers() {
+        let shared =
+            crate::alloc::rc::Rc::new(String::from("a string shared by roots"));
+
+        let mut builder = MultiRootBuilder::new();
+        builder.add::<_, Panic>("a", &shared).unwrap();
+        builder.add::<_, Panic>("b", &shared).unwrap();
+        let bytes = builder.finish::<Panic>().unwrap();
+
+        type ArchivedShared = ArchivedRc<ArchivedString, crate::rc::RcFlavor>;
+        let a = access_named::<ArchivedShared, Panic>(&bytes, "a").unwrap();
+        let b = access_named::<ArchivedShared, Panic>(&bytes, "b").unwrap();
+        assert_eq!(**a, "a string shared by roots");
+        assert!(core::ptr::eq(&**a, &**b));
+    }
+}
@@ -0,0 +1,359 @@
+//! Typed handles to archived values.
+//!
+//! An [`ArchivedOffset`] records the position of an archived value in its
+//! buffer. Unlike a raw `usize`, it remembers the type of the value, and
+//! resolving it checks that a valid value of that type is actually located at
+//! the position. Offsets can be stored outside of the archive, for example in
+//! a search index, and resolved later with [`ResolveOffset::resolve`].
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     offset::ResolveOffset, rancor::Error, to_bytes, Archive, Archived,
+//!     Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! struct Document {
+//!     title: String,
+//!     body: String,
+//! }
+//!
+//! let documents = vec![
+//!     Document {
+//!         title: "first".to_string(),
+//!         body: "the first document".to_string(),
+//!     },
+//!     Document {
+//!         title: "second".to_string(),
+//!         body: "the second document".to_string(),
+//!     },
+//! ];
+//! let bytes = to_bytes::<Error>(&documents).unwrap();
+//!
+//! let archived =
+//!     rkyv::access::<Archived<Vec<Document>>, Error>(&bytes).unwrap();
+//! let handle = bytes.offset_of(&archived[1]).unwrap();
+//!
+//! let document = bytes.resolve::<_, Error>(handle).unwrap();
+//! assert_eq!(document.title, "second");
+//! ```
+
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::size_of,
+};
+
+use rancor::Fallible;
+
+use crate::{
+    primitive::{ArchivedUsize, FixedUsize},
+    traits::{CopyOptimization, NoUndef},
+    Archive, Deserialize, DeserializeInPlace, Place, Portable, Serialize,
+};
+
+/// A typed handle to an archived `T` in a buffer.
+///
+/// An `ArchivedOffset` is just the position of the value in its buffer, so it
+/// can be stored anywhere and is itself [`Portable`]. It can be resolved
+/// against the buffer it was created from with [`ResolveOffset`]. See the
+/// [module docs](crate::offset) for an example.
+#[derive(Portable)]
+#[rkyv(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(C)]
+pub struct ArchivedOffset<T> {
+    pos: ArchivedUsize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ArchivedOffset<T> {
+    /// Creates an `ArchivedOffset` from the position of a value.
+    ///
+    /// The position is not checked until the offset is resolved. Returns
+    /// `None` if the position is too large to be archived.
+    pub fn from_pos(pos: usize) -> Option<Self> {
+        Some(Self {
+            pos: ArchivedUsize::from_native(FixedUsize::try_from(pos).ok()?),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Creates an `ArchivedOffset` to a value in the given buffer.
+    ///
+    /// Returns `None` if the value is not located entirely within the buffer.
+    pub fn new(buffer: &[u8], value: &T) -> Option<Self> {
+        let start = buffer.as_ptr() as usize;
+        let pos = (value as *const T as usize).checked_sub(start)?;
+        if pos.checked_add(size_of::<T>())? > buffer.len() {
+            return None;
+        }
+        Self::from_pos(pos)
+    }
+
+    /// Returns the position of the value in its buffer.
+    pub fn pos(&self) -> usize {
+        self.pos.to_native() as usize
+    }
+}
+
+impl<T> Clone for ArchivedOffset<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArchivedOffset<T> {}
+
+impl<T> PartialEq for ArchivedOffset<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pos == other.pos
+    }
+}
+
+impl<T> Eq for ArchivedOffset<T> {}
+
+impl<T> PartialOrd for ArchivedOffset<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for ArchivedOffset<T> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.pos().cmp(&other.pos())
+    }
+}
+
+impl<T> Hash for ArchivedOffset<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pos().hash(state);
+    }
+}
+
+impl<T> fmt::Debug for ArchivedOffset<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArchivedOffset").field(&self.pos()).finish()
+    }
+}
+
+// SAFETY: `ArchivedOffset` is a `repr(C)` struct of an `ArchivedUsize` and a
+// zero-sized `PhantomData`, so it has no padding bytes.
+unsafe impl<T> NoUndef for ArchivedOffset<T> {}
+
+impl<T> Archive for ArchivedOffset<T> {
+    const COPY_OPTIMIZATION: CopyOptimization<Self> =
+        unsafe { CopyOptimization::enable() };
+
+    type Archived = Self;
+    type Resolver = ();
+
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        out.write(*self);
+    }
+}
+
+impl<T, S: Fallible + ?Sized> Serialize<S> for ArchivedOffset<T> {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<T, D: Fallible + ?Sized> Deserialize<Self, D> for ArchivedOffset<T> {
+    fn deserialize(&self, _: &mut D) -> Result<Self, D::Error> {
+        Ok(*self)
+    }
+}
+
+impl<T, D: Fallible + ?Sized> DeserializeInPlace<Self, D>
+    for ArchivedOffset<T>
+{
+}
+
+/// Resolves [`ArchivedOffset`]s against a buffer.
+///
+/// This is implemented for byte slices, and can be called on any buffer which
+/// dereferences to one, like an [`AlignedVec`](crate::util::AlignedVec).
+pub trait ResolveOffset {
+    /// Returns an [`ArchivedOffset`] to a value in this buffer.
+    ///
+    /// Returns `None` if the value is not located entirely within the buffer.
+    fn offset_of<T>(&self, value: &T) -> Option<ArchivedOffset<T>>;
+
+    /// Resolves an offset without validating the value it points to.
+    ///
+    /// # Safety
+    ///
+    /// A valid `T` must be located at the position of the offset in this
+    /// buffer.
+    unsafe fn resolve_unchecked<T: Portable>(
+        &self,
+        offset: ArchivedOffset<T>,
+    ) -> &T;
+
+    /// Resolves an offset, validating the value it points to with the given
+    /// context.
+    ///
+    /// The offset must be in bounds and properly aligned, and the value it
+    /// points to must pass validation.
+    #[cfg(feature = "bytecheck")]
+    fn resolve_with_context<T, C, E>(
+        &self,
+        offset: ArchivedOffset<T>,
+        context: &mut C,
+    ) -> Result<&T, E>
+    where
+        T: Portable + bytecheck::CheckBytes<rancor::Strategy<C, E>>,
+        C: crate::validation::ArchiveContext<E> + ?Sized,
+        E: rancor::Source;
+
+    /// Resolves an offset, validating the value it points to.
+    ///
+    /// The offset must be in bounds and properly aligned, and the value it
+    /// points to must pass validation.
+    ///
+    /// This is part of the [high-level API](crate::api::high).
+    #[cfg(all(feature = "bytecheck", feature = "alloc"))]
+    fn resolve<T, E>(&self, offset: ArchivedOffset<T>) -> Result<&T, E>
+    where
+        T: Portable
+            + for<'a> bytecheck::CheckBytes<
+                crate::api::high::HighValidator<'a, E>,
+            >,
+        E: rancor::Source;
+}
+
+impl ResolveOffset for [u8] {
+    fn offset_of<T>(&self, value: &T) -> Option<ArchivedOffset<T>> {
+        ArchivedOffset::new(self, value)
+    }
+
+    unsafe fn resolve_unchecked<T: Portable>(
+        &self,
+        offset: ArchivedOffset<T>,
+    ) -> &T {
+        // SAFETY: The caller has guaranteed that a valid `T` is located at the
+        // position of the offset.
+        unsafe { crate::api::access_pos_unchecked::<T>(self, offset.pos()) }
+    }
+
+    #[cfg(feature = "bytecheck")]
+    fn resolve_with_context<T, C, E>(
+        &self,
+        offset: ArchivedOffset<T>,
+        context: &mut C,
+    ) -> Result<&T, E>
+    where
+        T: Portable + bytecheck::CheckBytes<rancor::Strategy<C, E>>,
+        C: crate::validation::ArchiveContext<E> + ?Sized,
+        E: rancor::Source,
+    {
+        crate::api::access_pos_with_context::<T, C, E>(
+            self,
+            offset.pos(),
+            context,
+        )
+    }
+
+    #[cfg(all(feature = "bytecheck", feature = "alloc"))]
+    fn resolve<T, E>(&self, offset: ArchivedOffset<T>) -> Result<&T, E>
+    where
+        T: Portable
+            + for<'a> bytecheck::CheckBytes<
+                crate::api::high::HighValidator<'a, E>,
+            >,
+        E: rancor::Source,
+    {
+        crate::api::high::access_pos::<T, E>(self, offset.pos())
+    }
+}
+
+#[cfg(all(test, feature = "bytecheck", feature = "alloc"))]
+mod tests {
+    use rancor::{Error, Panic};
+
+    use super::{ArchivedOffset, ResolveOffset as _};
+    use crate::{
+        alloc::{format, string::String, vec, vec::Vec},
+        to_bytes, Archive, Archived, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate)]
+    struct Entry {
+        key: u64,
+        value: String,
+    }
+
+    #[test]
+    fn resolve_offsets() {
+        let entries = (0..100)
+            .map(|i| Entry {
+                key: i,
+                value: format!("a value which is not inline {i}"),
+            })
+            .collect::<Vec<_>>();
+        let bytes = to_bytes::<Panic>(&entries).unwrap();
+        let archived =
+            crate::access::<Archived<Vec<Entry>>, Panic>(&bytes).unwrap();
+
+        let offsets = archived
+            .iter()
+            .map(|entry| bytes.offset_of(entry).unwrap())
+            .collect::<Vec<_>>();
+
+        // Offsets can be archived in side indexes
+        let index = to_bytes::<Panic>(&offsets).unwrap();
+        let index = crate::access::<
+            Archived<Vec<ArchivedOffset<ArchivedEntry>>>,
+            Panic,
+        >(&index)
+        .unwrap();
+
+        for (i, offset) in index.iter().enumerate() {
+            let entry = bytes.resolve::<_, Panic>(*offset).unwrap();
+            assert_eq!(entry.key, i as u64);
+            assert_eq!(entry.value, format!("a value which is not inline {i}"));
+            let entry = unsafe { bytes.resolve_unchecked(*offset) };
+            assert_eq!(entry.key, i as u64);
+        }
+    }
+
+    #[test]
+    fn resolve_invalid_offsets() {
+        let value = (0..10u32).collect::<Vec<_>>();
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived =
+            crate::access::<Archived<Vec<u32>>, Error>(&bytes).unwrap();
+
+        let other = 0u32;
+        assert!(bytes.offset_of(&other).is_none());
+
+        let offset = bytes.offset_of(&archived[3]).unwrap();
+        assert_eq!(*bytes.resolve::<_, Error>(offset).unwrap(), 3);
+
+        // Out of bounds
+        let offset = ArchivedOffset::<Archived<u32>>::from_pos(bytes.len());
+        assert!(bytes.resolve::<_, Error>(offset.unwrap()).is_err());
+        // Misaligned
+        #[cfg(not(feature = "unaligned"))]
+        {
+            let offset = ArchivedOffset::<Archived<u32>>::from_pos(1);
+            assert!(bytes.resolve::<_, Error>(offset.unwrap()).is_err());
+        }
+
+        // Invalid value
+        let flags = to_bytes::<Error>(&vec![0u8, 1, 2]).unwrap();
+        let archived =
+            crate::access::<Archived<Vec<u8>>, Error>(&flags).unwrap();
+        let pos = flags.offset_of(&archived[2]).unwrap().pos();
+        assert_eq!(flags[pos], 2);
+        let offset = ArchivedOffset::<bool>::from_pos(pos - 1);
+        assert!(*flags.resolve::<_, Error>(offset.unwrap()).unwrap());
+        let offset = ArchivedOffset::<bool>::from_pos(pos);
+        assert!(flags.resolve::<_, Error>(offset.unwrap()).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! Archives which own their backing storage.
+//!
+//! An [`OwnedArchive`] bundles the bytes of an archive with the type of its
+//! root. It is validated once when it is created, and can then be accessed
+//! through `Deref` without any further checks. This makes it easy to store an
+//! archived value in a struct or send it to another thread.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{owned::to_owned_archive, rancor::Error, Archive, Serialize};
+//!
+//! #[derive(Archive, Serialize)]
+//! struct Config {
+//!     name: String,
+//!     retries: u32,
+//! }
+//!
+//! let config = Config {
+//!     name: "production".to_string(),
+//!     retries: 3,
+//! };
+//! let archive = to_owned_archive::<_, Error>(&config).unwrap();
+//!
+//! let handle = std::thread::spawn(move || {
+//!     assert_eq!(archive.name, "production");
+//!     assert_eq!(archive.retries, 3);
+//! });
+//! handle.join().unwrap();
+//! ```
+
+use core::{fmt, marker::PhantomData, ops::Deref};
+
+use bytecheck::CheckBytes;
+use rancor::Source;
+
+use crate::{
+    alloc::{boxed::Box, sync::Arc, vec::Vec},
+    api::{
+        access_unchecked,
+        high::{access, HighDeserializer, HighSerializer, HighValidator},
+    },
+    ser::allocator::ArenaHandle,
+    util::AlignedVec,
+    Archive, Deserialize, Serialize,
+};
+
+/// Byte storage which always dereferences to the same bytes.
+///
+/// This is implemented for [`AlignedVec`], `Vec<u8>`, `Box<[u8]>`,
+/// `Arc<[u8]>`, and `Bytes` (with the `bytes-1` feature).
+///
+/// # Safety
+///
+/// Every call to `deref` must return a slice with the same address, length, and
+/// contents for as long as the value exists and is not mutably borrowed. In
+/// particular, moving the value must not move or change its bytes, and the
+/// bytes must not be modified through a shared reference.
+pub unsafe trait StableBytes: Deref<Target = [u8]> {}
+
+/// Byte storage whose clones dereference to the same bytes.
+///
+/// This is implemented for `Arc<[u8]>` and `Bytes` (with the `bytes-1`
+/// feature).
+///
+/// # Safety
+///
+/// Clones of the value must dereference to the same slice as the original,
+/// with the same address and length.
+pub unsafe trait CloneStableBytes: StableBytes + Clone {}
+
+// SAFETY: `AlignedVec` owns its heap allocation, which doesn't move when the
+// `AlignedVec` is moved and can only be modified through a mutable reference.
+unsafe impl<const ALIGNMENT: usize> StableBytes for AlignedVec<ALIGNMENT> {}
+
+// SAFETY: `Vec<u8>` owns its heap allocation, which doesn't move when the
+// `Vec` is moved and can only be modified through a mutable reference.
+unsafe impl StableBytes for Vec<u8> {}
+
+// SAFETY: `Box<[u8]>` owns its heap allocation, which doesn't move when the
+// `Box` is moved and can only be modified through a mutable reference.
+unsafe impl StableBytes for Box<[u8]> {}
+
+// SAFETY: The bytes of an `Arc<[u8]>` are shared between its clones and can
+// never be modified while they are shared.
+unsafe impl StableBytes for Arc<[u8]> {}
+
+// SAFETY: Clones of an `Arc<[u8]>` point to the same allocation.
+unsafe impl CloneStableBytes for Arc<[u8]> {}
+
+// SAFETY: `Bytes` is an immutable view of a shared buffer.
+#[cfg(feature = "bytes-1")]
+unsafe impl StableBytes for bytes_1::Bytes {}
+
+// SAFETY: Clones of a `Bytes` are views of the same part of the same shared
+// buffer.
+#[cfg(feature = "bytes-1")]
+unsafe impl CloneStableBytes for bytes_1::Bytes {}
+
+/// An archived `T` and the bytes which back it.
+///
+/// The bytes can be stored in any type which implements [`StableBytes`], like
+/// an [`AlignedVec`], `Box<[u8]>`, `Arc<[u8]>`, or `Bytes`. `OwnedArchive`
+/// dereferences to the archived value. It can be cloned cheaply when its bytes
+/// are reference-counted and implement [`CloneStableBytes`]. Other storage
+/// could copy the bytes to an address with a different alignment, so it can't
+/// be cloned without validating the copy again. See the
+/// [module docs](crate::owned) for an example.
+pub struct OwnedArchive<T, B = AlignedVec> {
+    bytes: B,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: Archive, B: StableBytes> OwnedArchive<T, B> {
+    /// Validates the archive in the given bytes.
+    pub fn new<E>(bytes: B) -> Result<Self, E>
+    where
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+        E: Source,
+    {
+        access::<T::Archived, E>(&bytes)?;
+        Ok(Self {
+            bytes,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Wraps the given bytes without validating them.
+    ///
+    /// # Safety
+    ///
+    /// The bytes must pass validation when passed to [`access`] as a
+    /// `T::Archived`.
+    pub unsafe fn new_unchecked(bytes: B) -> Self {
+        Self {
+            bytes,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the bytes of the archive.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consumes the `OwnedArchive` and returns its backing storage.
+    pub fn into_inner(self) -> B {
+        self.bytes
+    }
+
+    /// Deserializes the archived value.
+    pub fn deserialize<E>(&self) -> Result<T, E>
+    where
+        T::Archived: Deserialize<T, HighDeserializer<E>>,
+    {
+        crate::api::high::deserialize::<T, E>(&**self)
+    }
+}
+
+impl<T: Archive, B: StableBytes> Deref for OwnedArchive<T, B> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The bytes were validated when the `OwnedArchive` was created,
+        // and `B: StableBytes` guarantees that they haven't moved or changed.
+        unsafe { access_unchecked::<T::Archived>(&self.bytes) }
+    }
+}
+
+impl<T, B: CloneStableBytes> Clone for OwnedArchive<T, B> {
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Archive, B: StableBytes> fmt::Debug for OwnedArchive<T, B>
+where
+    T::Archived: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Serializes a value into an [`OwnedArchive`].
+///
+/// This is part of the [high-level API](crate::api::high).
+pub fn to_owned_archive<T, E>(value: &T) -> Result<OwnedArchive<T>, E>
+where
+    T: Archive
+        + for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+    T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source,
+{
+    let bytes = crate::api::high::to_bytes::<E>(value)?;
+    OwnedArchive::new(bytes)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::Arc;
+
+    use rancor::{Error, Panic};
+
+    use super::{to_owned_archive, OwnedArchive};
+    use crate::{
+        alloc::{string::String, vec, vec::Vec},
+        to_bytes, Archive, Deserialize, Serialize,
+    };
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(crate, derive(Debug))]
+    struct Message {
+        id: u64,
+        tags: Vec<String>,
+    }
+
+    fn message() -> Message {
+        Message {
+            id: 42,
+            tags: vec![String::from("a tag which is not inline"); 4],
+        }
+    }
+
+    #[test]
+    fn owned_archive() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let archive = to_owned_archive::<_, Panic>(&message()).unwrap();
+        assert_send_sync(&archive);
+        assert_eq!(archive.id, 42);
+        assert_eq!(archive.tags.len(), 4);
+        assert_eq!(archive.tags[3], "a tag which is not inline");
+        assert_eq!(archive.deserialize::<Panic>().unwrap(), message());
+    }
+
+    #[test]
+    fn owned_archive_storage() {
+        let bytes = to_bytes::<Panic>(&message()).unwrap();
+
+        let shared: Arc<[u8]> = Arc::from(bytes.as_slice());
+        let archive =
+            OwnedArchive::<Message, Arc<[u8]>>::new::<Panic>(shared).unwrap();
+        let cloned = archive.clone();
+        assert_eq!(cloned.as_bytes().as_ptr(), archive.as_bytes().as_ptr());
+        std::thread::spawn(move || assert_eq!(cloned.id, 42))
+            .join()
+            .unwrap();
+
+        let archive =
+            unsafe { OwnedArchive::<Message>::new_unchecked(bytes.clone()) };
+        assert_eq!(archive.deserialize::<Panic>().unwrap(), message());
+        assert_eq!(archive.into_inner().as_slice(), bytes.as_slice());
+    }
+
+    #[test]
+    fn owned_archive_invalid() {
+        let mut bytes = to_bytes::<Error>(&message()).unwrap();
+        let archived = crate::access::<ArchivedMessage, Error>(&bytes).unwrap();
+        let pos = archived.tags[0].as_ptr() as usize - bytes.as_ptr() as usize;
+
+        // Make the first tag invalid UTF-8
+        bytes[pos] = 0xff;
+        assert!(OwnedArchive::<Message>::new::<Error>(bytes).is_err());
+    }
+}
@@ -6,11 +6,12 @@ use munge::munge;
 use rancor::{Fallible, Source};
 
 use crate::{
-    primitive::FixedUsize,
+    primitive::{ArchivedIsize, FixedUsize},
+    rel_ptr::{Offset, RelPtr},
     seal::Seal,
     ser::{Sharing, SharingExt, Writer, WriterExt as _},
     traits::ArchivePointee,
-    ArchiveUnsized, Place, Portable, RelPtr, SerializeUnsized,
+    ArchiveUnsized, Place, Portable, SerializeUnsized,
 };
 
 /// A type marker for `ArchivedRc`.
@@ -40,7 +41,10 @@ impl Flavor for ArcFlavor {
 /// This is a thin wrapper around a [`RelPtr`] to the archived type paired with
 /// a "flavor" type. Because there may be many varieties of shared pointers and
 /// they may not be used together, the flavor helps check that memory is not
-/// being shared incorrectly during validation.
+/// being shared incorrectly during validation. The offset type `O` of the
+/// relative pointer defaults to [`ArchivedIsize`], see
+/// [`WideOffset`](crate::with::WideOffset) and
+/// [`NarrowOffset`](crate::with::NarrowOffset) for other offset types.
 #[derive(Portable)]
 #[rkyv(crate)]
 #[repr(transparent)]
@@ -49,12 +53,12 @@ impl Flavor for ArcFlavor {
     derive(bytecheck::CheckBytes),
     bytecheck(verify)
 )]
-pub struct ArchivedRc<T: ArchivePointee + ?Sized, F> {
-    ptr: RelPtr<T>,
+pub struct ArchivedRc<T: ArchivePointee + ?Sized, F, O = ArchivedIsize> {
+    ptr: RelPtr<T, O>,
     _phantom: PhantomData<F>,
 }
 
-impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
+impl<T: ArchivePointee + ?Sized, F, O: Offset> ArchivedRc<T, F, O> {
     /// Gets the value of the `ArchivedRc`.
     pub fn get(&self) -> &T {
         unsafe { &*self.ptr.as_ptr() }
@@ -84,7 +88,9 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
             ptr,
         );
     }
+}
 
+impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
     /// Serializes an archived `Rc` from a given reference.
     pub fn serialize_from_ref<U, S>(
         value: &U,
@@ -110,7 +116,7 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
     }
 }
 
-impl<T, F> AsRef<T> for ArchivedRc<T, F>
+impl<T, F, O: Offset> AsRef<T> for ArchivedRc<T, F, O>
 where
     T: ArchivePointee + ?Sized,
 {
@@ -119,7 +125,7 @@ where
     }
 }
 
-impl<T, F> Borrow<T> for ArchivedRc<T, F>
+impl<T, F, O: Offset> Borrow<T> for ArchivedRc<T, F, O>
 where
     T: ArchivePointee + ?Sized,
 {
@@ -128,7 +134,7 @@ where
     }
 }
 
-impl<T, F> fmt::Debug for ArchivedRc<T, F>
+impl<T, F, O: Offset> fmt::Debug for ArchivedRc<T, F, O>
 where
     T: ArchivePointee + fmt::Debug + ?Sized,
 {
@@ -137,7 +143,7 @@ where
     }
 }
 
-impl<T, F> Deref for ArchivedRc<T, F>
+impl<T, F, O: Offset> Deref for ArchivedRc<T, F, O>
 where
     T: ArchivePointee + ?Sized,
 {
@@ -147,7 +153,7 @@ where
     }
 }
 
-impl<T, F> fmt::Display for ArchivedRc<T, F>
+impl<T, F, O: Offset> fmt::Display for ArchivedRc<T, F, O>
 where
     T: ArchivePointee + fmt::Display + ?Sized,
 {
@@ -156,9 +162,12 @@ where
     }
 }
 
-impl<T, F> Eq for ArchivedRc<T, F> where T: ArchivePointee + Eq + ?Sized {}
+impl<T, F, O: Offset> Eq for ArchivedRc<T, F, O> where
+    T: ArchivePointee + Eq + ?Sized
+{
+}
 
-impl<T, F> hash::Hash for ArchivedRc<T, F>
+impl<T, F, O: Offset> hash::Hash for ArchivedRc<T, F, O>
 where
     T: ArchivePointee + hash::Hash + ?Sized,
 {
@@ -167,7 +176,7 @@ where
     }
 }
 
-impl<T, F> Ord for ArchivedRc<T, F>
+impl<T, F, O: Offset> Ord for ArchivedRc<T, F, O>
 where
     T: ArchivePointee + Ord + ?Sized,
 {
@@ -176,27 +185,36 @@ where
     }
 }
 
-impl<T, TF, U, UF> PartialEq<ArchivedRc<U, UF>> for ArchivedRc<T, TF>
+impl<T, TF, TO, U, UF, UO> PartialEq<ArchivedRc<U, UF, UO>>
+    for ArchivedRc<T, TF, TO>
 where
+    TO: Offset,
+    UO: Offset,
     T: ArchivePointee + PartialEq<U> + ?Sized,
     U: ArchivePointee + ?Sized,
 {
-    fn eq(&self, other: &ArchivedRc<U, UF>) -> bool {
+    fn eq(&self, other: &ArchivedRc<U, UF, UO>) -> bool {
         self.get().eq(other.get())
     }
 }
 
-impl<T, TF, U, UF> PartialOrd<ArchivedRc<U, UF>> for ArchivedRc<T, TF>
+impl<T, TF, TO, U, UF, UO> PartialOrd<ArchivedRc<U, UF, UO>>
+    for ArchivedRc<T, TF, TO>
 where
+    TO: Offset,
+    UO: Offset,
     T: ArchivePointee + PartialOrd<U> + ?Sized,
     U: ArchivePointee + ?Sized,
 {
-    fn partial_cmp(&self, other: &ArchivedRc<U, UF>) -> Option<cmp::Ordering> {
+    fn partial_cmp(
+        &self,
+        other: &ArchivedRc<U, UF, UO>,
+    ) -> Option<cmp::Ordering> {
         self.get().partial_cmp(other.get())
     }
 }
 
-impl<T, F> fmt::Pointer for ArchivedRc<T, F> {
+impl<T, F, O: Offset> fmt::Pointer for ArchivedRc<T, F, O> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Pointer::fmt(&self.ptr.base(), f)
     }
@@ -217,6 +235,12 @@ impl RcResolver {
             pos: pos as FixedUsize,
         }
     }
+
+    /// Returns the position of the serialized value.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn pos(&self) -> usize {
+        self.pos as usize
+    }
 }
 
 /// An archived `rc::Weak`.
@@ -231,7 +255,7 @@ impl RcResolver {
     bytecheck(verify)
 )]
 pub struct ArchivedRcWeak<T: ArchivePointee + ?Sized, F> {
-    ptr: RelPtr<T>,
+    ptr: RelPtr<T, ArchivedIsize>,
     _phantom: PhantomData<F>,
 }
 
@@ -323,6 +347,7 @@ mod verify {
 
     use crate::{
         rc::{ArchivedRc, ArchivedRcWeak, Flavor},
+        rel_ptr::Offset,
         traits::{ArchivePointee, LayoutRaw},
         validation::{
             shared::ValidationState, ArchiveContext, ArchiveContextExt,
@@ -341,16 +366,18 @@ mod verify {
 
     impl Error for CyclicSharedPointerError {}
 
-    unsafe impl<T, F, C> Verify<C> for ArchivedRc<T, F>
+    unsafe impl<T, F, O, C> Verify<C> for ArchivedRc<T, F, O>
     where
         T: ArchivePointee + CheckBytes<C> + LayoutRaw + ?Sized + 'static,
         T::ArchivedMetadata: CheckBytes<C>,
         F: Flavor,
+        O: Offset,
         C: Fallible + ArchiveContext + SharedContext + ?Sized,
         C::Error: Source,
     {
         fn verify(&self, context: &mut C) -> Result<(), C::Error> {
             let ptr = self.ptr.as_ptr_wrapping();
+            // Pointers with different offset types may share the same value
             let type_id = TypeId::of::<ArchivedRc<T, F>>();
 
             let addr = ptr as *const u8 as usize;
@@ -1,15 +1,37 @@
 use core::{
     alloc::Layout,
+    error::Error,
+    fmt,
     marker::PhantomData,
     mem::{align_of, size_of, ManuallyDrop},
     ptr::{slice_from_raw_parts_mut, NonNull},
 };
 
+use rancor::{fail, Source};
+
 use crate::{
     alloc::alloc::{alloc, dealloc, handle_alloc_error},
     ser::Allocator,
 };
 
+#[derive(Debug)]
+struct ArenaLimitExceeded {
+    limit: usize,
+    attempted: usize,
+}
+
+impl fmt::Display for ArenaLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "arena would grow by {} bytes, exceeding the limit of {} bytes",
+            self.attempted, self.limit,
+        )
+    }
+}
+
+impl Error for ArenaLimitExceeded {}
+
 struct Block {
     next_ptr: NonNull<Block>,
     next_size: usize,
@@ -163,16 +185,35 @@ impl Arena {
     ///
     /// The returned handle has exclusive allocation rights in the arena.
     pub fn acquire(&mut self) -> ArenaHandle<'_> {
-        self.shrink();
+        let capacity = self.shrink();
 
         ArenaHandle {
             tail_ptr: self.head_ptr,
             tail_size: unsafe { self.head_ptr.as_ref().next_size },
             used: size_of::<Block>(),
+            capacity,
             _phantom: PhantomData,
         }
     }
 
+    /// Acquires a handle to the arena which can't grow the arena's capacity
+    /// by more than `limit` bytes.
+    ///
+    /// The existing capacity of the arena can always be used, and allocations
+    /// which would need the arena to grow by more than `limit` bytes beyond it
+    /// fail instead.
+    pub fn acquire_with_limit(
+        &mut self,
+        limit: usize,
+    ) -> LimitedArenaHandle<'_> {
+        let handle = self.acquire();
+        LimitedArenaHandle {
+            base: handle.capacity,
+            handle,
+            limit,
+        }
+    }
+
     /// Consumes the `Arena`, returning a raw pointer.
     pub fn into_raw(self) -> NonNull<()> {
         let this = ManuallyDrop::new(self);
@@ -204,16 +245,24 @@ pub struct ArenaHandle<'a> {
     tail_ptr: NonNull<Block>,
     tail_size: usize,
     used: usize,
+    capacity: usize,
     _phantom: PhantomData<&'a mut Arena>,
 }
 
 // SAFETY: ArenaHandle is safe to send to other threads
 unsafe impl Send for ArenaHandle<'_> {}
 
-unsafe impl<E> Allocator<E> for ArenaHandle<'_> {
-    unsafe fn push_alloc(
+impl ArenaHandle<'_> {
+    /// Allocates within the arena, calling `block_size` with the minimum size
+    /// of a new block if the allocation doesn't fit in the current one.
+    ///
+    /// # Safety
+    ///
+    /// `layout` must have non-zero size.
+    unsafe fn push_alloc_with<E>(
         &mut self,
         layout: Layout,
+        block_size: impl FnOnce(&Self, usize) -> Result<usize, E>,
     ) -> Result<NonNull<[u8]>, E> {
         let pos = self.tail_ptr.as_ptr() as usize + self.used;
         let pad = 0usize.wrapping_sub(pos) % layout.align();
@@ -221,11 +270,13 @@ unsafe impl<E> Allocator<E> for ArenaHandle<'_> {
             self.used += pad;
         } else {
             // Allocation request is too large, allocate a new block
-            let size = usize::max(
-                2 * self.tail_size,
+            let min_size =
                 (size_of::<Block>() + layout.size() + layout.align())
-                    .next_power_of_two(),
-            );
+                    .next_power_of_two();
+            let size = block_size(self, min_size)?;
+            self.capacity =
+                self.capacity.saturating_add(size - size_of::<Block>());
+
             let next = Block::alloc(size);
             unsafe {
                 Block::push_next(self.tail_ptr, next);
@@ -248,11 +299,7 @@ unsafe impl<E> Allocator<E> for ArenaHandle<'_> {
         Ok(result)
     }
 
-    unsafe fn pop_alloc(
-        &mut self,
-        ptr: NonNull<u8>,
-        _: Layout,
-    ) -> Result<(), E> {
+    fn pop_alloc_inner(&mut self, ptr: NonNull<u8>) {
         // If the popped allocation was in the current tail block, then we can
         // reduce the amount of used space.
         let start = self.tail_ptr.as_ptr() as usize;
@@ -261,7 +308,73 @@ unsafe impl<E> Allocator<E> for ArenaHandle<'_> {
         if (start..end).contains(&pos) {
             self.used = pos - start;
         }
+    }
+}
+
+unsafe impl<E> Allocator<E> for ArenaHandle<'_> {
+    unsafe fn push_alloc(
+        &mut self,
+        layout: Layout,
+    ) -> Result<NonNull<[u8]>, E> {
+        unsafe {
+            self.push_alloc_with(layout, |this, min_size| {
+                Ok(usize::max(2 * this.tail_size, min_size))
+            })
+        }
+    }
+
+    unsafe fn pop_alloc(
+        &mut self,
+        ptr: NonNull<u8>,
+        _: Layout,
+    ) -> Result<(), E> {
+        self.pop_alloc_inner(ptr);
+        Ok(())
+    }
+}
+
+/// A handle which can allocate within an arena, up to a limit on how much the
+/// arena's capacity can grow.
+///
+/// This is returned from [`Arena::acquire_with_limit`].
+pub struct LimitedArenaHandle<'a> {
+    handle: ArenaHandle<'a>,
+    base: usize,
+    limit: usize,
+}
+
+unsafe impl<E: Source> Allocator<E> for LimitedArenaHandle<'_> {
+    unsafe fn push_alloc(
+        &mut self,
+        layout: Layout,
+    ) -> Result<NonNull<[u8]>, E> {
+        let (base, limit) = (self.base, self.limit);
+        unsafe {
+            self.handle.push_alloc_with(layout, |this, min_size| {
+                let growth = |size: usize| {
+                    (this.capacity - base)
+                        .saturating_add(size - size_of::<Block>())
+                };
+                let mut size = usize::max(2 * this.tail_size, min_size);
+                if growth(size) > limit {
+                    // Fall back to the smallest block that fits the allocation
+                    size = min_size;
+                }
+                let attempted = growth(size);
+                if attempted > limit {
+                    fail!(ArenaLimitExceeded { limit, attempted });
+                }
+                Ok(size)
+            })
+        }
+    }
 
+    unsafe fn pop_alloc(
+        &mut self,
+        ptr: NonNull<u8>,
+        _: Layout,
+    ) -> Result<(), E> {
+        self.handle.pop_alloc_inner(ptr);
         Ok(())
     }
 }
@@ -270,7 +383,7 @@ unsafe impl<E> Allocator<E> for ArenaHandle<'_> {
 mod tests {
     use core::alloc::Layout;
 
-    use rancor::{Panic, ResultExt};
+    use rancor::{Failure, Panic, ResultExt};
 
     use crate::{
         alloc::{string::ToString, vec},
@@ -301,6 +414,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn arena_limit() {
+        let mut arena = Arena::new();
+        let capacity = arena.capacity();
+        let mut handle = arena.acquire_with_limit(4 * capacity);
+
+        let small = Layout::from_size_align(capacity, 1).unwrap();
+        let large = Layout::from_size_align(4 * capacity, 1).unwrap();
+
+        unsafe {
+            Allocator::<Panic>::push_alloc(&mut handle, small).always_ok();
+            Allocator::<Panic>::push_alloc(&mut handle, small).always_ok();
+            assert!(
+                Allocator::<Failure>::push_alloc(&mut handle, large).is_err()
+            );
+        }
+    }
+
     #[test]
     fn pop_non_tail() {
         let mut arena = Arena::new();
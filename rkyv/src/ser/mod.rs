@@ -4,12 +4,12 @@ pub mod allocator;
 pub mod sharing;
 pub mod writer;
 
-use ::core::{alloc::Layout, ptr::NonNull};
+use ::core::{alloc::Layout, any::TypeId, ptr::NonNull};
 
 #[doc(inline)]
 pub use self::{
     allocator::Allocator,
-    sharing::{Sharing, SharingExt},
+    sharing::{Interning, InterningExt, Sharing, SharingExt},
     writer::{Positional, Writer, WriterExt},
 };
 
@@ -22,6 +22,7 @@ pub struct Serializer<W, A, S> {
     pub allocator: A,
     /// The pointer sharing of the serializer.
     pub sharing: S,
+    canonical: bool,
 }
 
 impl<W, A, S> Serializer<W, A, S> {
@@ -31,9 +32,47 @@ impl<W, A, S> Serializer<W, A, S> {
             writer,
             allocator,
             sharing,
+            canonical: false,
         }
     }
 
+    /// Creates a new canonical serializer from a writer, allocator, and pointer
+    /// sharing.
+    ///
+    /// A canonical serializer always serializes equal values to identical
+    /// bytes:
+    ///
+    /// - Hash maps and hash sets insert their entries in the order of their
+    ///   hashes, so the layout of the archived table doesn't depend on the
+    ///   iteration order of the value. Entries with equal hashes are ordered by
+    ///   the bytes their keys write to a hasher. Index maps and sets keep their
+    ///   insertion order, which is part of their archived value.
+    /// - Subtrees are laid out in the order that they are serialized, which is
+    ///   the same for equal values. Shared pointers are laid out the first time
+    ///   they are serialized. Wrappers that serialize their fields into a
+    ///   separate archive, like [`Compressed`](crate::with::Compressed),
+    ///   serialize them canonically too.
+    /// - Padding is always zeroed. This is true whether or not the serializer
+    ///   is canonical.
+    ///
+    /// [`Keyed`](crate::with::Keyed) hashes with a fixed seed by default.
+    /// Custom [`SeedSource`](crate::hash::SeedSource)s must also return a
+    /// fixed seed for the archive to be canonical.
+    pub fn new_canonical(writer: W, allocator: A, sharing: S) -> Self {
+        Self {
+            canonical: true,
+            ..Self::new(writer, allocator, sharing)
+        }
+    }
+
+    /// Returns whether the serializer produces canonical archives.
+    ///
+    /// See [`new_canonical`](Self::new_canonical) for the guarantees of
+    /// canonical serializers.
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
     /// Consumes the serializer and returns the components.
     pub fn into_raw_parts(self) -> (W, A, S) {
         (self.writer, self.allocator, self.sharing)
@@ -57,6 +96,10 @@ impl<W: Writer<E>, A, S, E> Writer<E> for Serializer<W, A, S> {
     fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
         self.writer.write(bytes)
     }
+
+    fn is_canonical(&self) -> bool {
+        self.canonical
+    }
 }
 
 unsafe impl<W, A: Allocator<E>, S, E> Allocator<E> for Serializer<W, A, S> {
@@ -89,3 +132,13 @@ impl<W, A, S: Sharing<E>, E> Sharing<E> for Serializer<W, A, S> {
         self.sharing.finish_sharing(address, pos)
     }
 }
+
+impl<W, A, S: Interning> Interning for Serializer<W, A, S> {
+    fn get_interned(&self, type_id: TypeId, bytes: &[u8]) -> Option<usize> {
+        self.sharing.get_interned(type_id, bytes)
+    }
+
+    fn add_interned(&mut self, type_id: TypeId, bytes: &[u8], pos: usize) {
+        self.sharing.add_interned(type_id, bytes, pos)
+    }
+}
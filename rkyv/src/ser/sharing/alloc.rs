@@ -1,19 +1,35 @@
-use core::{error::Error, fmt, hash::BuildHasherDefault};
+use core::{
+    any::TypeId,
+    error::Error,
+    fmt,
+    hash::{BuildHasherDefault, Hash, Hasher},
+};
 
-use hashbrown::hash_map::{Entry, HashMap};
+use hashbrown::{
+    hash_map::{Entry, HashMap},
+    HashTable,
+};
 use rancor::{fail, Source};
 
 use crate::{
+    alloc::boxed::Box,
     hash::FxHasher64,
-    ser::{sharing::SharingState, Sharing},
+    ser::{
+        sharing::{Interning, SharingState},
+        Sharing,
+    },
 };
 
 /// A shared pointer strategy that shares serializations of the same shared
 /// pointer.
+///
+/// `Share` also shares serializations of values with the same contents when
+/// they are [interned](crate::with::Intern).
 #[derive(Debug, Default)]
 pub struct Share {
     shared_address_to_pos:
         HashMap<usize, Option<usize>, BuildHasherDefault<FxHasher64>>,
+    interned: HashTable<(TypeId, Box<[u8]>, usize)>,
 }
 
 impl Share {
@@ -31,15 +47,30 @@ impl Share {
                 capacity,
                 Default::default(),
             ),
+            interned: HashTable::new(),
         }
     }
 
     /// Clears the shared pointer unifier for reuse.
     pub fn clear(&mut self) {
         self.shared_address_to_pos.clear();
+        self.interned.clear();
+    }
+
+    /// Returns the addresses of every shared pointer that was started.
+    #[cfg(feature = "rayon-1")]
+    pub(crate) fn shared_addresses(&self) -> impl Iterator<Item = usize> + '_ {
+        self.shared_address_to_pos.keys().copied()
     }
 }
 
+fn hash_interned(type_id: TypeId, bytes: &[u8]) -> u64 {
+    let mut hasher = FxHasher64::default();
+    type_id.hash(&mut hasher);
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
 #[derive(Debug)]
 struct NotStarted;
 
@@ -94,3 +125,21 @@ impl<E: Source> Sharing<E> for Share {
         }
     }
 }
+
+impl Interning for Share {
+    fn get_interned(&self, type_id: TypeId, bytes: &[u8]) -> Option<usize> {
+        self.interned
+            .find(hash_interned(type_id, bytes), |(id, b, _)| {
+                *id == type_id && **b == *bytes
+            })
+            .map(|(_, _, pos)| *pos)
+    }
+
+    fn add_interned(&mut self, type_id: TypeId, bytes: &[u8], pos: usize) {
+        self.interned.insert_unique(
+            hash_interned(type_id, bytes),
+            (type_id, bytes.into(), pos),
+            |(id, b, _)| hash_interned(*id, b),
+        );
+    }
+}
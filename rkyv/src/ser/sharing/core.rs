@@ -1,7 +1,15 @@
-use crate::ser::{sharing::SharingState, Sharing};
+use core::any::TypeId;
+
+use crate::ser::{
+    sharing::{Interning, SharingState},
+    Sharing,
+};
 
 /// A shared pointer strategy that duplicates serializations of the same shared
 /// pointer.
+///
+/// `Unshare` also duplicates values which are
+/// [interned](crate::with::Intern).
 #[derive(Debug, Default)]
 pub struct Unshare;
 
@@ -14,3 +22,11 @@ impl<E> Sharing<E> for Unshare {
         Ok(())
     }
 }
+
+impl Interning for Unshare {
+    fn get_interned(&self, _: TypeId, _: &[u8]) -> Option<usize> {
+        None
+    }
+
+    fn add_interned(&mut self, _: TypeId, _: &[u8], _: usize) {}
+}
@@ -4,7 +4,7 @@
 mod alloc;
 mod core;
 
-use ::core::{error::Error, fmt};
+use ::core::{any::TypeId, error::Error, fmt, mem::size_of_val, slice};
 use rancor::{fail, Fallible, Source, Strategy};
 
 #[cfg(feature = "alloc")]
@@ -110,3 +110,104 @@ pub trait SharingExt<E>: Sharing<E> {
 }
 
 impl<S, E> SharingExt<E> for S where S: Sharing<E> + ?Sized {}
+
+/// A type whose contents can be deduplicated during serialization.
+///
+/// Two values of the same type with the same
+/// [`intern_bytes`](Internable::intern_bytes) must serialize to the same bytes.
+pub trait Internable: 'static {
+    /// Returns the bytes which identify the contents of the value.
+    fn intern_bytes(&self) -> &[u8];
+}
+
+impl Internable for str {
+    fn intern_bytes(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+macro_rules! impl_internable_slice {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Internable for [$ty] {
+                fn intern_bytes(&self) -> &[u8] {
+                    // SAFETY: Primitives have no padding or uninitialized
+                    // bytes, so their slices can be viewed as bytes.
+                    unsafe {
+                        slice::from_raw_parts(
+                            self.as_ptr().cast(),
+                            size_of_val(self),
+                        )
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_internable_slice!(
+    u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64, char,
+);
+
+/// A content-based deduplication strategy.
+///
+/// This trait is required to serialize values with
+/// [`Intern`](crate::with::Intern).
+pub trait Interning {
+    /// Returns the position of a previously serialized value with the given
+    /// type and contents, if any.
+    fn get_interned(&self, type_id: TypeId, bytes: &[u8]) -> Option<usize>;
+
+    /// Records the position of a serialized value with the given type and
+    /// contents.
+    fn add_interned(&mut self, type_id: TypeId, bytes: &[u8], pos: usize);
+}
+
+impl<T> Interning for &mut T
+where
+    T: Interning + ?Sized,
+{
+    fn get_interned(&self, type_id: TypeId, bytes: &[u8]) -> Option<usize> {
+        T::get_interned(*self, type_id, bytes)
+    }
+
+    fn add_interned(&mut self, type_id: TypeId, bytes: &[u8], pos: usize) {
+        T::add_interned(*self, type_id, bytes, pos)
+    }
+}
+
+impl<T, E> Interning for Strategy<T, E>
+where
+    T: Interning + ?Sized,
+{
+    fn get_interned(&self, type_id: TypeId, bytes: &[u8]) -> Option<usize> {
+        T::get_interned(self, type_id, bytes)
+    }
+
+    fn add_interned(&mut self, type_id: TypeId, bytes: &[u8], pos: usize) {
+        T::add_interned(self, type_id, bytes, pos)
+    }
+}
+
+/// Helper methods for [`Interning`].
+pub trait InterningExt: Interning + Fallible {
+    /// Serializes the given value and returns its position. If an equal value
+    /// has already been interned then it returns the position of that value
+    /// instead.
+    fn serialize_interned<T: Internable + SerializeUnsized<Self> + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<usize, Self::Error> {
+        let type_id = TypeId::of::<T>();
+        let bytes = value.intern_bytes();
+        if let Some(pos) = self.get_interned(type_id, bytes) {
+            return Ok(pos);
+        }
+
+        let pos = value.serialize_unsized(self)?;
+        self.add_interned(type_id, bytes, pos);
+        Ok(pos)
+    }
+}
+
+impl<S> InterningExt for S where S: Interning + Fallible + ?Sized {}
@@ -0,0 +1,93 @@
+use crate::{
+    checksum::{trailer, Crc32c},
+    ser::{Positional, Writer},
+};
+
+/// A writer adapter which computes a checksum of the bytes written and can
+/// append it as a trailer.
+///
+/// Call [`finish`](ChecksumWriter::finish) after serializing to write the
+/// trailer and get the underlying writer back. The trailer covers the bytes
+/// written through the adapter, so the underlying writer should start empty.
+/// Archives with a trailer can be verified and accessed with
+/// [`access_checksummed`](crate::api::high::access_checksummed).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{access_checksummed, to_bytes_in},
+///     rancor::Error,
+///     ser::writer::ChecksumWriter,
+///     util::AlignedVec,
+///     Archived,
+/// };
+///
+/// let value = vec![1u32, 2, 3];
+///
+/// let writer = ChecksumWriter::new(AlignedVec::<16>::new());
+/// let writer = to_bytes_in::<_, Error>(&value, writer).unwrap();
+/// let bytes = writer.finish::<Error>().unwrap();
+///
+/// let archived =
+///     access_checksummed::<Archived<Vec<u32>>, Error>(&bytes).unwrap();
+/// assert_eq!(archived, &[1, 2, 3]);
+/// ```
+#[derive(Debug)]
+pub struct ChecksumWriter<W> {
+    inner: W,
+    crc: Crc32c,
+}
+
+impl<W> ChecksumWriter<W> {
+    /// Creates a new `ChecksumWriter` which writes to the given writer.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            crc: Crc32c::new(),
+        }
+    }
+
+    /// Returns the checksum of the bytes written so far.
+    pub fn checksum(&self) -> u32 {
+        self.crc.finish()
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Consumes the `ChecksumWriter` and returns the underlying writer without
+    /// writing a trailer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Writes the checksum trailer and returns the underlying writer.
+    pub fn finish<E>(mut self) -> Result<W, E>
+    where
+        W: Writer<E>,
+    {
+        self.inner.write(&trailer(self.checksum()))?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Positional> Positional for ChecksumWriter<W> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.inner.pos()
+    }
+}
+
+impl<W: Writer<E>, E> Writer<E> for ChecksumWriter<W> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.crc.update(bytes);
+        self.inner.write(bytes)
+    }
+
+    fn is_canonical(&self) -> bool {
+        self.inner.is_canonical()
+    }
+}
@@ -1,6 +1,4 @@
 use core::{
-    error::Error,
-    fmt,
     marker::PhantomData,
     mem::MaybeUninit,
     ops::{Deref, DerefMut},
@@ -10,32 +8,13 @@ use core::{
 
 use rancor::{fail, Source};
 
-use crate::ser::{Positional, Writer};
-
-#[derive(Debug)]
-struct BufferOverflow {
-    write_len: usize,
-    cap: usize,
-    len: usize,
-}
-
-impl fmt::Display for BufferOverflow {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "overflowed buffer while writing {} bytes into buffer of length \
-             {} (capacity is {})",
-            self.write_len, self.len, self.cap,
-        )
-    }
-}
-
-impl Error for BufferOverflow {}
+use crate::ser::{writer::ArchiveTooLarge, Positional, Writer};
 
 /// Wraps a byte buffer and equips it with [`Writer`].
 ///
 /// Common uses include archiving in `#![no_std]` environments and archiving
-/// small objects without allocating.
+/// small objects without allocating. Writing past the end of the buffer fails
+/// with [`ArchiveTooLarge`].
 ///
 /// # Examples
 ///
@@ -147,10 +126,9 @@ impl Positional for Buffer<'_> {
 impl<E: Source> Writer<E> for Buffer<'_> {
     fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
         if bytes.len() > self.cap - self.len {
-            fail!(BufferOverflow {
-                write_len: bytes.len(),
-                cap: self.cap,
-                len: self.len,
+            fail!(ArchiveTooLarge {
+                limit: self.cap,
+                attempted: self.len + bytes.len(),
             });
         } else {
             unsafe {
@@ -166,6 +144,62 @@ impl<E: Source> Writer<E> for Buffer<'_> {
     }
 }
 
+/// A writer that discards its output and only counts the number of bytes
+/// written.
+///
+/// Serializing with a `SizeCounter` writes the same bytes, including alignment
+/// padding, as any other writer would. Its final position is the exact size of
+/// the archive, so a second pass into a [`Buffer`] of that length always fits.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::to_bytes_in, rancor::Error, ser::writer::SizeCounter,
+///     to_bytes,
+/// };
+///
+/// let value = vec!["hello".to_string(), "world".to_string()];
+/// let counter = to_bytes_in::<_, Error>(&value, SizeCounter::new()).unwrap();
+/// assert_eq!(counter.len(), to_bytes::<Error>(&value).unwrap().len());
+/// ```
+#[derive(Debug, Default)]
+pub struct SizeCounter {
+    len: usize,
+}
+
+impl SizeCounter {
+    /// Returns a new `SizeCounter` at position 0.
+    pub fn new() -> Self {
+        Self { len: 0 }
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether no bytes have been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Positional for SizeCounter {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.len
+    }
+}
+
+impl<E> Writer<E> for SizeCounter {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.len += bytes.len();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use core::mem::MaybeUninit;
@@ -0,0 +1,111 @@
+use core::{error::Error, fmt};
+
+use rancor::{fail, Source};
+
+use crate::ser::{Positional, Writer};
+
+/// The error returned when an archive would grow past a size limit.
+///
+/// This is returned by [`LimitWriter`] and [`Buffer`](super::Buffer), and can
+/// be recovered from a [`rancor::Error`] by downcasting its
+/// [`inner`](rancor::Error::inner) error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArchiveTooLarge {
+    /// The maximum size of the archive in bytes.
+    pub limit: usize,
+    /// The size the archive would have had after the failed write.
+    pub attempted: usize,
+}
+
+impl fmt::Display for ArchiveTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archive of at least {} bytes exceeded the limit of {} bytes",
+            self.attempted, self.limit,
+        )
+    }
+}
+
+impl Error for ArchiveTooLarge {}
+
+/// A writer adapter which fails with [`ArchiveTooLarge`] instead of writing
+/// past a byte limit.
+///
+/// The limit applies to the position of the underlying writer, so the
+/// underlying writer should usually start empty. Writes which would exceed the
+/// limit fail before any of their bytes are written.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::to_bytes_in,
+///     rancor::Error,
+///     ser::writer::{ArchiveTooLarge, LimitWriter},
+///     util::AlignedVec,
+/// };
+///
+/// let value = vec![0u8; 1000];
+///
+/// let writer = LimitWriter::new(AlignedVec::<16>::new(), 2048);
+/// let writer = to_bytes_in::<_, Error>(&value, writer).unwrap();
+/// assert!(writer.get_ref().len() <= 2048);
+///
+/// let writer = LimitWriter::new(AlignedVec::<16>::new(), 256);
+/// let error = to_bytes_in::<_, Error>(&value, writer).unwrap_err();
+/// let too_large = Error::inner(&error).downcast_ref::<ArchiveTooLarge>();
+/// assert_eq!(too_large.unwrap().limit, 256);
+/// ```
+#[derive(Debug)]
+pub struct LimitWriter<W> {
+    inner: W,
+    limit: usize,
+}
+
+impl<W> LimitWriter<W> {
+    /// Creates a new `LimitWriter` which writes at most `limit` bytes to the
+    /// given writer.
+    pub fn new(inner: W, limit: usize) -> Self {
+        Self { inner, limit }
+    }
+
+    /// Returns the maximum position of the underlying writer.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Consumes the `LimitWriter` and returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Positional> Positional for LimitWriter<W> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.inner.pos()
+    }
+}
+
+impl<W: Writer<E>, E: Source> Writer<E> for LimitWriter<W> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        let attempted = self.inner.pos().saturating_add(bytes.len());
+        if attempted > self.limit {
+            fail!(ArchiveTooLarge {
+                limit: self.limit,
+                attempted,
+            });
+        }
+        self.inner.write(bytes)
+    }
+
+    fn is_canonical(&self) -> bool {
+        self.inner.is_canonical()
+    }
+}
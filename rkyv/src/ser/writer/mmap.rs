@@ -0,0 +1,228 @@
+use std::{fs::File, io};
+
+use memmap2_0_9::{Mmap, MmapMut, MmapOptions};
+use rancor::{ResultExt as _, Source};
+
+use crate::ser::{Positional, Writer};
+
+/// A writer that serializes directly into a memory-mapped file.
+///
+/// The file is grown in steps of at least
+/// [`growth_step`](MmapWriter::growth_step) bytes as data is written, and
+/// remapped after each step. Call [`finish`](MmapWriter::finish) when done to
+/// truncate the file to the length of the archive and get a read-only mapping
+/// of it. If the writer is dropped without finishing, the file is left at its
+/// grown length.
+///
+/// # Example
+///
+/// ```
+/// use std::fs::OpenOptions;
+///
+/// use rkyv::{
+///     api::high::to_bytes_in, rancor::Error, ser::writer::MmapWriter,
+///     Archived,
+/// };
+///
+/// let path = std::env::temp_dir().join("rkyv_mmap_writer_example.bin");
+/// let file = OpenOptions::new()
+///     .read(true)
+///     .write(true)
+///     .create(true)
+///     .truncate(true)
+///     .open(&path)
+///     .unwrap();
+///
+/// let value = vec!["hello".to_string(), "world".to_string()];
+/// // SAFETY: No other process modifies the file while it is mapped.
+/// let writer = unsafe { MmapWriter::new(file) };
+/// let writer = to_bytes_in::<_, Error>(&value, writer).unwrap();
+/// let mmap = writer.finish().unwrap();
+///
+/// let archived = rkyv::access::<Archived<Vec<String>>, Error>(&mmap).unwrap();
+/// assert_eq!(archived[1], "world");
+/// # drop(mmap);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct MmapWriter {
+    file: File,
+    map: Option<MmapMut>,
+    len: usize,
+    growth_step: usize,
+}
+
+impl MmapWriter {
+    /// The default number of bytes to grow the file by: 64 MiB.
+    pub const DEFAULT_GROWTH_STEP: usize = 64 * 1024 * 1024;
+
+    /// Creates a new `MmapWriter` which writes to the start of the given file.
+    ///
+    /// The file must be opened for both reading and writing. Any existing
+    /// contents are overwritten.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated by any other process or
+    /// handle until the writer is finished or dropped.
+    pub unsafe fn new(file: File) -> Self {
+        // SAFETY: The caller has guaranteed that the file will not be modified
+        // while it is mapped.
+        unsafe { Self::with_growth_step(file, Self::DEFAULT_GROWTH_STEP) }
+    }
+
+    /// Creates a new `MmapWriter` which grows the file in multiples of the
+    /// given step.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated by any other process or
+    /// handle until the writer is finished or dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `growth_step` is zero.
+    pub unsafe fn with_growth_step(file: File, growth_step: usize) -> Self {
+        assert!(growth_step > 0, "growth step must be greater than zero");
+
+        Self {
+            file,
+            map: None,
+            len: 0,
+            growth_step,
+        }
+    }
+
+    /// Returns the number of bytes the file is grown by.
+    pub fn growth_step(&self) -> usize {
+        self.growth_step
+    }
+
+    /// Returns the number of bytes written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether no bytes have been written.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the current length of the mapped file.
+    pub fn capacity(&self) -> usize {
+        self.map.as_ref().map_or(0, |map| map.len())
+    }
+
+    /// Returns the bytes written so far.
+    pub fn as_slice(&self) -> &[u8] {
+        match self.map {
+            Some(ref map) => &map[..self.len],
+            None => &[],
+        }
+    }
+
+    fn grow(&mut self, min_capacity: usize) -> io::Result<()> {
+        let steps = min_capacity.div_ceil(self.growth_step);
+        let capacity = steps
+            .checked_mul(self.growth_step)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::OutOfMemory))?;
+
+        // Some platforms can't resize a file while it is mapped.
+        self.map = None;
+        self.file.set_len(capacity as u64)?;
+        // SAFETY: The caller of `new` guaranteed that the file will not be
+        // modified while it is mapped.
+        self.map = Some(unsafe {
+            MmapOptions::new().len(capacity).map_mut(&self.file)?
+        });
+
+        Ok(())
+    }
+
+    /// Truncates the file to the written length and returns a read-only
+    /// mapping of it.
+    pub fn finish(mut self) -> io::Result<Mmap> {
+        self.map = None;
+        self.file.set_len(self.len as u64)?;
+        // SAFETY: The caller of `new` guaranteed that the file will not be
+        // modified while it is mapped.
+        unsafe { MmapOptions::new().len(self.len).map(&self.file) }
+    }
+}
+
+impl Positional for MmapWriter {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.len
+    }
+}
+
+impl<E: Source> Writer<E> for MmapWriter {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        let end = self
+            .len
+            .checked_add(bytes.len())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::OutOfMemory))
+            .into_error()?;
+        if end > self.capacity() {
+            self.grow(end).into_error()?;
+        }
+
+        let map = self.map.as_mut().unwrap();
+        map[self.len..end].copy_from_slice(bytes);
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, OpenOptions};
+
+    use rancor::Panic;
+
+    use crate::{
+        access, alloc::string::ToString, api::high::to_bytes_in,
+        ser::writer::MmapWriter, vec::ArchivedVec, Archived,
+    };
+
+    #[test]
+    fn mmap_writer_grows_and_truncates() {
+        let path = std::env::temp_dir()
+            .join(format!("rkyv_mmap_writer_{}.bin", std::process::id()));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+
+        let value = (0..100).map(|i| i.to_string()).collect::<Vec<_>>();
+        let writer = unsafe { MmapWriter::with_growth_step(file, 64) };
+        let writer = to_bytes_in::<_, Panic>(&value, writer).unwrap();
+        assert_eq!(writer.capacity() % 64, 0);
+        assert!(writer.capacity() >= writer.len());
+        assert_eq!(
+            writer.as_slice(),
+            &*crate::to_bytes::<Panic>(&value).unwrap()
+        );
+
+        let len = writer.len();
+        let mmap = writer.finish().unwrap();
+        assert_eq!(mmap.len(), len);
+        assert_eq!(fs::metadata(&path).unwrap().len(), len as u64);
+
+        let archived =
+            access::<ArchivedVec<Archived<String>>, Panic>(&mmap).unwrap();
+        assert_eq!(archived.len(), 100);
+        assert_eq!(archived[42], "42");
+
+        drop(mmap);
+        fs::remove_file(&path).unwrap();
+    }
+}
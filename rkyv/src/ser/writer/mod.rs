@@ -2,16 +2,26 @@
 
 #[cfg(feature = "alloc")]
 mod alloc;
+mod checksum;
 mod core;
+mod limit;
+#[cfg(feature = "memmap2-0_9")]
+mod mmap;
 #[cfg(feature = "std")]
 mod std;
+#[cfg(feature = "tokio-1")]
+mod tokio;
 
 use ::core::mem;
 use rancor::{Fallible, Strategy};
 
-pub use self::core::*;
+#[cfg(feature = "memmap2-0_9")]
+pub use self::mmap::*;
 #[cfg(feature = "std")]
 pub use self::std::*;
+#[cfg(feature = "tokio-1")]
+pub use self::tokio::*;
+pub use self::{checksum::*, core::*, limit::*};
 use crate::{Archive, ArchiveUnsized, Place, RelPtr};
 
 /// A writer that knows its current position.
@@ -59,6 +69,17 @@ where
 pub trait Writer<E = <Self as Fallible>::Error>: Positional {
     /// Attempts to write the given bytes to the serializer.
     fn write(&mut self, bytes: &[u8]) -> Result<(), E>;
+
+    /// Returns whether values should be serialized canonically.
+    ///
+    /// Values which have more than one valid archived layout, like hash maps,
+    /// check this to choose a layout that depends only on their contents. See
+    /// [`Serializer::new_canonical`](crate::ser::Serializer::new_canonical)
+    /// for more details.
+    #[inline]
+    fn is_canonical(&self) -> bool {
+        false
+    }
 }
 
 impl<T, E> Writer<E> for &mut T
@@ -68,6 +89,10 @@ where
     fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
         T::write(*self, bytes)
     }
+
+    fn is_canonical(&self) -> bool {
+        T::is_canonical(*self)
+    }
 }
 
 impl<T, E> Writer<E> for Strategy<T, E>
@@ -77,6 +102,10 @@ where
     fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
         T::write(self, bytes)
     }
+
+    fn is_canonical(&self) -> bool {
+        T::is_canonical(self)
+    }
 }
 
 /// Helper methods for [`Writer`].
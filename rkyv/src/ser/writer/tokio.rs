@@ -0,0 +1,221 @@
+use std::io;
+
+use tokio_1::io::{AsyncWrite, AsyncWriteExt as _};
+
+use crate::{
+    alloc::{collections::VecDeque, vec::Vec},
+    ser::{Positional, Writer},
+};
+
+/// A writer that collects bytes into reusable chunks which can be written to
+/// an [`AsyncWrite`].
+///
+/// Serialization is synchronous, so the archive is first written into chunks
+/// of [`chunk_size`](ChunkedWriter::chunk_size) bytes. The chunks are then
+/// written out with [`write_to`](ChunkedWriter::write_to), which keeps their
+/// allocations around for the next archive. Unlike an `AlignedVec`, the
+/// archive is never copied into a single contiguous buffer.
+///
+/// `ChunkedWriter` composes with any allocator and sharing strategy through
+/// [`Serializer`](crate::ser::Serializer). See
+/// [`to_async_writer`](crate::api::high::to_async_writer) for a high-level
+/// API.
+///
+/// # Example
+///
+/// ```
+/// # tokio_1::runtime::Builder::new_current_thread().build().unwrap()
+/// #     .block_on(async {
+/// use rkyv::{
+///     api::high::to_bytes_in, rancor::Error, ser::writer::ChunkedWriter,
+/// };
+///
+/// let mut output = Vec::new();
+/// let value = vec!["hello".to_string(), "world".to_string()];
+///
+/// let mut writer =
+///     to_bytes_in::<_, Error>(&value, ChunkedWriter::with_chunk_size(8))
+///         .unwrap();
+/// assert!(writer.chunks().count() > 1);
+/// writer.write_to(&mut output).await.unwrap();
+///
+/// assert_eq!(output, rkyv::to_bytes::<Error>(&value).unwrap().as_slice());
+/// assert!(writer.is_empty());
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct ChunkedWriter {
+    chunks: VecDeque<Vec<u8>>,
+    spare: Vec<Vec<u8>>,
+    chunk_size: usize,
+    pos: usize,
+}
+
+impl Default for ChunkedWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChunkedWriter {
+    /// The default size of each chunk: 64 KiB.
+    pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+    /// Creates a new `ChunkedWriter` with the default chunk size.
+    pub fn new() -> Self {
+        Self::with_chunk_size(Self::DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates a new `ChunkedWriter` with the given chunk size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be greater than zero");
+
+        Self {
+            chunks: VecDeque::new(),
+            spare: Vec::new(),
+            chunk_size,
+            pos: 0,
+        }
+    }
+
+    /// Returns the size of each chunk.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns the number of bytes written and not yet written out.
+    pub fn len(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns whether there are no bytes waiting to be written out.
+    pub fn is_empty(&self) -> bool {
+        self.pos == 0
+    }
+
+    /// Returns an iterator over the chunks which have been written.
+    pub fn chunks(&self) -> impl Iterator<Item = &[u8]> {
+        self.chunks.iter().map(|chunk| chunk.as_slice())
+    }
+
+    /// Writes all of the chunks to the given writer and resets the position to
+    /// zero.
+    ///
+    /// The chunks are kept for reuse. The writer is not flushed.
+    pub async fn write_to<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        while let Some(chunk) = self.chunks.front() {
+            writer.write_all(chunk).await?;
+            let mut chunk = self.chunks.pop_front().unwrap();
+            self.pos -= chunk.len();
+            chunk.clear();
+            self.spare.push(chunk);
+        }
+        Ok(())
+    }
+
+    /// Discards all written bytes and resets the position to zero.
+    ///
+    /// The chunks are kept for reuse.
+    pub fn clear(&mut self) {
+        for mut chunk in self.chunks.drain(..) {
+            chunk.clear();
+            self.spare.push(chunk);
+        }
+        self.pos = 0;
+    }
+
+    fn push_chunk(&mut self) {
+        let chunk = self
+            .spare
+            .pop()
+            .unwrap_or_else(|| Vec::with_capacity(self.chunk_size));
+        self.chunks.push_back(chunk);
+    }
+}
+
+impl Positional for ChunkedWriter {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<E> Writer<E> for ChunkedWriter {
+    fn write(&mut self, mut bytes: &[u8]) -> Result<(), E> {
+        self.pos += bytes.len();
+
+        while !bytes.is_empty() {
+            let chunk_size = self.chunk_size;
+            if self.chunks.back().map_or(true, |c| c.len() == chunk_size) {
+                self.push_chunk();
+            }
+            let chunk = self.chunks.back_mut().unwrap();
+            let len = bytes.len().min(chunk_size - chunk.len());
+            chunk.extend_from_slice(&bytes[..len]);
+            bytes = &bytes[len..];
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rancor::Panic;
+
+    use crate::{
+        alloc::{string::ToString, vec::Vec},
+        api::high::{to_async_writer, to_bytes_in},
+        ser::writer::ChunkedWriter,
+        to_bytes,
+    };
+
+    fn block_on<F: core::future::Future>(future: F) -> F::Output {
+        tokio_1::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn chunked_writer_reuses_chunks() {
+        let value = (0..50).map(|i| i.to_string()).collect::<Vec<_>>();
+        let expected = to_bytes::<Panic>(&value).unwrap();
+
+        let mut writer =
+            to_bytes_in::<_, Panic>(&value, ChunkedWriter::with_chunk_size(7))
+                .unwrap();
+        assert_eq!(writer.len(), expected.len());
+        assert!(writer.chunks().all(|chunk| chunk.len() <= 7));
+
+        let mut output = Vec::new();
+        block_on(writer.write_to(&mut output)).unwrap();
+        assert_eq!(output, expected.as_slice());
+        assert!(writer.is_empty());
+        assert_eq!(writer.chunks().count(), 0);
+
+        let spare = writer.spare.len();
+        let mut writer = to_bytes_in::<_, Panic>(&value, writer).unwrap();
+        assert!(writer.spare.len() < spare);
+
+        let mut output = Vec::new();
+        block_on(writer.write_to(&mut output)).unwrap();
+        assert_eq!(output, expected.as_slice());
+    }
+
+    #[test]
+    fn async_writer_roundtrip() {
+        let value = (0..50).map(|i| i.to_string()).collect::<Vec<_>>();
+
+        let mut output = Vec::new();
+        block_on(to_async_writer::<_, Panic>(&value, &mut output)).unwrap();
+        assert_eq!(output, to_bytes::<Panic>(&value).unwrap().as_slice());
+    }
+}
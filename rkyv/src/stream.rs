@@ -0,0 +1,423 @@
+//! Streams of independently archived values.
+//!
+//! A stream is a sequence of frames which each contain one archived root.
+//! Every frame starts on a [`FRAME_ALIGN`]-byte boundary with a header:
+//!
+//! - A four-byte magic number (`rkyf`)
+//! - The length of the archive as a little-endian `u32`
+//! - The bitwise complement of the length as a little-endian `u32`
+//! - Four reserved bytes, which must be zero
+//!
+//! The archive follows the header and is padded to the next frame boundary.
+//! Because each archive is self-contained, frames can be validated and
+//! accessed independently, and a reader can skip over a corrupted frame by
+//! scanning for the next valid header.
+//!
+//! Use [`FrameWriter`] to append frames to a [`Writer`] and [`FrameReader`] to
+//! iterate over the frames in a byte slice.
+
+use core::{error::Error, fmt, mem};
+
+use rancor::{fail, Source};
+
+use crate::{
+    api::high::{to_bytes_in, HighSerializer},
+    ser::{allocator::ArenaHandle, Writer, WriterExt as _},
+    util::AlignedVec,
+    Serialize,
+};
+
+/// The alignment of each frame in a stream.
+pub const FRAME_ALIGN: usize = 16;
+
+/// The size of the header at the start of each frame.
+pub const HEADER_SIZE: usize = 16;
+
+const MAGIC: [u8; 4] = *b"rkyf";
+
+#[derive(Debug)]
+struct FrameTooLarge {
+    len: usize,
+}
+
+impl fmt::Display for FrameTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archive of {} bytes is too large for a frame (maximum is {})",
+            self.len,
+            u32::MAX,
+        )
+    }
+}
+
+impl Error for FrameTooLarge {}
+
+pub(crate) fn header(len: u32) -> [u8; HEADER_SIZE] {
+    let mut header = [0; HEADER_SIZE];
+    header[0..4].copy_from_slice(&MAGIC);
+    header[4..8].copy_from_slice(&len.to_le_bytes());
+    header[8..12].copy_from_slice(&(!len).to_le_bytes());
+    header
+}
+
+/// A writer which appends archived values to a stream as frames.
+///
+/// Each value is serialized into a reusable scratch buffer before it is
+/// written, so that its length can be written first. Frames can be appended to
+/// any [`Writer`]; wrap an [`io::Write`](std::io::Write) in an
+/// [`IoWriter`](crate::ser::writer::IoWriter) to write frames to it.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     rancor::Error, stream::FrameWriter, util::AlignedVec, Archive,
+///     Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Event {
+///     id: u32,
+///     message: String,
+/// }
+///
+/// let mut writer = FrameWriter::new(AlignedVec::<16>::new());
+/// for id in 0..3 {
+///     let event = Event {
+///         id,
+///         message: format!("event {id}"),
+///     };
+///     writer.write::<_, Error>(&event).unwrap();
+/// }
+/// assert_eq!(writer.frames(), 3);
+/// ```
+#[derive(Debug)]
+pub struct FrameWriter<W> {
+    inner: W,
+    scratch: AlignedVec,
+    frames: usize,
+}
+
+impl<W> FrameWriter<W> {
+    /// Creates a new `FrameWriter` which appends frames to the given writer.
+    ///
+    /// The stream is assumed to start at position 0 of the writer. If the
+    /// writer is not at a frame boundary, it is padded to the next one before
+    /// the first frame is written.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            scratch: AlignedVec::new(),
+            frames: 0,
+        }
+    }
+
+    /// Returns the number of frames written.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Consumes the `FrameWriter` and returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Serializes a value and appends it to the stream as a frame.
+    ///
+    /// Returns the position of the frame in the writer.
+    pub fn write<T, E>(&mut self, value: &T) -> Result<usize, E>
+    where
+        T: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+        W: Writer<E>,
+        E: Source,
+    {
+        let mut scratch = mem::take(&mut self.scratch);
+        scratch.clear();
+        let scratch = to_bytes_in(value, scratch)?;
+
+        let len = scratch.len();
+        let Ok(header_len) = u32::try_from(len) else {
+            fail!(FrameTooLarge { len });
+        };
+
+        let pos = self.inner.align(FRAME_ALIGN)?;
+        self.inner.write(&header(header_len))?;
+        self.inner.write(&scratch)?;
+        self.inner.align(FRAME_ALIGN)?;
+        self.frames += 1;
+
+        self.scratch = scratch;
+        Ok(pos)
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+pub use self::reader::*;
+
+#[cfg(feature = "bytecheck")]
+mod reader {
+    use core::{error::Error, fmt, marker::PhantomData};
+
+    use bytecheck::CheckBytes;
+    use rancor::{ResultExt as _, Source};
+
+    use super::{FRAME_ALIGN, HEADER_SIZE, MAGIC};
+    use crate::{
+        api::high::{access, HighValidator},
+        Portable,
+    };
+
+    /// Returns the length of the archive in the frame with the given header, or
+    /// `None` if the header is invalid.
+    pub(crate) fn parse_header(header: &[u8]) -> Option<usize> {
+        if header.len() < HEADER_SIZE || header[0..4] != MAGIC {
+            return None;
+        }
+
+        let len = u32::from_le_bytes(header[4..8].try_into().unwrap());
+        let check = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if check != !len || header[12..16] != [0; 4] {
+            return None;
+        }
+
+        Some(len as usize)
+    }
+
+    #[derive(Debug)]
+    struct InvalidFrame {
+        pos: usize,
+    }
+
+    impl fmt::Display for InvalidFrame {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "invalid frame header at position {}", self.pos)
+        }
+    }
+
+    impl Error for InvalidFrame {}
+
+    #[derive(Debug)]
+    struct FramePosition {
+        pos: usize,
+    }
+
+    impl fmt::Display for FramePosition {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "while validating the frame at position {}", self.pos)
+        }
+    }
+
+    /// An iterator over the validated frames of a stream.
+    ///
+    /// Each frame is validated independently. When a frame is corrupted, the
+    /// iterator returns an error for it and then skips ahead to the next valid
+    /// frame header.
+    ///
+    /// The byte slice must start at the beginning of the stream and be aligned
+    /// to [`FRAME_ALIGN`] bytes, like an [`AlignedVec`] or a memory map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rkyv::{
+    ///     rancor::Error,
+    ///     stream::{FrameReader, FrameWriter},
+    ///     util::AlignedVec,
+    ///     Archive, Archived, Serialize,
+    /// };
+    ///
+    /// let mut writer = FrameWriter::new(AlignedVec::<16>::new());
+    /// writer.write::<_, Error>(&"hello".to_string()).unwrap();
+    /// writer.write::<_, Error>(&"world".to_string()).unwrap();
+    /// let bytes = writer.into_inner();
+    ///
+    /// let frames = FrameReader::<Archived<String>, Error>::new(&bytes)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(frames, ["hello", "world"]);
+    /// ```
+    ///
+    /// [`AlignedVec`]: crate::util::AlignedVec
+    pub struct FrameReader<'a, T, E> {
+        bytes: &'a [u8],
+        pos: usize,
+        resync: bool,
+        _phantom: PhantomData<fn() -> (&'a T, E)>,
+    }
+
+    impl<'a, T, E> FrameReader<'a, T, E> {
+        /// Creates a new `FrameReader` over the frames in the given bytes.
+        pub fn new(bytes: &'a [u8]) -> Self {
+            Self {
+                bytes,
+                pos: 0,
+                resync: false,
+                _phantom: PhantomData,
+            }
+        }
+
+        /// Returns the position of the next frame header to read.
+        pub fn pos(&self) -> usize {
+            self.pos
+        }
+
+        /// Returns the archive length of the frame at `pos`, if it has a valid
+        /// header and fits in the bytes.
+        fn frame_at(&self, pos: usize) -> Option<usize> {
+            let len = parse_header(&self.bytes[pos..])?;
+            let end = (pos + HEADER_SIZE).checked_add(len)?;
+            (end <= self.bytes.len()).then_some(len)
+        }
+    }
+
+    impl<'a, T, E> Iterator for FrameReader<'a, T, E>
+    where
+        T: Portable + for<'b> CheckBytes<HighValidator<'b, E>>,
+        E: Source,
+    {
+        type Item = Result<&'a T, E>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let len = loop {
+                if self.pos >= self.bytes.len() {
+                    return None;
+                }
+
+                match self.frame_at(self.pos) {
+                    Some(len) => break len,
+                    None if self.resync => self.pos += FRAME_ALIGN,
+                    None => {
+                        let pos = self.pos;
+                        self.resync = true;
+                        self.pos += FRAME_ALIGN;
+                        return Some(Err(E::new(InvalidFrame { pos })));
+                    }
+                }
+            };
+
+            let pos = self.pos;
+            let start = pos + HEADER_SIZE;
+            let archive = &self.bytes[start..start + len];
+            match access::<T, E>(archive) {
+                Ok(value) => {
+                    self.resync = false;
+                    let end = start + len;
+                    self.pos =
+                        end + (FRAME_ALIGN - end % FRAME_ALIGN) % FRAME_ALIGN;
+                    Some(Ok(value))
+                }
+                Err(error) => {
+                    self.resync = true;
+                    self.pos += FRAME_ALIGN;
+                    Some(Err(error).with_trace(|| FramePosition { pos }))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use core::mem::size_of;
+
+    use rancor::Failure;
+
+    use super::{FrameReader, FrameWriter, FRAME_ALIGN, HEADER_SIZE};
+    use crate::{
+        alloc::{format, string::String, vec::Vec},
+        util::AlignedVec,
+        Archive, Deserialize, Serialize,
+    };
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(crate, compare(PartialEq), derive(Debug))]
+    struct Event {
+        id: u32,
+        message: String,
+    }
+
+    fn events(count: u32) -> Vec<Event> {
+        (0..count)
+            .map(|id| Event {
+                id,
+                message: format!("event number {id}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn frames_roundtrip() {
+        let events = events(10);
+        let mut writer = FrameWriter::new(AlignedVec::<16>::new());
+        for event in events.iter() {
+            let pos = writer.write::<_, Failure>(event).unwrap();
+            assert_eq!(pos % FRAME_ALIGN, 0);
+        }
+
+        let bytes = writer.into_inner();
+        let frames = FrameReader::<ArchivedEvent, Failure>::new(&bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(frames.len(), events.len());
+        for (frame, event) in frames.iter().zip(events.iter()) {
+            assert_eq!(*frame, event);
+        }
+    }
+
+    #[test]
+    fn frames_resync_after_corruption() {
+        let events = events(3);
+        let mut writer = FrameWriter::new(AlignedVec::<16>::new());
+        let mut positions = Vec::new();
+        for event in events.iter() {
+            positions.push(writer.write::<_, Failure>(event).unwrap());
+        }
+
+        // Corrupt the header of the second frame
+        let mut bytes = writer.into_inner();
+        bytes[positions[1] + 4] ^= 0xff;
+        let results = FrameReader::<ArchivedEvent, Failure>::new(&bytes)
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), 3);
+        assert_eq!(*results[0].as_ref().unwrap(), &events[0]);
+        assert!(results[1].is_err());
+        assert_eq!(*results[2].as_ref().unwrap(), &events[2]);
+
+        // Corrupt the archive of the second frame
+        let mut writer = FrameWriter::new(AlignedVec::<16>::new());
+        for event in events.iter() {
+            writer.write::<_, Failure>(event).unwrap();
+        }
+        let mut bytes = writer.into_inner();
+        let len = u32::from_le_bytes(
+            bytes[positions[1] + 4..positions[1] + 8]
+                .try_into()
+                .unwrap(),
+        ) as usize;
+        let end = positions[1] + HEADER_SIZE + len;
+        bytes[end - size_of::<ArchivedEvent>()..end].fill(0xc0);
+        let results = FrameReader::<ArchivedEvent, Failure>::new(&bytes)
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), 3);
+        assert!(results[1].is_err());
+        assert_eq!(*results[2].as_ref().unwrap(), &events[2]);
+
+        // Truncate the last frame
+        let mut writer = FrameWriter::new(AlignedVec::<16>::new());
+        for event in events.iter() {
+            writer.write::<_, Failure>(event).unwrap();
+        }
+        let bytes = writer.into_inner();
+        let bytes = &bytes[..positions[2] + 20];
+        let results = FrameReader::<ArchivedEvent, Failure>::new(bytes)
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), 3);
+        assert!(results[2].is_err());
+    }
+}
@@ -19,14 +19,21 @@ use rancor::{fail, Fallible, Source};
 use repr::{ArchivedStringRepr, INLINE_CAPACITY};
 
 use crate::{
-    primitive::FixedUsize, seal::Seal, Place, Portable, SerializeUnsized,
+    primitive::{ArchivedIsize, FixedUsize},
+    rel_ptr::Offset,
+    seal::Seal,
+    ser::{Interning, InterningExt as _},
+    Place, Portable, SerializeUnsized,
 };
 
 /// An archived [`String`].
 ///
 /// This has inline and out-of-line representations. Short strings will use the
 /// available space inside the structure to store the string, and long strings
-/// will store a [`RelPtr`](crate::RelPtr) to a `str` instead.
+/// will store a [`RelPtr`](crate::RelPtr) to a `str` instead. The offset type
+/// `O` of out-of-line strings defaults to [`ArchivedIsize`], see
+/// [`WideOffset`](crate::with::WideOffset) and
+/// [`NarrowOffset`](crate::with::NarrowOffset) for other offset types.
 #[repr(transparent)]
 #[cfg_attr(
     feature = "bytecheck",
@@ -35,11 +42,11 @@ use crate::{
 )]
 #[derive(Portable)]
 #[rkyv(crate)]
-pub struct ArchivedString {
-    repr: ArchivedStringRepr,
+pub struct ArchivedString<O: Offset = ArchivedIsize> {
+    repr: ArchivedStringRepr<O>,
 }
 
-impl ArchivedString {
+impl<O: Offset> ArchivedString<O> {
     /// Extracts a string slice containing the entire `ArchivedString`.
     #[inline]
     pub fn as_str(&self) -> &str {
@@ -70,13 +77,15 @@ impl ArchivedString {
             unsafe {
                 ArchivedStringRepr::emplace_out_of_line(
                     value,
-                    resolver.pos as usize,
+                    resolver.pos.map_or(0, |pos| pos as usize),
                     repr,
                 );
             }
         }
     }
+}
 
+impl ArchivedString {
     /// Serializes an archived string from a given `str`.
     pub fn serialize_from_str<S: Fallible + ?Sized>(
         value: &str,
@@ -85,9 +94,39 @@ impl ArchivedString {
     where
         S::Error: Source,
         str: SerializeUnsized<S>,
+    {
+        Self::serialize_out_of_line(value, serializer, |value, serializer| {
+            value.serialize_unsized(serializer)
+        })
+    }
+
+    /// Serializes an archived string from a given `str`, sharing the bytes
+    /// of out-of-line strings with any equal strings that were previously
+    /// interned.
+    pub fn serialize_from_str_interned<S>(
+        value: &str,
+        serializer: &mut S,
+    ) -> Result<StringResolver, S::Error>
+    where
+        S: Fallible + Interning + ?Sized,
+        S::Error: Source,
+        str: SerializeUnsized<S>,
+    {
+        Self::serialize_out_of_line(value, serializer, |value, serializer| {
+            serializer.serialize_interned(value)
+        })
+    }
+
+    fn serialize_out_of_line<S: Fallible + ?Sized>(
+        value: &str,
+        serializer: &mut S,
+        serialize: impl FnOnce(&str, &mut S) -> Result<usize, S::Error>,
+    ) -> Result<StringResolver, S::Error>
+    where
+        S::Error: Source,
     {
         if value.len() <= INLINE_CAPACITY {
-            Ok(StringResolver { pos: 0 })
+            Ok(StringResolver { pos: None })
         } else if value.len() > repr::OUT_OF_LINE_CAPACITY {
             #[derive(Debug)]
             struct StringTooLongError;
@@ -106,34 +145,34 @@ impl ArchivedString {
             fail!(StringTooLongError);
         } else {
             Ok(StringResolver {
-                pos: value.serialize_unsized(serializer)? as FixedUsize,
+                pos: Some(serialize(value, serializer)? as FixedUsize),
             })
         }
     }
 }
 
-impl AsRef<str> for ArchivedString {
+impl<O: Offset> AsRef<str> for ArchivedString<O> {
     #[inline]
     fn as_ref(&self) -> &str {
         self.as_str()
     }
 }
 
-impl Borrow<str> for ArchivedString {
+impl<O: Offset> Borrow<str> for ArchivedString<O> {
     #[inline]
     fn borrow(&self) -> &str {
         self.as_str()
     }
 }
 
-impl fmt::Debug for ArchivedString {
+impl<O: Offset> fmt::Debug for ArchivedString<O> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(self.as_str(), f)
     }
 }
 
-impl Deref for ArchivedString {
+impl<O: Offset> Deref for ArchivedString<O> {
     type Target = str;
 
     #[inline]
@@ -142,16 +181,16 @@ impl Deref for ArchivedString {
     }
 }
 
-impl fmt::Display for ArchivedString {
+impl<O: Offset> fmt::Display for ArchivedString<O> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(self.as_str(), f)
     }
 }
 
-impl Eq for ArchivedString {}
+impl<O: Offset> Eq for ArchivedString<O> {}
 
-impl hash::Hash for ArchivedString {
+impl<O: Offset> hash::Hash for ArchivedString<O> {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         self.as_str().hash(state)
     }
@@ -159,7 +198,7 @@ impl hash::Hash for ArchivedString {
 
 macro_rules! impl_index {
     ($index:ty) => {
-        impl Index<$index> for ArchivedString {
+        impl<O: Offset> Index<$index> for ArchivedString<O> {
             type Output = str;
 
             #[inline]
@@ -177,86 +216,95 @@ impl_index!(RangeInclusive<usize>);
 impl_index!(RangeTo<usize>);
 impl_index!(RangeToInclusive<usize>);
 
-impl Ord for ArchivedString {
+impl<O: Offset> Ord for ArchivedString<O> {
     #[inline]
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.as_str().cmp(other.as_str())
     }
 }
 
-impl PartialEq for ArchivedString {
+impl<O: Offset> PartialEq for ArchivedString<O> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         self.as_str() == other.as_str()
     }
 }
 
-impl PartialOrd for ArchivedString {
+impl<O: Offset> PartialOrd for ArchivedString<O> {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl PartialEq<&str> for ArchivedString {
+impl<O: Offset> PartialEq<&str> for ArchivedString<O> {
     #[inline]
     fn eq(&self, other: &&str) -> bool {
         PartialEq::eq(self.as_str(), *other)
     }
 }
 
-impl PartialEq<str> for ArchivedString {
+impl<O: Offset> PartialEq<str> for ArchivedString<O> {
     #[inline]
     fn eq(&self, other: &str) -> bool {
         PartialEq::eq(self.as_str(), other)
     }
 }
 
-impl PartialEq<ArchivedString> for &str {
+impl<O: Offset> PartialEq<ArchivedString<O>> for &str {
     #[inline]
-    fn eq(&self, other: &ArchivedString) -> bool {
+    fn eq(&self, other: &ArchivedString<O>) -> bool {
         PartialEq::eq(other.as_str(), *self)
     }
 }
 
-impl PartialEq<ArchivedString> for str {
+impl<O: Offset> PartialEq<ArchivedString<O>> for str {
     #[inline]
-    fn eq(&self, other: &ArchivedString) -> bool {
+    fn eq(&self, other: &ArchivedString<O>) -> bool {
         PartialEq::eq(other.as_str(), self)
     }
 }
 
-impl PartialOrd<&str> for ArchivedString {
+impl<O: Offset> PartialOrd<&str> for ArchivedString<O> {
     #[inline]
     fn partial_cmp(&self, other: &&str) -> Option<cmp::Ordering> {
         self.as_str().partial_cmp(*other)
     }
 }
 
-impl PartialOrd<str> for ArchivedString {
+impl<O: Offset> PartialOrd<str> for ArchivedString<O> {
     #[inline]
     fn partial_cmp(&self, other: &str) -> Option<cmp::Ordering> {
         self.as_str().partial_cmp(other)
     }
 }
 
-impl PartialOrd<ArchivedString> for &str {
+impl<O: Offset> PartialOrd<ArchivedString<O>> for &str {
     #[inline]
-    fn partial_cmp(&self, other: &ArchivedString) -> Option<cmp::Ordering> {
+    fn partial_cmp(&self, other: &ArchivedString<O>) -> Option<cmp::Ordering> {
         self.partial_cmp(&other.as_str())
     }
 }
 
-impl PartialOrd<ArchivedString> for str {
+impl<O: Offset> PartialOrd<ArchivedString<O>> for str {
     #[inline]
-    fn partial_cmp(&self, other: &ArchivedString) -> Option<cmp::Ordering> {
+    fn partial_cmp(&self, other: &ArchivedString<O>) -> Option<cmp::Ordering> {
         self.partial_cmp(other.as_str())
     }
 }
 
 /// The resolver for `String`.
 pub struct StringResolver {
-    pos: FixedUsize,
+    pos: Option<FixedUsize>,
+}
+
+impl StringResolver {
+    /// Returns the position of the string's bytes, or `None` if the string is
+    /// stored inline.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn out_of_line_pos(&self) -> Option<usize> {
+        self.pos.map(|pos| pos as usize)
+    }
 }
 
 #[cfg(feature = "bytecheck")]
@@ -267,12 +315,14 @@ mod verify {
     };
 
     use crate::{
+        rel_ptr::Offset,
         string::{repr::ArchivedStringRepr, ArchivedString},
         validation::{ArchiveContext, ArchiveContextExt},
     };
 
-    unsafe impl<C> Verify<C> for ArchivedString
+    unsafe impl<O, C> Verify<C> for ArchivedString<O>
     where
+        O: Offset,
         C: Fallible + ArchiveContext + ?Sized,
         C::Error: Source,
     {
@@ -283,19 +333,15 @@ mod verify {
                 }
             } else {
                 let base =
-                    (&self.repr as *const ArchivedStringRepr).cast::<u8>();
+                    (&self.repr as *const ArchivedStringRepr<O>).cast::<u8>();
                 let offset = unsafe { self.repr.out_of_line_offset() };
                 let metadata = self.repr.len();
 
                 let address = base.wrapping_offset(offset).cast::<()>();
-                let ptr = ptr_meta::from_raw_parts(address, metadata);
-
-                context.in_subtree(ptr, |context| {
-                    // SAFETY: `in_subtree` has guaranteed that `ptr` is
-                    // properly aligned and points to enough bytes to represent
-                    // the pointed-to `str`.
-                    unsafe { str::check_bytes(ptr, context) }
-                })?;
+                let ptr: *const str =
+                    ptr_meta::from_raw_parts(address, metadata);
+
+                context.check_aliasable_subtree(ptr)?;
             }
 
             Ok(())
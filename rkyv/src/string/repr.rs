@@ -11,7 +11,8 @@ use munge::munge;
 use rancor::{Panic, ResultExt as _, Source};
 
 use crate::{
-    primitive::{ArchivedIsize, ArchivedUsize, FixedIsize, FixedUsize},
+    primitive::{ArchivedIsize, ArchivedUsize, FixedUsize},
+    rel_ptr::Offset,
     seal::Seal,
     Place, Portable,
 };
@@ -19,14 +20,17 @@ use crate::{
 #[derive(Clone, Copy, Portable)]
 #[rkyv(crate)]
 #[repr(C)]
-struct OutOfLineRepr {
+struct OutOfLineRepr<O> {
     len: ArchivedUsize,
-    offset: ArchivedIsize,
+    offset: O,
     _phantom: PhantomPinned,
 }
 
 /// The maximum number of bytes that can be inlined.
-pub const INLINE_CAPACITY: usize = mem::size_of::<OutOfLineRepr>();
+///
+/// This is the same for every offset type.
+pub const INLINE_CAPACITY: usize =
+    mem::size_of::<OutOfLineRepr<ArchivedIsize>>();
 /// The maximum number of bytes that can be out-of-line.
 pub const OUT_OF_LINE_CAPACITY: usize = !(0b11 << (FixedUsize::BITS - 2));
 
@@ -38,15 +42,18 @@ struct InlineRepr {
 }
 
 /// An archived string representation that can inline short strings.
+///
+/// Out-of-line strings store their offset as an `O`, which defaults to
+/// [`ArchivedIsize`].
 #[derive(Portable)]
 #[rkyv(crate)]
 #[repr(C)]
-pub union ArchivedStringRepr {
-    out_of_line: OutOfLineRepr,
+pub union ArchivedStringRepr<O: Offset = ArchivedIsize> {
+    out_of_line: OutOfLineRepr<O>,
     inline: InlineRepr,
 }
 
-impl ArchivedStringRepr {
+impl<O: Offset> ArchivedStringRepr<O> {
     /// Returns whether the representation is inline.
     #[inline]
     pub fn is_inline(&self) -> bool {
@@ -62,7 +69,7 @@ impl ArchivedStringRepr {
     pub unsafe fn out_of_line_offset(&self) -> isize {
         // SAFETY: The caller has guaranteed that the internal representation is
         // out-of-line
-        unsafe { self.out_of_line.offset.to_native() as isize }
+        unsafe { self.out_of_line.offset.to_isize() }
     }
 
     /// Returns a pointer to the bytes of the string.
@@ -191,6 +198,8 @@ impl ArchivedStringRepr {
 
     /// Emplaces a new out-of-line representation for the given `str`.
     ///
+    /// Returns an error if the offset to `target` doesn't fit in an `O`.
+    ///
     /// # Safety
     ///
     /// The length of `str` must be greater than [`INLINE_CAPACITY`] and less
@@ -214,7 +223,7 @@ impl ArchivedStringRepr {
         len.write(ArchivedUsize::from_native(l));
 
         let off = crate::rel_ptr::signed_offset(out.pos(), target)?;
-        offset.write(ArchivedIsize::from_native(off as FixedIsize));
+        offset.write(O::from_isize(off)?);
 
         Ok(())
     }
@@ -224,7 +233,7 @@ impl ArchivedStringRepr {
     /// # Panics
     ///
     /// - The offset calculated for the repr does not fit in an `isize`
-    /// - The offset calculated for the repr exceeds the offset storage
+    /// - The offset calculated for the repr does not fit in an `O`
     ///
     /// # Safety
     ///
@@ -271,8 +280,9 @@ const _: () = {
 
     impl Error for CheckStringReprError {}
 
-    unsafe impl<C> CheckBytes<C> for ArchivedStringRepr
+    unsafe impl<O, C> CheckBytes<C> for ArchivedStringRepr<O>
     where
+        O: Offset,
         C: Fallible + ?Sized,
         C::Error: Source,
     {
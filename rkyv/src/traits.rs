@@ -6,7 +6,9 @@ use core::{
     marker::PhantomData,
 };
 
-pub use ::rkyv_derive::{Archive, Deserialize, Portable, Serialize};
+pub use ::rkyv_derive::{
+    Archive, Deserialize, DeserializeInPlace, Portable, Serialize,
+};
 
 use crate::{ptr_meta::Pointee, rancor::Fallible, ArchivedMetadata, Place};
 
@@ -260,6 +262,25 @@ pub trait Serialize<S: Fallible + ?Sized>: Archive {
     /// create the archived type.
     fn serialize(&self, serializer: &mut S)
         -> Result<Self::Resolver, S::Error>;
+
+    /// Checks that a resolver returned by `serialize` can be resolved into an
+    /// archived value at the given position.
+    ///
+    /// Resolving can't fail, so types which can't be resolved at every
+    /// position (for example, because a relative pointer might not be able to
+    /// reach its target) should return an error here instead. Callers check
+    /// the resolver once the archived value's position is known, just before
+    /// resolving it. Types which store other values inline should check each
+    /// of them at its own position.
+    ///
+    /// The default implementation always succeeds.
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        let _ = (resolver, pos);
+        Ok(())
+    }
 }
 
 /// Converts a type back from its archived form.
@@ -275,6 +296,39 @@ pub trait Deserialize<T, D: Fallible + ?Sized> {
     fn deserialize(&self, deserializer: &mut D) -> Result<T, D::Error>;
 }
 
+/// Deserializes an archived value into an existing value.
+///
+/// [`Deserialize`] always builds a new value. `DeserializeInPlace` instead
+/// overwrites an existing value, and reuses its allocations where possible.
+/// For example, deserializing into a `Vec` reuses its capacity and deserializes
+/// into its existing elements. This is useful when repeatedly deserializing
+/// into the same value.
+///
+/// The default implementation replaces the target with a newly deserialized
+/// value, which is the best that can be done for types which don't own any
+/// allocations. Every type which implements `Deserialize` in rkyv also
+/// implements `DeserializeInPlace`, so derived implementations can use it for
+/// any field.
+///
+/// This can be derived with
+/// [`DeserializeInPlace`](macro@crate::DeserializeInPlace).
+pub trait DeserializeInPlace<T, D: Fallible + ?Sized>:
+    Deserialize<T, D>
+{
+    /// Deserializes into the given target using the given deserializer.
+    ///
+    /// If an error is returned, the target is left in a valid but unspecified
+    /// state.
+    fn deserialize_into(
+        &self,
+        target: &mut T,
+        deserializer: &mut D,
+    ) -> Result<(), D::Error> {
+        *target = self.deserialize(deserializer)?;
+        Ok(())
+    }
+}
+
 /// A counterpart of [`Archive`] that's suitable for unsized types.
 ///
 /// Unlike `Archive`, types that implement `ArchiveUnsized` must be serialized
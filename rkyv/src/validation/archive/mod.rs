@@ -4,18 +4,61 @@ mod validator;
 
 use core::{alloc::Layout, ops::Range};
 
-use bytecheck::rancor::{Fallible, Source, Strategy};
+use bytecheck::{
+    rancor::{Fallible, Source, Strategy},
+    CheckBytes,
+};
 use rancor::ResultExt as _;
 
 pub use self::validator::*;
 use crate::traits::LayoutRaw;
 
+/// Identifies the function used to check the bytes of a subtree.
+///
+/// Different functions only share an ID if they compile to the same code, in
+/// which case they perform the same checks. The same function may have multiple
+/// IDs, in which case aliased subtrees are checked again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CheckId(usize);
+
+impl CheckId {
+    /// Returns the ID of the function which checks a `T` with a context `C`.
+    pub fn of<T, C>() -> Self
+    where
+        T: CheckBytes<C> + ?Sized,
+        C: Fallible + ?Sized,
+    {
+        let check: unsafe fn(*const T, &mut C) -> Result<(), C::Error> =
+            T::check_bytes;
+        Self(check as usize)
+    }
+}
+
+/// The state of a subtree which may be pointed to by multiple pointers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AliasState {
+    /// The subtree has not been claimed yet, or the context doesn't allow
+    /// aliasing. It must be claimed as usual.
+    Unclaimed,
+    /// The subtree was already claimed and validated with a different check.
+    /// It must be checked again, but not claimed.
+    Claimed,
+    /// The subtree was already claimed and validated with the same check.
+    Checked,
+}
+
 /// A context that can validate nonlocal archive memory.
 ///
 /// # Safety
 ///
 /// `check_subtree_ptr` must only return true if `ptr` is located entirely
 /// within the subtree range and is safe to dereference.
+///
+/// `check_aliased_subtree` must only return `Claimed` if a subtree with the
+/// same address and size was previously recorded with
+/// `finish_aliasable_subtree` and `ptr` is properly aligned, and only return
+/// `Checked` if it was also recorded with the same check. Contexts which allow
+/// aliasing must only be used to access archives immutably.
 pub unsafe trait ArchiveContext<E = <Self as Fallible>::Error> {
     /// Checks that the given data address and layout is located completely
     /// within the subtree range.
@@ -53,6 +96,38 @@ pub unsafe trait ArchiveContext<E = <Self as Fallible>::Error> {
         &mut self,
         range: Range<usize>,
     ) -> Result<(), E>;
+
+    /// Checks whether the given data address and layout were already claimed
+    /// by another pointer, and whether they were checked with the given check.
+    ///
+    /// By default, aliased subtrees are not allowed and this always returns
+    /// [`AliasState::Unclaimed`].
+    fn check_aliased_subtree(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+        check: CheckId,
+    ) -> Result<AliasState, E> {
+        let _ = (ptr, layout, check);
+        Ok(AliasState::Unclaimed)
+    }
+
+    /// Records that the given data address and layout were validated with the
+    /// given check, so that later pointers may alias them.
+    ///
+    /// # Safety
+    ///
+    /// The subtree at `ptr` must have been claimed, and successfully checked
+    /// by the function with the given check ID.
+    unsafe fn finish_aliasable_subtree(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+        check: CheckId,
+    ) -> Result<(), E> {
+        let _ = (ptr, layout, check);
+        Ok(())
+    }
 }
 
 unsafe impl<T, E> ArchiveContext<E> for Strategy<T, E>
@@ -85,6 +160,26 @@ where
         // has the same safety requirements.
         unsafe { T::pop_subtree_range(self, range) }
     }
+
+    fn check_aliased_subtree(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+        check: CheckId,
+    ) -> Result<AliasState, E> {
+        T::check_aliased_subtree(self, ptr, layout, check)
+    }
+
+    unsafe fn finish_aliasable_subtree(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+        check: CheckId,
+    ) -> Result<(), E> {
+        // SAFETY: This just forwards the call to the underlying context, which
+        // has the same safety requirements.
+        unsafe { T::finish_aliasable_subtree(self, ptr, layout, check) }
+    }
 }
 
 /// Helper methods for [`ArchiveContext`].
@@ -107,6 +202,14 @@ pub trait ArchiveContextExt<E>: ArchiveContext<E> {
         ptr: *const T,
         f: impl FnOnce(&mut Self) -> Result<R, E>,
     ) -> Result<R, E>;
+
+    /// Checks the value the given pointer points to in a new subtree range,
+    /// unless the context allows aliasing and it was already claimed by another
+    /// pointer.
+    fn check_aliasable_subtree<T>(&mut self, ptr: *const T) -> Result<(), E>
+    where
+        T: CheckBytes<Self> + LayoutRaw + ?Sized,
+        Self: Fallible<Error = E>;
 }
 
 impl<C: ArchiveContext<E> + ?Sized, E: Source> ArchiveContextExt<E> for C {
@@ -145,4 +248,36 @@ impl<C: ArchiveContext<E> + ?Sized, E: Source> ArchiveContextExt<E> for C {
 
         self.in_subtree_raw(root, layout, f)
     }
+
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn check_aliasable_subtree<T>(&mut self, ptr: *const T) -> Result<(), E>
+    where
+        T: CheckBytes<Self> + LayoutRaw + ?Sized,
+        Self: Fallible<Error = E>,
+    {
+        let layout = T::layout_raw(ptr_meta::metadata(ptr)).into_error()?;
+        let root = ptr as *const u8;
+        let check = CheckId::of::<T, Self>();
+
+        match self.check_aliased_subtree(root, &layout, check)? {
+            AliasState::Unclaimed => {
+                self.in_subtree_raw(root, layout, |context| {
+                    // SAFETY: `in_subtree_raw` has guaranteed that `ptr` is
+                    // properly aligned and points to enough bytes to represent
+                    // a `T`.
+                    unsafe { T::check_bytes(ptr, context) }
+                })?;
+            }
+            AliasState::Claimed => {
+                // SAFETY: `check_aliased_subtree` has guaranteed that `ptr` is
+                // properly aligned and points to a subtree that was already
+                // claimed with enough bytes to represent a `T`.
+                unsafe { T::check_bytes(ptr, self)? };
+            }
+            AliasState::Checked => return Ok(()),
+        }
+
+        // SAFETY: The subtree at `root` was claimed and checked as a `T`.
+        unsafe { self.finish_aliasable_subtree(root, &layout, check) }
+    }
 }
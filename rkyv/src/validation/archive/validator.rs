@@ -5,6 +5,11 @@ use core::{
 
 use rancor::{fail, OptionExt, Source};
 
+#[cfg(feature = "alloc")]
+use crate::{
+    alloc::{collections::BTreeSet, vec::Vec},
+    validation::archive::{AliasState, CheckId},
+};
 use crate::{fmt::Pointer, validation::ArchiveContext};
 
 #[derive(Debug)]
@@ -84,11 +89,32 @@ impl fmt::Display for RangePoppedOutOfOrder {
 
 impl Error for RangePoppedOutOfOrder {}
 
+/// A subtree which was validated and may be pointed to again.
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct AliasableSubtree {
+    start: usize,
+    size: usize,
+    check: CheckId,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Debug, Default)]
+struct Aliasable {
+    // Subtrees finish validating in address order, so claimed subtrees can be
+    // pushed in order and found with a binary search.
+    claimed: Vec<AliasableSubtree>,
+    // Previously-claimed subtrees which were checked again with another check.
+    rechecked: BTreeSet<AliasableSubtree>,
+}
+
 /// A validator that can verify archives with nonlocal memory.
 #[derive(Debug)]
 pub struct ArchiveValidator<'a> {
     subtree_range: Range<usize>,
     max_subtree_depth: Option<NonZeroUsize>,
+    #[cfg(feature = "alloc")]
+    aliasable: Option<Aliasable>,
     _phantom: PhantomData<&'a [u8]>,
 }
 
@@ -113,9 +139,26 @@ impl<'a> ArchiveValidator<'a> {
                 end: end as usize,
             },
             max_subtree_depth,
+            #[cfg(feature = "alloc")]
+            aliasable: None,
             _phantom: PhantomData,
         }
     }
+
+    /// Allows pointers to subtrees which were already validated as the same
+    /// type, like the ones written by [`Intern`](crate::with::Intern).
+    ///
+    /// Aliased subtrees are only validated once. A validator which allows
+    /// aliasing must only be used to access archives immutably, since multiple
+    /// pointers to the same bytes could otherwise be used to mutate them at
+    /// the same time.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn allow_aliasing(&mut self) {
+        if self.aliasable.is_none() {
+            self.aliasable = Some(Aliasable::default());
+        }
+    }
 }
 
 unsafe impl<E: Source> ArchiveContext<E> for ArchiveValidator<'_> {
@@ -175,4 +218,70 @@ unsafe impl<E: Source> ArchiveContext<E> for ArchiveValidator<'_> {
         }
         Ok(())
     }
+
+    #[cfg(feature = "alloc")]
+    fn check_aliased_subtree(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+        check: CheckId,
+    ) -> Result<AliasState, E> {
+        let start = ptr as usize;
+        let Some(aliasable) = &self.aliasable else {
+            return Ok(AliasState::Unclaimed);
+        };
+        if start >= self.subtree_range.start {
+            return Ok(AliasState::Unclaimed);
+        }
+
+        let first = aliasable.claimed.partition_point(|s| s.start < start);
+        let mut claimed = false;
+        for subtree in aliasable.claimed[first..]
+            .iter()
+            .take_while(|s| s.start == start && s.size <= layout.size())
+        {
+            if subtree.size == layout.size() {
+                if subtree.check == check {
+                    return Ok(AliasState::Checked);
+                }
+                claimed = true;
+            }
+        }
+
+        let subtree = AliasableSubtree {
+            start,
+            size: layout.size(),
+            check,
+        };
+        if !claimed || start & (layout.align() - 1) != 0 {
+            // Claiming the subtree as usual will report the error.
+            Ok(AliasState::Unclaimed)
+        } else if aliasable.rechecked.contains(&subtree) {
+            Ok(AliasState::Checked)
+        } else {
+            Ok(AliasState::Claimed)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    unsafe fn finish_aliasable_subtree(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+        check: CheckId,
+    ) -> Result<(), E> {
+        if let Some(aliasable) = &mut self.aliasable {
+            let subtree = AliasableSubtree {
+                start: ptr as usize,
+                size: layout.size(),
+                check,
+            };
+            if aliasable.claimed.last().is_some_and(|last| *last > subtree) {
+                aliasable.rechecked.insert(subtree);
+            } else {
+                aliasable.claimed.push(subtree);
+            }
+        }
+        Ok(())
+    }
 }
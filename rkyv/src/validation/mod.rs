@@ -5,6 +5,7 @@ pub mod shared;
 
 use core::{any::TypeId, ops::Range};
 
+use self::archive::{AliasState, CheckId};
 pub use self::{
     archive::{ArchiveContext, ArchiveContextExt},
     shared::SharedContext,
@@ -55,6 +56,26 @@ where
         // which has the same safety requirements.
         unsafe { self.archive.pop_subtree_range(range) }
     }
+
+    fn check_aliased_subtree(
+        &mut self,
+        ptr: *const u8,
+        layout: &core::alloc::Layout,
+        check: CheckId,
+    ) -> Result<AliasState, E> {
+        self.archive.check_aliased_subtree(ptr, layout, check)
+    }
+
+    unsafe fn finish_aliasable_subtree(
+        &mut self,
+        ptr: *const u8,
+        layout: &core::alloc::Layout,
+        check: CheckId,
+    ) -> Result<(), E> {
+        // SAFETY: This just forwards the call to the underlying `CoreValidator`
+        // which has the same safety requirements.
+        unsafe { self.archive.finish_aliasable_subtree(ptr, layout, check) }
+    }
 }
 
 impl<A, S, E> SharedContext<E> for Validator<A, S>
@@ -11,10 +11,11 @@ use munge::munge;
 use rancor::Fallible;
 
 use crate::{
-    primitive::{ArchivedUsize, FixedUsize},
+    primitive::{ArchivedIsize, ArchivedUsize},
+    rel_ptr::{Offset, RelPtr},
     seal::Seal,
     ser::{Allocator, Writer, WriterExt as _},
-    Archive, Place, Portable, RelPtr, Serialize, SerializeUnsized,
+    Archive, Place, Portable, Serialize, SerializeUnsized,
 };
 
 /// An archived [`Vec`].
@@ -22,6 +23,12 @@ use crate::{
 /// This uses a [`RelPtr`] to a `[T]` under the hood. Unlike
 /// [`ArchivedString`](crate::string::ArchivedString), it does not have an
 /// inline representation.
+///
+/// The offset type `O` of the relative pointer defaults to
+/// [`ArchivedIsize`]. Other offset types can be used to reach data that is
+/// farther away. See
+/// [`WideOffset`](crate::with::WideOffset) and
+/// [`NarrowOffset`](crate::with::NarrowOffset).
 #[derive(Portable)]
 #[cfg_attr(
     feature = "bytecheck",
@@ -30,12 +37,12 @@ use crate::{
 )]
 #[rkyv(crate)]
 #[repr(C)]
-pub struct ArchivedVec<T> {
-    ptr: RelPtr<T>,
+pub struct ArchivedVec<T, O = ArchivedIsize> {
+    ptr: RelPtr<T, O>,
     len: ArchivedUsize,
 }
 
-impl<T> ArchivedVec<T> {
+impl<T, O: Offset> ArchivedVec<T, O> {
     /// Returns a pointer to the first element of the archived vec.
     pub fn as_ptr(&self) -> *const T {
         unsafe { self.ptr.as_ptr() }
@@ -82,10 +89,12 @@ impl<T> ArchivedVec<T> {
         out: Place<Self>,
     ) {
         munge!(let ArchivedVec { ptr, len: out_len } = out);
-        RelPtr::emplace(resolver.pos as usize, ptr);
+        RelPtr::emplace(resolver.pos, ptr);
         usize::resolve(&len, (), out_len);
     }
+}
 
+impl<T> ArchivedVec<T> {
     /// Serializes an archived `Vec` from a given slice.
     pub fn serialize_from_slice<
         U: Serialize<S, Archived = T>,
@@ -95,7 +104,7 @@ impl<T> ArchivedVec<T> {
         serializer: &mut S,
     ) -> Result<VecResolver, S::Error> {
         Ok(VecResolver {
-            pos: slice.serialize_unsized(serializer)? as FixedUsize,
+            pos: slice.serialize_unsized(serializer)?,
         })
     }
 
@@ -127,14 +136,13 @@ impl<T> ArchivedVec<T> {
 
                 let pos = serializer.align_for::<T>()?;
                 for (value, resolver) in iter.zip(resolvers.drain()) {
+                    U::check_resolver(&resolver, serializer.pos())?;
                     unsafe {
                         serializer.resolve_aligned(value.borrow(), resolver)?;
                     }
                 }
 
-                Ok(VecResolver {
-                    pos: pos as FixedUsize,
-                })
+                Ok(VecResolver { pos })
             },
         )?
     }
@@ -162,35 +170,34 @@ impl<T> ArchivedVec<T> {
                 let pos_cached = serializer.pos();
                 let resolver = value.serialize(serializer)?;
                 assert!(serializer.pos() == pos_cached);
+                B::check_resolver(&resolver, pos_cached)?;
                 serializer.resolve_aligned(value.borrow(), resolver)?;
             }
 
-            Ok(VecResolver {
-                pos: pos as FixedUsize,
-            })
+            Ok(VecResolver { pos })
         }
     }
 }
 
-impl<T> AsRef<[T]> for ArchivedVec<T> {
+impl<T, O: Offset> AsRef<[T]> for ArchivedVec<T, O> {
     fn as_ref(&self) -> &[T] {
         self.as_slice()
     }
 }
 
-impl<T> Borrow<[T]> for ArchivedVec<T> {
+impl<T, O: Offset> Borrow<[T]> for ArchivedVec<T, O> {
     fn borrow(&self) -> &[T] {
         self.as_slice()
     }
 }
 
-impl<T: fmt::Debug> fmt::Debug for ArchivedVec<T> {
+impl<T: fmt::Debug, O: Offset> fmt::Debug for ArchivedVec<T, O> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.as_slice()).finish()
     }
 }
 
-impl<T> Deref for ArchivedVec<T> {
+impl<T, O: Offset> Deref for ArchivedVec<T, O> {
     type Target = [T];
 
     fn deref(&self) -> &Self::Target {
@@ -198,15 +205,15 @@ impl<T> Deref for ArchivedVec<T> {
     }
 }
 
-impl<T: Eq> Eq for ArchivedVec<T> {}
+impl<T: Eq, O: Offset> Eq for ArchivedVec<T, O> {}
 
-impl<T: hash::Hash> hash::Hash for ArchivedVec<T> {
+impl<T: hash::Hash, O: Offset> hash::Hash for ArchivedVec<T, O> {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         self.as_slice().hash(state)
     }
 }
 
-impl<T, I: SliceIndex<[T]>> Index<I> for ArchivedVec<T> {
+impl<T, O: Offset, I: SliceIndex<[T]>> Index<I> for ArchivedVec<T, O> {
     type Output = <[T] as Index<I>>::Output;
 
     fn index(&self, index: I) -> &Self::Output {
@@ -214,72 +221,93 @@ impl<T, I: SliceIndex<[T]>> Index<I> for ArchivedVec<T> {
     }
 }
 
-impl<T: Ord> Ord for ArchivedVec<T> {
+impl<T: Ord, O: Offset> Ord for ArchivedVec<T, O> {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.as_slice().cmp(other.as_slice())
     }
 }
 
-impl<T: PartialEq<U>, U> PartialEq<ArchivedVec<U>> for ArchivedVec<T> {
-    fn eq(&self, other: &ArchivedVec<U>) -> bool {
+impl<T, U, O, P> PartialEq<ArchivedVec<U, P>> for ArchivedVec<T, O>
+where
+    T: PartialEq<U>,
+    O: Offset,
+    P: Offset,
+{
+    fn eq(&self, other: &ArchivedVec<U, P>) -> bool {
         self.as_slice().eq(other.as_slice())
     }
 }
 
-impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for ArchivedVec<T> {
+impl<T, U, O, const N: usize> PartialEq<[U; N]> for ArchivedVec<T, O>
+where
+    T: PartialEq<U>,
+    O: Offset,
+{
     fn eq(&self, other: &[U; N]) -> bool {
         self.as_slice().eq(&other[..])
     }
 }
 
-impl<T: PartialEq<U>, U, const N: usize> PartialEq<ArchivedVec<T>> for [U; N] {
-    fn eq(&self, other: &ArchivedVec<T>) -> bool {
+impl<T, U, O, const N: usize> PartialEq<ArchivedVec<T, O>> for [U; N]
+where
+    T: PartialEq<U>,
+    O: Offset,
+{
+    fn eq(&self, other: &ArchivedVec<T, O>) -> bool {
         other.eq(self)
     }
 }
 
-impl<T: PartialEq<U>, U> PartialEq<[U]> for ArchivedVec<T> {
+impl<T: PartialEq<U>, U, O: Offset> PartialEq<[U]> for ArchivedVec<T, O> {
     fn eq(&self, other: &[U]) -> bool {
         self.as_slice().eq(other)
     }
 }
 
-impl<T: PartialEq<U>, U> PartialEq<ArchivedVec<U>> for [T] {
-    fn eq(&self, other: &ArchivedVec<U>) -> bool {
+impl<T: PartialEq<U>, U, O: Offset> PartialEq<ArchivedVec<U, O>> for [T] {
+    fn eq(&self, other: &ArchivedVec<U, O>) -> bool {
         self.eq(other.as_slice())
     }
 }
 
-impl<T: PartialOrd> PartialOrd<ArchivedVec<T>> for ArchivedVec<T> {
-    fn partial_cmp(&self, other: &ArchivedVec<T>) -> Option<cmp::Ordering> {
+impl<T, O, P> PartialOrd<ArchivedVec<T, P>> for ArchivedVec<T, O>
+where
+    T: PartialOrd,
+    O: Offset,
+    P: Offset,
+{
+    fn partial_cmp(&self, other: &ArchivedVec<T, P>) -> Option<cmp::Ordering> {
         self.as_slice().partial_cmp(other.as_slice())
     }
 }
 
-impl<T: PartialOrd> PartialOrd<[T]> for ArchivedVec<T> {
+impl<T: PartialOrd, O: Offset> PartialOrd<[T]> for ArchivedVec<T, O> {
     fn partial_cmp(&self, other: &[T]) -> Option<cmp::Ordering> {
         self.as_slice().partial_cmp(other)
     }
 }
 
-impl<T: PartialOrd> PartialOrd<ArchivedVec<T>> for [T] {
-    fn partial_cmp(&self, other: &ArchivedVec<T>) -> Option<cmp::Ordering> {
+impl<T: PartialOrd, O: Offset> PartialOrd<ArchivedVec<T, O>> for [T] {
+    fn partial_cmp(&self, other: &ArchivedVec<T, O>) -> Option<cmp::Ordering> {
         self.partial_cmp(other.as_slice())
     }
 }
 
 /// The resolver for [`ArchivedVec`].
 pub struct VecResolver {
-    pos: FixedUsize,
+    pos: usize,
 }
 
 impl VecResolver {
     /// Creates a new `VecResolver` from a position in the output buffer where
     /// the elements of the archived vector are stored.
     pub fn from_pos(pos: usize) -> Self {
-        Self {
-            pos: pos as FixedUsize,
-        }
+        Self { pos }
+    }
+
+    /// Returns the position of the elements of the archived vector.
+    pub(crate) fn pos(&self) -> usize {
+        self.pos
     }
 }
 
@@ -291,13 +319,15 @@ mod verify {
     };
 
     use crate::{
+        rel_ptr::Offset,
         validation::{ArchiveContext, ArchiveContextExt},
         vec::ArchivedVec,
     };
 
-    unsafe impl<T, C> Verify<C> for ArchivedVec<T>
+    unsafe impl<T, O, C> Verify<C> for ArchivedVec<T, O>
     where
         T: CheckBytes<C>,
+        O: Offset,
         C: Fallible + ArchiveContext + ?Sized,
         C::Error: Source,
     {
@@ -307,9 +337,7 @@ mod verify {
                 self.len.to_native() as usize,
             );
 
-            context.in_subtree(ptr, |context| unsafe {
-                <[T]>::check_bytes(ptr, context)
-            })
+            context.check_aliasable_subtree(ptr)
         }
     }
 }
@@ -11,7 +11,11 @@ use rancor::Fallible;
 
 #[doc(inline)]
 pub use crate::niche::niching::DefaultNiche;
-use crate::{Archive, Deserialize, Place, Portable, Serialize};
+use crate::{
+    hash::{DefaultSeed, SipHasher13},
+    primitive::ArchivedI16,
+    Archive, Deserialize, DeserializeInPlace, Place, Portable, Serialize,
+};
 
 /// A variant of [`Archive`] that works with wrappers.
 ///
@@ -129,6 +133,18 @@ pub trait SerializeWith<F: ?Sized, S: Fallible + ?Sized>:
         field: &F,
         serializer: &mut S,
     ) -> Result<Self::Resolver, S::Error>;
+
+    /// Checks that a resolver returned by `serialize_with` can be resolved
+    /// into an archived value at the given position.
+    ///
+    /// See [`Serialize::check_resolver`] for details.
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), S::Error> {
+        let _ = (resolver, pos);
+        Ok(())
+    }
 }
 
 /// A variant of `Deserialize` for "with" types.
@@ -180,6 +196,13 @@ where
     ) -> Result<Self::Resolver, <S as Fallible>::Error> {
         W::serialize_with(&self.field, serializer)
     }
+
+    fn check_resolver(
+        resolver: &Self::Resolver,
+        pos: usize,
+    ) -> Result<(), <S as Fallible>::Error> {
+        W::check_resolver(resolver, pos)
+    }
 }
 
 impl<T, D, F, W> Deserialize<T, D> for With<F, W>
@@ -196,6 +219,14 @@ where
     }
 }
 
+impl<T, D, F, W> DeserializeInPlace<T, D> for With<F, W>
+where
+    D: Fallible + ?Sized,
+    F: ?Sized,
+    W: DeserializeWith<F, T, D>,
+{
+}
+
 /// A wrapper that applies another wrapper to the values contained in a type.
 /// This can be applied to a vector to map each element, or an option to map any
 /// contained value.
@@ -350,6 +381,71 @@ pub struct AsBox;
 #[derive(Debug)]
 pub struct InlineAsBox;
 
+/// A wrapper that archives a `Box`, `Vec`, `String`, `Rc`, or `Arc` with a
+/// 64-bit relative pointer.
+///
+/// Regular relative pointers use [`ArchivedIsize`] offsets, which can only
+/// reach data within the range of
+/// [`FixedIsize`](crate::primitive::FixedIsize). Fields archived with this
+/// wrapper can point to data anywhere in the archive, at the cost of a larger
+/// pointer. A `String` is archived as an [`ArchivedString<_>`], and short
+/// strings are still stored inline.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{with::WideOffset, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = WideOffset)]
+///     blob: Vec<u8>,
+///     #[rkyv(with = WideOffset)]
+///     name: String,
+/// }
+/// ```
+///
+/// [`ArchivedIsize`]: crate::primitive::ArchivedIsize
+/// [`ArchivedString<_>`]: crate::string::ArchivedString
+#[derive(Debug)]
+pub struct WideOffset;
+
+/// A wrapper that archives a `Box`, `Vec`, `String`, `Rc`, or `Arc` with a
+/// smaller relative pointer.
+///
+/// The offset type `O` may be any [`Offset`](crate::rel_ptr::Offset), and is
+/// usually `ArchivedI16` (the default) or `i8`. A `String` is archived as an
+/// [`ArchivedString<O>`](crate::string::ArchivedString).
+///
+/// The pointed-to data must be close enough to the pointer for the offset to
+/// fit. Serialization fails if it isn't, including when values serialized
+/// between the data and the pointer push the data out of range (see
+/// [`SerializeWith::check_resolver`]). Narrow offsets work best for small
+/// values in small structs stored behind their own pointer.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{with::NarrowOffset, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = NarrowOffset)]
+///     name: String,
+///     #[rkyv(with = NarrowOffset<i8>)]
+///     tags: Vec<u8>,
+/// }
+/// ```
+pub struct NarrowOffset<O = ArchivedI16> {
+    _phantom: PhantomData<O>,
+}
+
+impl<O> fmt::Debug for NarrowOffset<O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NarrowOffset")
+    }
+}
+
 /// A wrapper that attempts to convert a type to and from UTF-8.
 ///
 /// Types like `OsString` and `PathBuf` aren't guaranteed to be encoded as
@@ -444,6 +540,258 @@ pub struct AsOwned;
 #[derive(Debug)]
 pub struct AsVec;
 
+/// A wrapper that archives sets of `u32` as compressed bitmaps.
+///
+/// The archived [`roaring`](crate::collections::roaring) bitmap supports
+/// membership tests, ranks, and set operations without deserializing.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::BTreeSet;
+///
+/// use rkyv::{with::AsBitmap, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = AsBitmap)]
+///     ids: BTreeSet<u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsBitmap;
+
+/// A wrapper that archives a `Vec` of structs as a set of columns.
+///
+/// Each field of the struct is stored in its own contiguous archived vector,
+/// which makes scanning a single field across many rows cache-friendly. The
+/// struct must derive `Archive` with `#[rkyv(columnar)]`. See
+/// [`columnar`](crate::columnar) for more details.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{with::Columnar, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[rkyv(columnar)]
+/// struct Point {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     #[rkyv(with = Columnar)]
+///     points: Vec<Point>,
+/// }
+///
+/// let example = Example {
+///     points: vec![Point { x: 1.0, y: 2.0 }, Point { x: 3.0, y: 4.0 }],
+/// };
+/// let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&example).unwrap();
+/// let archived =
+///     rkyv::access::<ArchivedExample, rkyv::rancor::Error>(&bytes).unwrap();
+///
+/// assert_eq!(archived.points.columns().x[1], 3.0);
+/// assert_eq!(*archived.points.get(1).unwrap().y, 4.0);
+/// ```
+#[derive(Debug)]
+pub struct Columnar;
+
+/// A wrapper that archives a `Vec` as a chain of segments which are each
+/// within the range of a relative pointer.
+///
+/// This allows single vectors much larger than the range of
+/// [`FixedIsize`](crate::primitive::FixedIsize) to be archived without changing
+/// the pointer width of the whole archive. By default, each segment holds as
+/// many elements as fit in half of the relative pointer range. A nonzero
+/// `SEGMENT_LEN` caps the number of elements in each segment instead. See
+/// [`segmented_vec`](crate::collections::segmented_vec) for more details.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{with::Segmented, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = Segmented)]
+///     bytes: Vec<u8>,
+///     #[rkyv(with = Segmented<1024>)]
+///     values: Vec<u64>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Segmented<const SEGMENT_LEN: usize = 0>;
+
+/// A wrapper that archives a value with a checksum of its subtree.
+///
+/// The value and everything it points to are serialized contiguously, and a
+/// CRC-32C checksum of those bytes is stored alongside them. Large archives can
+/// then verify just the subtrees they read. Shared pointers inside the value
+/// which were already serialized elsewhere in the archive are serialized again
+/// inside of it, so that the checksum covers everything the value points to.
+/// See [`ArchivedChecksummed`](crate::checksum::ArchivedChecksummed) for more
+/// details.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{rancor::Error, with::Checksummed, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     #[rkyv(with = Checksummed)]
+///     names: Vec<String>,
+///     #[rkyv(with = Checksummed)]
+///     values: Vec<u64>,
+/// }
+///
+/// let example = Example {
+///     names: vec!["a".to_string(), "b".to_string()],
+///     values: vec![1, 2, 3],
+/// };
+/// let bytes = rkyv::to_bytes::<Error>(&example).unwrap();
+/// let archived = rkyv::access::<ArchivedExample, Error>(&bytes).unwrap();
+///
+/// let values = archived.values.get_verified::<Error>().unwrap();
+/// assert_eq!(values[2], 3);
+/// ```
+#[derive(Debug)]
+pub struct Checksummed;
+
+/// A wrapper that archives a value compressed with a
+/// [`Codec`](crate::compression::Codec).
+///
+/// The value is serialized into its own archive and compressed, and is only
+/// decompressed when it is accessed or deserialized. This suits large fields
+/// which are rarely read, like text bodies and images. Shared pointers inside
+/// the value are not shared with the rest of the archive. Deserializing
+/// requires the `bytecheck` feature, because the decompressed archive must be
+/// validated. See
+/// [`ArchivedCompressed`](crate::compression::ArchivedCompressed)
+/// for more details, and [`Lz4`](crate::compression::Lz4) for an example.
+pub struct Compressed<C> {
+    _phantom: PhantomData<C>,
+}
+
+impl<C> fmt::Debug for Compressed<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Compressed")
+    }
+}
+
+/// A wrapper that shares the serialized contents of equal strings and slices.
+///
+/// Values with the same contents are only written once, and every other copy
+/// points to the first one. The archived type is the same as without the
+/// wrapper. Short strings which are stored inline are never interned.
+///
+/// Deduplication requires a serializer with
+/// [`Share`](crate::ser::sharing::Share), like the one used by
+/// [`to_bytes`](crate::to_bytes). With
+/// [`Unshare`](crate::ser::sharing::Unshare), every copy is written out.
+///
+/// Interned values are validated once no matter how many times they are
+/// referenced. Immutable access with [`access`](crate::access) accepts
+/// archives with shared values, but [`access_mut`](crate::access_mut) rejects
+/// them since it would allow mutating the same bytes through multiple
+/// pointers. See [`allow_aliasing`] for validating them with a custom context.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{rancor::Error, with::Intern, Archive, Archived, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Event {
+///     #[rkyv(with = Intern)]
+///     source: String,
+///     id: u32,
+/// }
+///
+/// let events = (0..10)
+///     .map(|id| Event {
+///         source: "a long and frequently repeated source".to_string(),
+///         id,
+///     })
+///     .collect::<Vec<_>>();
+/// let bytes = rkyv::to_bytes::<Error>(&events).unwrap();
+/// let archived = rkyv::access::<Archived<Vec<Event>>, Error>(&bytes).unwrap();
+///
+/// let first = archived[0].source.as_ptr();
+/// assert!(archived.iter().all(|event| event.source.as_ptr() == first));
+/// ```
+///
+/// [`allow_aliasing`]:
+///     crate::validation::archive::ArchiveValidator::allow_aliasing
+#[derive(Debug)]
+pub struct Intern;
+
+/// A wrapper that archives a value in a separate archive and stores a
+/// reference to it.
+///
+/// The value is serialized into the
+/// [`ExternalArchive`](crate::ext_ref::ExternalArchive) of the serializer, and
+/// the field is archived as an
+/// [`ArchivedExtRef`](crate::ext_ref::ArchivedExtRef). The serializer must
+/// implement [`ExternalWriter`](crate::ext_ref::ExternalWriter), like the one
+/// used by
+/// [`to_bytes_with_external`](crate::ext_ref::to_bytes_with_external).
+/// Deserializing requires the `bytecheck` feature and a deserializer which
+/// implements [`ExtRefContext`](crate::ext_ref::ExtRefContext), like an
+/// [`ExtRefRegistry`](crate::ext_ref::ExtRefRegistry). See
+/// [`ext_ref`](crate::ext_ref) for an example.
+#[derive(Debug)]
+pub struct External;
+
+/// A wrapper that archives hash maps and sets with a seeded [`KeyedHasher`].
+///
+/// The seed is chosen by the [`SeedSource`] `R` each time the value is
+/// serialized, and is stored next to the archived hash table. By default, a new
+/// random seed is chosen every time. Because the seed is not known in advance,
+/// keys can't be chosen to collide in the archived table. Use this wrapper when
+/// archiving maps built from untrusted keys.
+///
+/// The default [`DefaultSeed`] requires the `std` feature to generate random
+/// seeds, and uses a fixed seed when serializing canonically. Without `std`,
+/// supply a seed with [`FixedSeed`] or your own [`SeedSource`].
+///
+/// # Example
+///
+/// ```
+/// use std::collections::{HashMap, HashSet};
+///
+/// use rkyv::{
+///     hash::{FixedSeed, SipHasher13},
+///     with::Keyed,
+///     Archive,
+/// };
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = Keyed)]
+///     values: HashMap<String, u32>,
+///     #[rkyv(with = Keyed<SipHasher13, FixedSeed<0x1234, 0x5678>>)]
+///     names: HashSet<String>,
+/// }
+/// ```
+///
+/// [`KeyedHasher`]: crate::hash::KeyedHasher
+/// [`SeedSource`]: crate::hash::SeedSource
+/// [`DefaultSeed`]: crate::hash::DefaultSeed
+/// [`FixedSeed`]: crate::hash::FixedSeed
+pub struct Keyed<H = SipHasher13, R = DefaultSeed> {
+    _phantom: PhantomData<(H, R)>,
+}
+
+impl<H, R> fmt::Debug for Keyed<H, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Keyed")
+    }
+}
+
 /// A wrapper that niches some type combinations.
 ///
 /// A common type combination is `Option<Box<T>>`. By using a null pointer, the
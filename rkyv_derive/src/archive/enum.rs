@@ -28,6 +28,13 @@ pub fn impl_enum(
         ..
     } = printing;
 
+    if let Some(ref columnar) = attributes.columnar {
+        return Err(Error::new_spanned(
+            columnar,
+            "`columnar` may only be used on structs",
+        ));
+    }
+
     if data.variants.len() > 256 {
         return Err(Error::new_spanned(
             &printing.name,
@@ -8,6 +8,7 @@ use syn::{
 use crate::{
     archive::{archived_doc, printing::Printing, resolver_doc},
     attributes::{Attributes, FieldAttributes},
+    columnar,
 };
 
 pub fn impl_struct(
@@ -124,6 +125,12 @@ pub fn impl_struct(
         }
     }
 
+    if attributes.columnar.is_some() {
+        result.extend(columnar::impl_archive_columns(
+            printing, generics, attributes, fields,
+        )?);
+    }
+
     Ok(result)
 }
 
@@ -35,6 +35,7 @@ pub struct Attributes {
     pub deserialize_bounds: Option<Punctuated<WherePredicate, Token![,]>>,
     pub bytecheck: Option<TokenStream>,
     pub crate_path: Option<Path>,
+    pub columnar: Option<Path>,
 }
 
 impl Attributes {
@@ -138,6 +139,8 @@ impl Attributes {
             self.metas
                 .extend(metas.parse_terminated(Meta::parse, Token![,])?);
             Ok(())
+        } else if meta.path.is_ident("columnar") {
+            try_set_attribute(&mut self.columnar, meta.path, "columnar")
         } else if meta.path.is_ident("remote") {
             try_set_attribute(
                 &mut self.remote,
@@ -184,6 +187,16 @@ impl Attributes {
             }
         }
 
+        if let Some(ref columnar) = result.columnar {
+            if result.as_type.is_some() || result.remote.is_some() {
+                return Err(Error::new_spanned(
+                    columnar,
+                    "`columnar` may not be used with `as = ...` or `remote = \
+                     ...`",
+                ));
+            }
+        }
+
         Ok(result)
     }
 
@@ -325,6 +338,22 @@ impl FieldAttributes {
         }
     }
 
+    pub fn deserialize_in_place_bound(
+        &self,
+        rkyv_path: &Path,
+        field: &Field,
+    ) -> Option<WherePredicate> {
+        if self.omit_bounds.is_some() || self.with.is_some() {
+            return None;
+        }
+
+        let archived = self.archived(rkyv_path, field);
+        let ty = &field.ty;
+        Some(parse_quote! {
+            #archived: #rkyv_path::DeserializeInPlace<#ty, __D>
+        })
+    }
+
     fn archive_item(
         &self,
         rkyv_path: &Path,
@@ -373,6 +402,25 @@ impl FieldAttributes {
         }
     }
 
+    pub fn check_resolver(
+        &self,
+        rkyv_path: &Path,
+        field: &Field,
+    ) -> TokenStream {
+        let ty = &field.ty;
+        if let Some(with) = &self.with {
+            quote! {
+                <
+                    #with as #rkyv_path::with::SerializeWith<#ty, __S>
+                >::check_resolver
+            }
+        } else {
+            quote! {
+                <#ty as #rkyv_path::Serialize<__S>>::check_resolver
+            }
+        }
+    }
+
     pub fn deserialize(&self, rkyv_path: &Path, field: &Field) -> TokenStream {
         let ty = &field.ty;
         let archived = self.archived(rkyv_path, field);
@@ -394,6 +442,34 @@ impl FieldAttributes {
         }
     }
 
+    pub fn deserialize_in_place(
+        &self,
+        rkyv_path: &Path,
+        field: &Field,
+        archived_field: &impl ToTokens,
+        target_field: &impl ToTokens,
+    ) -> TokenStream {
+        let ty = &field.ty;
+        let archived = self.archived(rkyv_path, field);
+
+        if self.with.is_some() {
+            // Wrappers can only deserialize new values
+            let deserialize = self.deserialize(rkyv_path, field);
+            quote! {
+                *#target_field = #deserialize(#archived_field, deserializer)?;
+            }
+        } else {
+            quote! {
+                <#archived as #rkyv_path::DeserializeInPlace<#ty, __D>>
+                    ::deserialize_into(
+                        #archived_field,
+                        #target_field,
+                        deserializer,
+                    )?;
+            }
+        }
+    }
+
     pub fn access_field(
         &self,
         this: &Ident,
@@ -0,0 +1,333 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Error, Field, Fields, Generics, Ident, WhereClause};
+
+use crate::{
+    archive::printing::Printing,
+    attributes::{Attributes, FieldAttributes},
+    util::strip_raw,
+};
+
+fn columns_name(printing: &Printing) -> Ident {
+    format_ident!("{}Columns", printing.archived_name)
+}
+
+fn row_name(printing: &Printing) -> Ident {
+    format_ident!("{}Row", printing.archived_name)
+}
+
+fn columns_resolver_name(printing: &Printing) -> Ident {
+    format_ident!("{}ColumnsResolver", strip_raw(&printing.name))
+}
+
+fn struct_body(
+    fields: &Fields,
+    where_clause: &Option<WhereClause>,
+    body: TokenStream,
+) -> TokenStream {
+    match fields {
+        Fields::Named(_) => quote! { #where_clause { #body } },
+        Fields::Unnamed(_) => quote! { (#body) #where_clause; },
+        Fields::Unit => quote! { #where_clause; },
+    }
+}
+
+pub fn impl_archive_columns(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        vis,
+        name,
+        ..
+    } = printing;
+
+    if fields.is_empty() {
+        return Err(Error::new_spanned(
+            attributes.columnar.as_ref(),
+            "`columnar` may only be used on structs with at least one field",
+        ));
+    }
+
+    let columns_name = columns_name(printing);
+    let row_name = row_name(printing);
+    let resolver_name = columns_resolver_name(printing);
+
+    let mut column_fields = TokenStream::new();
+    let mut row_fields = TokenStream::new();
+    let mut resolver_fields = TokenStream::new();
+    let mut column_types = Vec::new();
+    for field in fields.iter() {
+        let Field {
+            vis,
+            ident,
+            colon_token,
+            ..
+        } = field;
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        let archived = field_attrs.archived(rkyv_path, field);
+        let column = quote! { #rkyv_path::vec::ArchivedVec<#archived> };
+
+        column_fields.extend(quote! { #vis #ident #colon_token #column, });
+        row_fields.extend(quote! { #vis #ident #colon_token &'__a #archived, });
+        resolver_fields.extend(quote! {
+            #ident #colon_token #rkyv_path::vec::VecResolver,
+        });
+        column_types.push(column);
+    }
+
+    let members = fields.members().collect::<Vec<_>>();
+    let where_clause = &generics.where_clause;
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+
+    let mut row_generics = generics.clone();
+    row_generics.params.insert(0, parse_quote! { '__a });
+    let (_, row_ty_generics, _) = row_generics.split_for_impl();
+
+    let columns_body = struct_body(fields, where_clause, column_fields);
+    let row_body = struct_body(fields, &row_generics.where_clause, row_fields);
+    let resolver_body = struct_body(fields, &None, resolver_fields);
+
+    let row_values = members.iter().map(|member| {
+        quote! { #member: &self.#member[index] }
+    });
+
+    let columns_doc =
+        format!("The archived columns of a sequence of [`{}`]", name);
+    let row_doc = format!("A row of the archived columns of [`{}`]", name);
+    let resolver_doc =
+        format!("The resolver for the archived columns of [`{}`]", name);
+
+    #[cfg(not(feature = "bytecheck"))]
+    let columns_metas = TokenStream::new();
+    #[cfg(feature = "bytecheck")]
+    let columns_metas = quote! {
+        #[derive(#rkyv_path::bytecheck::CheckBytes)]
+        #[bytecheck(crate = #rkyv_path::bytecheck)]
+    };
+
+    let mut portable_where = generics.where_clause.clone().unwrap();
+    let mut debug_where = generics.where_clause.clone().unwrap();
+    for column in column_types.iter() {
+        portable_where.predicates.push(parse_quote! {
+            #column: #rkyv_path::Portable
+        });
+        debug_where.predicates.push(parse_quote! {
+            #column: ::core::fmt::Debug
+        });
+    }
+    let debug_name = columns_name.to_string();
+    let debug_fields = members.iter().map(|member| {
+        let name = quote!(#member).to_string();
+        quote! { .field(#name, &self.#member) }
+    });
+
+    Ok(quote! {
+        #[automatically_derived]
+        #[doc = #columns_doc]
+        #columns_metas
+        #[repr(C)]
+        #vis struct #columns_name #generics #columns_body
+
+        #[automatically_derived]
+        #[doc = #row_doc]
+        #vis struct #row_name #row_generics #row_body
+
+        #[automatically_derived]
+        #[doc = #resolver_doc]
+        #vis struct #resolver_name #resolver_body
+
+        // SAFETY: The columns type is `repr(C)` and all of its fields are
+        // `Portable`.
+        unsafe impl #impl_generics #rkyv_path::Portable
+            for #columns_name #ty_generics
+        #portable_where
+        {}
+
+        impl #impl_generics ::core::fmt::Debug for #columns_name #ty_generics
+        #debug_where
+        {
+            fn fmt(
+                &self,
+                f: &mut ::core::fmt::Formatter<'_>,
+            ) -> ::core::fmt::Result {
+                f.debug_struct(#debug_name)
+                    #(#debug_fields)*
+                    .finish()
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #rkyv_path::columnar::Columns
+            for #columns_name #ty_generics
+        #where_clause
+        {
+            type Row<'__a> = #row_name #row_ty_generics where Self: '__a;
+
+            fn has_len(&self, len: usize) -> bool {
+                true #(&& self.#members.len() == len)*
+            }
+
+            fn row(&self, index: usize) -> Self::Row<'_> {
+                #row_name { #(#row_values,)* }
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #rkyv_path::columnar::ArchiveColumns
+            for #name #ty_generics
+        #where_clause
+        {
+            type Columns = #columns_name #ty_generics;
+            type ColumnsResolver = #resolver_name;
+
+            fn resolve_columns(
+                len: usize,
+                resolver: Self::ColumnsResolver,
+                out: #rkyv_path::Place<Self::Columns>,
+            ) {
+                #(
+                    let field_ptr = unsafe {
+                        ::core::ptr::addr_of_mut!((*out.ptr()).#members)
+                    };
+                    let field_out = unsafe {
+                        #rkyv_path::Place::from_field_unchecked(out, field_ptr)
+                    };
+                    #rkyv_path::vec::ArchivedVec::resolve_from_len(
+                        len,
+                        resolver.#members,
+                        field_out,
+                    );
+                )*
+            }
+        }
+    })
+}
+
+pub fn impl_serialize_columns(
+    printing: &Printing,
+    impl_generics: &Generics,
+    ty_generics: &Generics,
+    mut where_clause: WhereClause,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path, name, ..
+    } = printing;
+    let resolver_name = columns_resolver_name(printing);
+
+    where_clause.predicates.push(parse_quote! {
+        __S: #rkyv_path::ser::Writer + #rkyv_path::ser::Allocator
+    });
+
+    let mut resolver_values = Vec::new();
+    for (field, member) in fields.iter().zip(fields.members()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        where_clause
+            .predicates
+            .extend(field_attrs.serialize_bound(rkyv_path, field));
+
+        let ty = &field.ty;
+        let archived = field_attrs.archived(rkyv_path, field);
+        let (element, access) = if let Some(with) = &field_attrs.with {
+            (
+                quote! { #rkyv_path::with::With<#ty, #with> },
+                quote! {
+                    #rkyv_path::with::With::<#ty, #with>::cast(&row.#member)
+                },
+            )
+        } else {
+            (quote! { #ty }, quote! { &row.#member })
+        };
+
+        resolver_values.push(quote! {
+            #member: #rkyv_path::vec::ArchivedVec::<#archived>
+                ::serialize_from_iter::<#element, _, _>(
+                    rows.iter().map(|row| #access),
+                    serializer,
+                )?
+        });
+    }
+
+    let (impl_generics, ..) = impl_generics.split_for_impl();
+    let (_, ty_generics, _) = ty_generics.split_for_impl();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics #rkyv_path::columnar::SerializeColumns<__S>
+            for #name #ty_generics
+        #where_clause
+        {
+            fn serialize_columns(
+                rows: &[Self],
+                serializer: &mut __S,
+            ) -> ::core::result::Result<
+                <Self as #rkyv_path::columnar::ArchiveColumns>::ColumnsResolver,
+                <__S as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                ::core::result::Result::Ok(#resolver_name {
+                    #(#resolver_values,)*
+                })
+            }
+        }
+    })
+}
+
+pub fn impl_deserialize_columns(
+    printing: &Printing,
+    impl_generics: &Generics,
+    ty_generics: &Generics,
+    mut where_clause: WhereClause,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path, name, ..
+    } = printing;
+    let columns_name = columns_name(printing);
+
+    let mut field_values = Vec::new();
+    for (field, member) in fields.iter().zip(fields.members()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        where_clause
+            .predicates
+            .extend(field_attrs.archive_bound(rkyv_path, field));
+        where_clause
+            .predicates
+            .extend(field_attrs.deserialize_bound(rkyv_path, field));
+
+        let deserialize = field_attrs.deserialize(rkyv_path, field);
+        field_values.push(quote! {
+            #member: #deserialize(&self.#member[index], deserializer)?
+        });
+    }
+
+    let (impl_generics, ..) = impl_generics.split_for_impl();
+    let (_, ty_generics, _) = ty_generics.split_for_impl();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics
+            #rkyv_path::columnar::DeserializeColumns<#name #ty_generics, __D>
+            for #columns_name #ty_generics
+        #where_clause
+        {
+            fn deserialize_row(
+                &self,
+                index: usize,
+                deserializer: &mut __D,
+            ) -> ::core::result::Result<
+                #name #ty_generics,
+                <__D as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                ::core::result::Result::Ok(#name {
+                    #(#field_values,)*
+                })
+            }
+        }
+    })
+}
@@ -8,6 +8,7 @@ use syn::{
 use crate::{
     archive::printing::Printing,
     attributes::{Attributes, FieldAttributes},
+    columnar,
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -104,7 +105,7 @@ fn derive_deserialize_impl(
             name,
         )?;
 
-        Ok(quote! {
+        let mut result = quote! {
             #[automatically_derived]
             impl #impl_generics #rkyv_path::Deserialize<#name #ty_generics, __D>
                 for #rkyv_path::Archived<#name #ty_generics>
@@ -121,7 +122,22 @@ fn derive_deserialize_impl(
                     #body
                 }
             }
-        })
+        };
+
+        if let (Some(_), Data::Struct(data)) =
+            (&attributes.columnar, &input.data)
+        {
+            result.extend(columnar::impl_deserialize_columns(
+                &Printing::new(&input, attributes)?,
+                &impl_input_generics,
+                &input.generics,
+                where_clause.clone(),
+                attributes,
+                &data.fields,
+            )?);
+        }
+
+        Ok(result)
     }
 }
 
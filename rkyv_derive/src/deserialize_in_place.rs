@@ -0,0 +1,232 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, punctuated::Punctuated, spanned::Spanned, Data, DeriveInput,
+    Error, Field, Fields, Generics, Ident, Index, Path, WhereClause,
+};
+
+use crate::attributes::{Attributes, FieldAttributes};
+
+pub fn derive(mut input: DeriveInput) -> Result<TokenStream, Error> {
+    let attributes = Attributes::parse(&input)?;
+
+    if let Some(ref remote) = attributes.remote {
+        return Err(Error::new_spanned(
+            remote,
+            "DeserializeInPlace cannot be derived for remote types",
+        ));
+    }
+
+    let rkyv_path = attributes.crate_path();
+
+    let where_clause = input.generics.make_where_clause();
+    if let Some(ref bounds) = attributes.archive_bounds {
+        for bound in bounds {
+            where_clause.predicates.push(bound.clone());
+        }
+    }
+    if let Some(ref bounds) = attributes.deserialize_bounds {
+        for bound in bounds {
+            where_clause.predicates.push(bound.clone());
+        }
+    }
+
+    let mut impl_input_params = Punctuated::default();
+    impl_input_params
+        .push(parse_quote! { __D: #rkyv_path::rancor::Fallible + ?Sized });
+    for param in input.generics.params.iter() {
+        impl_input_params.push(param.clone());
+    }
+    let impl_input_generics = Generics {
+        lt_token: Some(Default::default()),
+        params: impl_input_params,
+        gt_token: Some(Default::default()),
+        where_clause: input.generics.where_clause.clone(),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ..) = impl_input_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut deserialize_where = where_clause.unwrap().clone();
+
+    let body = generate_deserialize_in_place_body(
+        &input,
+        &attributes,
+        &mut deserialize_where,
+        &rkyv_path,
+    )?;
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics
+            #rkyv_path::DeserializeInPlace<#name #ty_generics, __D>
+            for #rkyv_path::Archived<#name #ty_generics>
+        #deserialize_where
+        {
+            fn deserialize_into(
+                &self,
+                target: &mut #name #ty_generics,
+                deserializer: &mut __D,
+            ) -> ::core::result::Result<
+                (),
+                <__D as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                #body
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+fn push_bounds(
+    deserialize_where: &mut WhereClause,
+    field_attrs: &FieldAttributes,
+    rkyv_path: &Path,
+    field: &Field,
+) {
+    deserialize_where
+        .predicates
+        .extend(field_attrs.archive_bound(rkyv_path, field));
+    deserialize_where
+        .predicates
+        .extend(field_attrs.deserialize_bound(rkyv_path, field));
+    deserialize_where
+        .predicates
+        .extend(field_attrs.deserialize_in_place_bound(rkyv_path, field));
+}
+
+fn generate_deserialize_in_place_body(
+    input: &DeriveInput,
+    attributes: &Attributes,
+    deserialize_where: &mut WhereClause,
+    rkyv_path: &Path,
+) -> Result<TokenStream, Error> {
+    let name = &input.ident;
+    let body = match input.data {
+        Data::Struct(ref data) => {
+            let fields = data
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let field_attrs =
+                        FieldAttributes::parse(attributes, field)?;
+                    push_bounds(
+                        deserialize_where,
+                        &field_attrs,
+                        rkyv_path,
+                        field,
+                    );
+
+                    let member = match field.ident {
+                        Some(ref ident) => quote! { #ident },
+                        None => {
+                            let index = Index::from(i);
+                            quote! { #index }
+                        }
+                    };
+                    Ok(field_attrs.deserialize_in_place(
+                        rkyv_path,
+                        field,
+                        &quote! { &self.#member },
+                        &quote! { &mut target.#member },
+                    ))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            quote! { #(#fields)* }
+        }
+        Data::Enum(ref data) => {
+            let variants = data
+                .variants
+                .iter()
+                .map(|v| {
+                    let variant = &v.ident;
+                    let bindings = v
+                        .fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, field)| match field.ident {
+                            Some(ref ident) => (
+                                ident.clone(),
+                                format_ident!("__target_{}", ident),
+                            ),
+                            None => (
+                                Ident::new(&format!("_{}", i), field.span()),
+                                Ident::new(
+                                    &format!("__target_{}", i),
+                                    field.span(),
+                                ),
+                            ),
+                        })
+                        .collect::<Vec<_>>();
+                    let fields = v
+                        .fields
+                        .iter()
+                        .zip(bindings.iter())
+                        .map(|(field, (archived, target))| {
+                            let field_attrs =
+                                FieldAttributes::parse(attributes, field)?;
+                            push_bounds(
+                                deserialize_where,
+                                &field_attrs,
+                                rkyv_path,
+                                field,
+                            );
+                            Ok(field_attrs.deserialize_in_place(
+                                rkyv_path, field, archived, target,
+                            ))
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?;
+
+                    let archived = bindings.iter().map(|(a, _)| a);
+                    let target = bindings.iter().map(|(_, t)| t);
+                    let (archived_pat, target_pat) = match v.fields {
+                        Fields::Named(ref named) => {
+                            let names = named.named.iter().map(|f| &f.ident);
+                            (
+                                quote! { Self::#variant { #(#archived,)* .. } },
+                                quote! {
+                                    #name::#variant { #(#names: #target,)* }
+                                },
+                            )
+                        }
+                        Fields::Unnamed(_) => (
+                            quote! { Self::#variant(#(#archived,)* ..) },
+                            quote! { #name::#variant(#(#target,)*) },
+                        ),
+                        Fields::Unit => (
+                            quote! { Self::#variant },
+                            quote! { #name::#variant },
+                        ),
+                    };
+
+                    Ok(quote! {
+                        (#archived_pat, #target_pat) => { #(#fields)* }
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            // Deserialize a new value if the variants don't match
+            quote! {
+                match (self, target) {
+                    #(#variants,)*
+                    #[allow(unreachable_patterns)]
+                    (this, target) => {
+                        *target = <
+                            Self as #rkyv_path::Deserialize<_, __D>
+                        >::deserialize(this, deserializer)?;
+                    }
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                input,
+                "DeserializeInPlace cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(body)
+}
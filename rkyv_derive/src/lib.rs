@@ -8,7 +8,9 @@
 
 mod archive;
 mod attributes;
+mod columnar;
 mod deserialize;
+mod deserialize_in_place;
 mod portable;
 mod repr;
 mod serde;
@@ -66,6 +68,9 @@ pub fn derive_portable(
 ///   default, resolver types are named `the name of the type` + "Resolver".
 /// - `remote = ..`: Generate a remote derive for the annotated type instead of
 ///   a regular derive.
+/// - `columnar`: Also generates a columns type for archiving sequences of the
+///   struct with the `Columnar` wrapper. Columns types are named `the archived
+///   type` + "Columns".
 ///
 /// ## Fields only
 ///
@@ -137,3 +142,25 @@ pub fn derive_deserialize(
         Err(e) => e.to_compile_error().into(),
     }
 }
+
+/// Derives `DeserializeInPlace` for the labeled type.
+///
+/// The labeled type must also derive `Deserialize`. Fields are deserialized in
+/// place, except for fields with wrappers which are replaced with newly
+/// deserialized values. Enums are deserialized in place when the archived
+/// variant matches the variant of the target, and are replaced otherwise.
+///
+/// This macro also supports the `#[rkyv]` attribute. See [`Archive`] for more
+/// information. Remote derives are not supported.
+#[proc_macro_derive(DeserializeInPlace, attributes(rkyv))]
+pub fn derive_deserialize_in_place(
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let mut derive_input = parse_macro_input!(input as DeriveInput);
+    serde::receiver::replace_receiver(&mut derive_input);
+
+    match deserialize_in_place::derive(derive_input) {
+        Ok(result) => result.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
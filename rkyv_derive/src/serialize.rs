@@ -1,13 +1,15 @@
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
     parse_quote, punctuated::Punctuated, spanned::Spanned, Data, DeriveInput,
-    Error, Fields, Generics, Ident, Index, Path, WhereClause,
+    Error, Fields, Generics, Ident, Index, Member, Path, WhereClause,
 };
 
 use crate::{
+    archive::printing::Printing,
     attributes::{Attributes, FieldAttributes, VariantAttributes},
-    util::{strip_generics_from_path, strip_raw},
+    columnar,
+    util::{iter_fields, strip_generics_from_path, strip_raw},
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -21,6 +23,19 @@ fn derive_serialize_impl(
 ) -> Result<TokenStream, Error> {
     let rkyv_path = attributes.crate_path();
 
+    // The layouts of archived enum variants are only bounded by `Archive`
+    let mut layout_generics = input.generics.clone();
+    let layout_where = layout_generics.make_where_clause();
+    if let Some(ref bounds) = attributes.archive_bounds {
+        layout_where.predicates.extend(bounds.iter().cloned());
+    }
+    for field in iter_fields(&input.data) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        layout_where
+            .predicates
+            .extend(field_attrs.archive_bound(&rkyv_path, field));
+    }
+
     let where_clause = input.generics.make_where_clause();
     if let Some(ref bounds) = attributes.archive_bounds {
         for bound in bounds {
@@ -64,27 +79,44 @@ fn derive_serialize_impl(
             attributes,
             &mut serialize_where,
             &rkyv_path,
-            resolver,
+            resolver.clone(),
             strip_generics_from_path(remote.clone()),
         )?;
+        let (layouts, check_resolver) = generate_check_resolver(
+            &input,
+            attributes,
+            &layout_generics,
+            &rkyv_path,
+            &resolver,
+            quote! { <Self as #rkyv_path::with::ArchiveWith<#remote>> },
+        )?;
 
         Ok(quote! {
-            #[automatically_derived]
-            impl #impl_generics #rkyv_path::with::SerializeWith<#remote, __S>
-                for #name #ty_generics
-            #serialize_where
-            {
-                fn serialize_with(
-                    field: &#remote,
-                    serializer: &mut __S,
-                ) -> ::core::result::Result<
-                    <Self as #rkyv_path::with::ArchiveWith<#remote>>::Resolver,
-                    <__S as #rkyv_path::rancor::Fallible>::Error,
-                > {
-                    let __this = field;
-                    #body
+            const _: () = {
+                #layouts
+
+                #[automatically_derived]
+                impl #impl_generics
+                    #rkyv_path::with::SerializeWith<#remote, __S>
+                    for #name #ty_generics
+                #serialize_where
+                {
+                    fn serialize_with(
+                        field: &#remote,
+                        serializer: &mut __S,
+                    ) -> ::core::result::Result<
+                        <
+                            Self as #rkyv_path::with::ArchiveWith<#remote>
+                        >::Resolver,
+                        <__S as #rkyv_path::rancor::Fallible>::Error,
+                    > {
+                        let __this = field;
+                        #body
+                    }
+
+                    #check_resolver
                 }
-            }
+            };
         })
     } else {
         let body = generate_serialize_body(
@@ -92,28 +124,57 @@ fn derive_serialize_impl(
             attributes,
             &mut serialize_where,
             &rkyv_path,
-            resolver,
+            resolver.clone(),
             parse_quote!(#name),
         )?;
+        let (layouts, check_resolver) = generate_check_resolver(
+            &input,
+            attributes,
+            &layout_generics,
+            &rkyv_path,
+            &resolver,
+            quote! { <Self as #rkyv_path::Archive> },
+        )?;
 
-        Ok(quote! {
-            #[automatically_derived]
-            impl #impl_generics #rkyv_path::Serialize<__S>
-                for #name #ty_generics
-            #serialize_where
-            {
-                fn serialize(
-                    &self,
-                    serializer: &mut __S,
-                ) -> ::core::result::Result<
-                    <Self as #rkyv_path::Archive>::Resolver,
-                    <__S as #rkyv_path::rancor::Fallible>::Error,
-                > {
-                    let __this = self;
-                    #body
+        let mut result = quote! {
+            const _: () = {
+                #layouts
+
+                #[automatically_derived]
+                impl #impl_generics #rkyv_path::Serialize<__S>
+                    for #name #ty_generics
+                #serialize_where
+                {
+                    fn serialize(
+                        &self,
+                        serializer: &mut __S,
+                    ) -> ::core::result::Result<
+                        <Self as #rkyv_path::Archive>::Resolver,
+                        <__S as #rkyv_path::rancor::Fallible>::Error,
+                    > {
+                        let __this = self;
+                        #body
+                    }
+
+                    #check_resolver
                 }
-            }
-        })
+            };
+        };
+
+        if let (Some(_), Data::Struct(data)) =
+            (&attributes.columnar, &input.data)
+        {
+            result.extend(columnar::impl_serialize_columns(
+                &Printing::new(&input, attributes)?,
+                &impl_input_generics,
+                &input.generics,
+                where_clause.clone(),
+                attributes,
+                &data.fields,
+            )?);
+        }
+
+        Ok(result)
     }
 }
 
@@ -295,3 +356,157 @@ fn generate_serialize_body(
 
     Ok(quote! { ::core::result::Result::Ok(#body) })
 }
+
+// Returns the items and the `check_resolver` method which check the resolvers
+// of each field at the position of its archived field. Enum variants are laid
+// out like the `repr(C)` structs generated for them by `Archive`, so those are
+// generated again to find the offsets of their fields.
+fn generate_check_resolver(
+    input: &DeriveInput,
+    attributes: &Attributes,
+    layout_generics: &Generics,
+    rkyv_path: &Path,
+    resolver: &Ident,
+    archive: TokenStream,
+) -> Result<(TokenStream, TokenStream), Error> {
+    if iter_fields(&input.data).next().is_none() {
+        return Ok((TokenStream::new(), TokenStream::new()));
+    }
+
+    let mut layouts = TokenStream::new();
+    let body = match input.data {
+        Data::Struct(ref data) => {
+            let mut checks = Vec::new();
+            for (field, member) in data.fields.iter().zip(data.fields.members())
+            {
+                let field_attrs = FieldAttributes::parse(attributes, field)?;
+                let check_resolver =
+                    field_attrs.check_resolver(rkyv_path, field);
+                checks.push(quote! {
+                    #check_resolver(
+                        &resolver.#member,
+                        pos + ::core::mem::offset_of!(
+                            #archive::Archived,
+                            #member
+                        ),
+                    )?;
+                });
+            }
+
+            quote! {
+                #(#checks)*
+                ::core::result::Result::Ok(())
+            }
+        }
+        Data::Enum(ref data) => {
+            let name = &input.ident;
+            let (_, ty_generics, where_clause) =
+                layout_generics.split_for_impl();
+
+            let mut arms = Vec::new();
+            for variant in data.variants.iter() {
+                let variant_name = &variant.ident;
+                let layout_name =
+                    format_ident!("ArchivedVariant{}", strip_raw(variant_name));
+
+                let mut layout_fields = Vec::new();
+                let mut bindings = Vec::new();
+                let mut checks = Vec::new();
+                for (i, field) in variant.fields.iter().enumerate() {
+                    let field_attrs =
+                        FieldAttributes::parse(attributes, field)?;
+                    let archived = field_attrs.archived(rkyv_path, field);
+                    let binding = Ident::new(&format!("_{}", i), field.span());
+                    let member = match field.ident {
+                        Some(ref ident) => Member::Named(ident.clone()),
+                        None => Member::Unnamed(Index::from(i + 1)),
+                    };
+                    let check_resolver =
+                        field_attrs.check_resolver(rkyv_path, field);
+
+                    layout_fields.push(match field.ident {
+                        Some(ref ident) => quote! { #ident: #archived },
+                        None => quote! { #archived },
+                    });
+                    checks.push(quote! {
+                        #check_resolver(
+                            #binding,
+                            pos + ::core::mem::offset_of!(
+                                #layout_name #ty_generics,
+                                #member
+                            ),
+                        )?;
+                    });
+                    bindings.push(binding);
+                }
+
+                match variant.fields {
+                    Fields::Named(ref fields) => {
+                        let members = fields.named.iter().map(|f| &f.ident);
+                        layouts.extend(quote! {
+                            #[repr(C)]
+                            struct #layout_name #layout_generics
+                            #where_clause
+                            {
+                                __tag: u8,
+                                #(#layout_fields,)*
+                                __phantom: ::core::marker::PhantomData<
+                                    #name #ty_generics
+                                >,
+                            }
+                        });
+                        arms.push(quote! {
+                            #resolver::#variant_name {
+                                #(#members: #bindings,)*
+                            } => {
+                                #(#checks)*
+                            }
+                        });
+                    }
+                    Fields::Unnamed(_) => {
+                        layouts.extend(quote! {
+                            #[repr(C)]
+                            struct #layout_name #layout_generics (
+                                u8,
+                                #(#layout_fields,)*
+                                ::core::marker::PhantomData<
+                                    #name #ty_generics
+                                >,
+                            ) #where_clause;
+                        });
+                        arms.push(quote! {
+                            #resolver::#variant_name(#(#bindings,)*) => {
+                                #(#checks)*
+                            }
+                        });
+                    }
+                    Fields::Unit => arms.push(quote! {
+                        #resolver::#variant_name => (),
+                    }),
+                }
+            }
+
+            quote! {
+                match resolver {
+                    #(#arms)*
+                }
+                ::core::result::Result::Ok(())
+            }
+        }
+        Data::Union(_) => return Ok((TokenStream::new(), TokenStream::new())),
+    };
+
+    let check_resolver = quote! {
+        fn check_resolver(
+            resolver: &#archive::Resolver,
+            pos: usize,
+        ) -> ::core::result::Result<
+            (),
+            <__S as #rkyv_path::rancor::Fallible>::Error,
+        > {
+            #body
+        }
+    };
+
+    Ok((layouts, check_resolver))
+}